@@ -0,0 +1,89 @@
+// race-protocol/src/lib.rs
+//
+// Messaggi JSON scambiati via WebSocket tra il client web e il race server
+
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_PORT: u16 = 7878;
+pub const WS_PATH: &str = "/ws";
+
+// Frasi di ogni gara, tratte dal dataset della stanza con il seed condiviso
+pub const RACE_PHRASES: usize = 5;
+pub const COUNTDOWN_SECONDS: u32 = 3;
+pub const MAX_PLAYERS: usize = 8;
+
+pub type PlayerId = u32;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlayerInfo {
+    pub id: PlayerId,
+    pub name: String,
+    pub ready: bool,
+    // Caratteri percorsi nella gara in corso
+    pub chars: usize,
+    pub wpm: f64,
+    pub accuracy: Option<f64>,
+    // Posizione d'arrivo, una volta terminata la gara
+    pub place: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    // Entra in una stanza, creandola se non esiste; la difficoltà vale solo per chi la crea
+    Join {
+        room: String,
+        name: String,
+        difficulty: String,
+    },
+    SetReady {
+        ready: bool,
+    },
+    Progress {
+        chars: usize,
+        wpm: f64,
+    },
+    Finish {
+        wpm: f64,
+        accuracy: f64,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Welcome {
+        player_id: PlayerId,
+    },
+    // Stato completo della stanza: inviato a ogni ingresso, uscita o cambio di "pronto"
+    Lobby {
+        room: String,
+        difficulty: String,
+        racing: bool,
+        players: Vec<PlayerInfo>,
+    },
+    // Tutti pronti: i client mescolano il dataset con `seed` e partono dopo il countdown
+    RaceStart {
+        seed: u64,
+        difficulty: String,
+        phrases: usize,
+        countdown: u32,
+    },
+    Progress {
+        player_id: PlayerId,
+        chars: usize,
+        wpm: f64,
+    },
+    Finished {
+        player_id: PlayerId,
+        place: u32,
+        wpm: f64,
+        accuracy: f64,
+    },
+    RaceOver {
+        standings: Vec<PlayerInfo>,
+    },
+    Error {
+        message: String,
+    },
+}
@@ -0,0 +1,94 @@
+// race-server/src/lib.rs
+//
+// Server WebSocket per le gare multiplayer: gira in locale, per giocare in LAN
+
+mod rooms;
+
+pub use rooms::Rooms;
+
+use axum::Router;
+use axum::extract::State;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::response::IntoResponse;
+use axum::routing::get;
+use futures_util::{SinkExt, StreamExt};
+use race_protocol::{ClientMessage, PlayerId, ServerMessage, WS_PATH};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+pub fn app(rooms: Rooms) -> Router {
+    Router::new()
+        .route(WS_PATH, get(ws_handler))
+        .with_state(rooms)
+}
+
+// Avvia il server su un listener già aperto (anche su porta 0, es. nei test)
+pub async fn serve(listener: TcpListener) -> std::io::Result<()> {
+    axum::serve(listener, app(Rooms::default())).await
+}
+
+async fn ws_handler(ws: WebSocketUpgrade, State(rooms): State<Rooms>) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_socket(socket, rooms))
+}
+
+async fn handle_socket(socket: WebSocket, rooms: Rooms) {
+    let (mut sink, mut stream) = socket.split();
+    let (outbox, mut inbox) = mpsc::unbounded_channel::<ServerMessage>();
+
+    // I messaggi per questo giocatore passano da una coda, scritta da qualsiasi stanza
+    let writer = tokio::spawn(async move {
+        while let Some(message) = inbox.recv().await {
+            let Ok(text) = serde_json::to_string(&message) else {
+                continue;
+            };
+            if sink.send(Message::Text(text.into())).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut joined: Option<(String, PlayerId)> = None;
+    while let Some(Ok(message)) = stream.next().await {
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let message = match serde_json::from_str::<ClientMessage>(text.as_str()) {
+            Ok(message) => message,
+            Err(e) => {
+                let _ = outbox.send(ServerMessage::Error {
+                    message: format!("Messaggio non valido: {}", e),
+                });
+                continue;
+            }
+        };
+
+        match (&joined, message) {
+            (
+                None,
+                ClientMessage::Join {
+                    room,
+                    name,
+                    difficulty,
+                },
+            ) => match rooms.join(&room, &name, &difficulty, outbox.clone()) {
+                Ok(id) => joined = Some((room.trim().to_string(), id)),
+                Err(message) => {
+                    let _ = outbox.send(ServerMessage::Error { message });
+                }
+            },
+            (Some((room, id)), message) => rooms.handle(room, *id, message),
+            (None, _) => {
+                let _ = outbox.send(ServerMessage::Error {
+                    message: "Entra prima in una stanza".to_string(),
+                });
+            }
+        }
+    }
+
+    if let Some((room, id)) = joined {
+        rooms.leave(&room, id);
+    }
+    writer.abort();
+}
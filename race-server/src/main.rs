@@ -0,0 +1,21 @@
+// race-server/src/main.rs
+//
+// Uso: race-server [indirizzo]  (default 0.0.0.0:7878, raggiungibile dalla LAN)
+
+use race_protocol::{DEFAULT_PORT, WS_PATH};
+use tokio::net::TcpListener;
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| format!("0.0.0.0:{}", DEFAULT_PORT));
+
+    let listener = TcpListener::bind(&address).await?;
+    println!(
+        "🏁 Race server in ascolto su ws://{}{}",
+        listener.local_addr()?,
+        WS_PATH
+    );
+    race_server::serve(listener).await
+}
@@ -0,0 +1,356 @@
+// race-server/src/rooms.rs
+//
+// Stanze di gara: giocatori, stato "pronto", avvio con seed condiviso e classifica d'arrivo
+
+use race_protocol::{
+    COUNTDOWN_SECONDS, ClientMessage, MAX_PLAYERS, PlayerId, PlayerInfo, RACE_PHRASES,
+    ServerMessage,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::UnboundedSender;
+
+// Coda dei messaggi in uscita verso un giocatore
+pub type Outbox = UnboundedSender<ServerMessage>;
+
+struct Player {
+    info: PlayerInfo,
+    outbox: Outbox,
+}
+
+struct Room {
+    difficulty: String,
+    racing: bool,
+    players: BTreeMap<PlayerId, Player>,
+}
+
+impl Room {
+    fn broadcast(&self, message: &ServerMessage) {
+        for player in self.players.values() {
+            // Un client già disconnesso verrà rimosso alla chiusura del suo socket
+            let _ = player.outbox.send(message.clone());
+        }
+    }
+
+    fn standings(&self) -> Vec<PlayerInfo> {
+        let mut standings: Vec<PlayerInfo> =
+            self.players.values().map(|p| p.info.clone()).collect();
+        standings.sort_by_key(|p| (p.place.is_none(), p.place, std::cmp::Reverse(p.chars)));
+        standings
+    }
+
+    fn broadcast_lobby(&self, name: &str) {
+        self.broadcast(&ServerMessage::Lobby {
+            room: name.to_string(),
+            difficulty: self.difficulty.clone(),
+            racing: self.racing,
+            players: self.players.values().map(|p| p.info.clone()).collect(),
+        });
+    }
+
+    // Parte quando tutti i presenti sono pronti
+    fn start_if_ready(&mut self) {
+        if self.racing || !self.players.values().all(|p| p.info.ready) {
+            return;
+        }
+        self.racing = true;
+        for player in self.players.values_mut() {
+            player.info.chars = 0;
+            player.info.wpm = 0.0;
+            player.info.accuracy = None;
+            player.info.place = None;
+        }
+        self.broadcast(&ServerMessage::RaceStart {
+            seed: rand::random(),
+            difficulty: self.difficulty.clone(),
+            phrases: RACE_PHRASES,
+            countdown: COUNTDOWN_SECONDS,
+        });
+    }
+
+    // A gara conclusa da tutti si torna in lobby, con i "pronto" azzerati
+    fn finish_if_done(&mut self, name: &str) {
+        if !self.racing || self.players.values().any(|p| p.info.place.is_none()) {
+            return;
+        }
+        self.broadcast(&ServerMessage::RaceOver {
+            standings: self.standings(),
+        });
+        self.racing = false;
+        for player in self.players.values_mut() {
+            player.info.ready = false;
+        }
+        self.broadcast_lobby(name);
+    }
+}
+
+#[derive(Default)]
+struct RoomsState {
+    rooms: HashMap<String, Room>,
+    next_player: PlayerId,
+}
+
+#[derive(Clone, Default)]
+pub struct Rooms {
+    state: Arc<Mutex<RoomsState>>,
+}
+
+impl Rooms {
+    pub fn join(
+        &self,
+        room: &str,
+        name: &str,
+        difficulty: &str,
+        outbox: Outbox,
+    ) -> Result<PlayerId, String> {
+        let room = room.trim();
+        let name = name.trim();
+        if room.is_empty() || name.is_empty() {
+            return Err("Nome e stanza sono obbligatori".to_string());
+        }
+
+        let mut state = self.state.lock().unwrap();
+        state.next_player += 1;
+        let id = state.next_player;
+
+        let entry = state.rooms.entry(room.to_string()).or_insert_with(|| Room {
+            difficulty: difficulty.to_string(),
+            racing: false,
+            players: BTreeMap::new(),
+        });
+        if entry.racing {
+            return Err("Gara già in corso in questa stanza".to_string());
+        }
+        if entry.players.len() >= MAX_PLAYERS {
+            return Err(format!("Stanza piena ({} giocatori)", MAX_PLAYERS));
+        }
+
+        let _ = outbox.send(ServerMessage::Welcome { player_id: id });
+        entry.players.insert(
+            id,
+            Player {
+                info: PlayerInfo {
+                    id,
+                    name: name.to_string(),
+                    ready: false,
+                    chars: 0,
+                    wpm: 0.0,
+                    accuracy: None,
+                    place: None,
+                },
+                outbox,
+            },
+        );
+        entry.broadcast_lobby(room);
+        Ok(id)
+    }
+
+    pub fn handle(&self, room: &str, player: PlayerId, message: ClientMessage) {
+        let mut state = self.state.lock().unwrap();
+        let Some(entry) = state.rooms.get_mut(room) else {
+            return;
+        };
+
+        match message {
+            ClientMessage::Join { .. } => {}
+            ClientMessage::SetReady { ready } => {
+                if entry.racing {
+                    return;
+                }
+                if let Some(p) = entry.players.get_mut(&player) {
+                    p.info.ready = ready;
+                }
+                entry.broadcast_lobby(room);
+                entry.start_if_ready();
+            }
+            ClientMessage::Progress { chars, wpm } => {
+                let Some(p) = entry.players.get_mut(&player) else {
+                    return;
+                };
+                if !entry.racing || p.info.place.is_some() {
+                    return;
+                }
+                p.info.chars = chars;
+                p.info.wpm = wpm;
+                entry.broadcast(&ServerMessage::Progress {
+                    player_id: player,
+                    chars,
+                    wpm,
+                });
+            }
+            ClientMessage::Finish { wpm, accuracy } => {
+                let place = entry
+                    .players
+                    .values()
+                    .filter(|p| p.info.place.is_some())
+                    .count() as u32
+                    + 1;
+                let Some(p) = entry.players.get_mut(&player) else {
+                    return;
+                };
+                if !entry.racing || p.info.place.is_some() {
+                    return;
+                }
+                p.info.wpm = wpm;
+                p.info.accuracy = Some(accuracy);
+                p.info.place = Some(place);
+                entry.broadcast(&ServerMessage::Finished {
+                    player_id: player,
+                    place,
+                    wpm,
+                    accuracy,
+                });
+                entry.finish_if_done(room);
+            }
+        }
+    }
+
+    pub fn leave(&self, room: &str, player: PlayerId) {
+        let mut state = self.state.lock().unwrap();
+        let Some(entry) = state.rooms.get_mut(room) else {
+            return;
+        };
+        entry.players.remove(&player);
+        if entry.players.is_empty() {
+            state.rooms.remove(room);
+            return;
+        }
+        entry.broadcast_lobby(room);
+        // Chi resta potrebbe essere già tutto pronto o tutto arrivato
+        entry.finish_if_done(room);
+        entry.start_if_ready();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc::{UnboundedReceiver, unbounded_channel};
+
+    fn join(rooms: &Rooms, name: &str) -> (PlayerId, UnboundedReceiver<ServerMessage>) {
+        let (outbox, inbox) = unbounded_channel();
+        let id = rooms.join("sala", name, "base", outbox).unwrap();
+        (id, inbox)
+    }
+
+    fn drain(inbox: &mut UnboundedReceiver<ServerMessage>) -> Vec<ServerMessage> {
+        let mut messages = Vec::new();
+        while let Ok(message) = inbox.try_recv() {
+            messages.push(message);
+        }
+        messages
+    }
+
+    fn race_started(messages: &[ServerMessage]) -> bool {
+        messages
+            .iter()
+            .any(|m| matches!(m, ServerMessage::RaceStart { .. }))
+    }
+
+    fn race_over(messages: &[ServerMessage]) -> Option<Vec<PlayerInfo>> {
+        messages.iter().find_map(|m| match m {
+            ServerMessage::RaceOver { standings } => Some(standings.clone()),
+            _ => None,
+        })
+    }
+
+    fn ready(rooms: &Rooms, id: PlayerId) {
+        rooms.handle("sala", id, ClientMessage::SetReady { ready: true });
+    }
+
+    fn finish(rooms: &Rooms, id: PlayerId, wpm: f64) {
+        rooms.handle(
+            "sala",
+            id,
+            ClientMessage::Finish {
+                wpm,
+                accuracy: 95.0,
+            },
+        );
+    }
+
+    #[test]
+    fn full_room_rejects_join_and_starts_when_all_ready() {
+        let rooms = Rooms::default();
+        let players: Vec<_> = (0..MAX_PLAYERS)
+            .map(|i| join(&rooms, &format!("giocatore {}", i)))
+            .collect();
+
+        let (outbox, _inbox) = unbounded_channel();
+        assert!(rooms.join("sala", "ultimo", "base", outbox).is_err());
+
+        let (ids, mut inboxes): (Vec<_>, Vec<_>) = players.into_iter().unzip();
+        for id in &ids[..MAX_PLAYERS - 1] {
+            ready(&rooms, *id);
+        }
+        assert!(!race_started(&drain(&mut inboxes[0])));
+
+        ready(&rooms, ids[MAX_PLAYERS - 1]);
+        for inbox in &mut inboxes {
+            assert!(race_started(&drain(inbox)));
+        }
+    }
+
+    #[test]
+    fn join_is_rejected_while_racing() {
+        let rooms = Rooms::default();
+        let (first, _first_inbox) = join(&rooms, "anna");
+        ready(&rooms, first);
+
+        let (outbox, mut inbox) = unbounded_channel();
+        assert_eq!(
+            rooms.join("sala", "bruno", "base", outbox),
+            Err("Gara già in corso in questa stanza".to_string())
+        );
+        assert!(drain(&mut inbox).is_empty());
+
+        // A gara finita la stanza torna aperta
+        finish(&rooms, first, 60.0);
+        let (outbox, _inbox) = unbounded_channel();
+        assert!(rooms.join("sala", "bruno", "base", outbox).is_ok());
+    }
+
+    #[test]
+    fn leaving_mid_race_closes_the_race_for_those_left() {
+        let rooms = Rooms::default();
+        let (first, mut first_inbox) = join(&rooms, "anna");
+        let (second, _second_inbox) = join(&rooms, "bruno");
+        ready(&rooms, first);
+        ready(&rooms, second);
+        finish(&rooms, first, 70.0);
+        assert!(race_over(&drain(&mut first_inbox)).is_none());
+
+        rooms.leave("sala", second);
+        let messages = drain(&mut first_inbox);
+        let standings = race_over(&messages).expect("gara conclusa");
+        assert_eq!(standings.len(), 1);
+        assert_eq!(standings[0].id, first);
+        assert_eq!(standings[0].place, Some(1));
+        // Di nuovo in lobby, con il "pronto" azzerato
+        assert!(matches!(
+            messages.last(),
+            Some(ServerMessage::Lobby { racing: false, players, .. })
+                if players.iter().all(|p| !p.ready)
+        ));
+    }
+
+    #[test]
+    fn leaving_the_lobby_starts_when_the_rest_are_ready() {
+        let rooms = Rooms::default();
+        let (first, mut first_inbox) = join(&rooms, "anna");
+        let (second, _second_inbox) = join(&rooms, "bruno");
+        ready(&rooms, first);
+        assert!(!race_started(&drain(&mut first_inbox)));
+
+        rooms.leave("sala", second);
+        assert!(race_started(&drain(&mut first_inbox)));
+    }
+
+    #[test]
+    fn empty_room_is_removed() {
+        let rooms = Rooms::default();
+        let (first, _inbox) = join(&rooms, "anna");
+        rooms.leave("sala", first);
+        assert!(rooms.state.lock().unwrap().rooms.is_empty());
+    }
+}
@@ -0,0 +1,151 @@
+// race-server/tests/race.rs
+//
+// Gara completa tra due client WebSocket contro un server su porta effimera
+
+use futures_util::{SinkExt, StreamExt};
+use race_protocol::{ClientMessage, PlayerId, RACE_PHRASES, ServerMessage, WS_PATH};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+async fn start_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(race_server::serve(listener));
+    format!("ws://{}{}", address, WS_PATH)
+}
+
+async fn send(socket: &mut Socket, message: ClientMessage) {
+    let text = serde_json::to_string(&message).unwrap();
+    socket.send(Message::Text(text.into())).await.unwrap();
+}
+
+// Scarta i messaggi fino al primo che soddisfa `pick`
+async fn expect<T>(socket: &mut Socket, pick: impl Fn(ServerMessage) -> Option<T>) -> T {
+    tokio::time::timeout(TIMEOUT, async {
+        loop {
+            let message = socket.next().await.expect("socket chiuso").unwrap();
+            let Message::Text(text) = message else {
+                continue;
+            };
+            let message: ServerMessage = serde_json::from_str(text.as_str()).unwrap();
+            if let Some(value) = pick(message) {
+                return value;
+            }
+        }
+    })
+    .await
+    .expect("messaggio atteso non arrivato")
+}
+
+async fn join(url: &str, name: &str) -> (Socket, PlayerId) {
+    let (mut socket, _) = connect_async(url).await.unwrap();
+    send(
+        &mut socket,
+        ClientMessage::Join {
+            room: "sala".to_string(),
+            name: name.to_string(),
+            difficulty: "intermediate".to_string(),
+        },
+    )
+    .await;
+    let id = expect(&mut socket, |m| match m {
+        ServerMessage::Welcome { player_id } => Some(player_id),
+        _ => None,
+    })
+    .await;
+    (socket, id)
+}
+
+async fn race_start(socket: &mut Socket) -> (u64, String) {
+    expect(socket, |m| match m {
+        ServerMessage::RaceStart {
+            seed,
+            difficulty,
+            phrases,
+            ..
+        } => {
+            assert_eq!(phrases, RACE_PHRASES);
+            Some((seed, difficulty))
+        }
+        _ => None,
+    })
+    .await
+}
+
+#[tokio::test]
+async fn two_players_race_to_the_finish() {
+    let url = start_server().await;
+    let (mut anna, anna_id) = join(&url, "anna").await;
+    let (mut bruno, bruno_id) = join(&url, "bruno").await;
+    assert_ne!(anna_id, bruno_id);
+
+    send(&mut anna, ClientMessage::SetReady { ready: true }).await;
+    send(&mut bruno, ClientMessage::SetReady { ready: true }).await;
+
+    // Stesso seed e stessa difficoltà (quella di chi ha creato la stanza) per entrambi
+    let (anna_seed, anna_difficulty) = race_start(&mut anna).await;
+    let (bruno_seed, bruno_difficulty) = race_start(&mut bruno).await;
+    assert_eq!(anna_seed, bruno_seed);
+    assert_eq!(anna_difficulty, "intermediate");
+    assert_eq!(bruno_difficulty, "intermediate");
+
+    send(
+        &mut anna,
+        ClientMessage::Progress {
+            chars: 42,
+            wpm: 80.0,
+        },
+    )
+    .await;
+    let chars = expect(&mut bruno, |m| match m {
+        ServerMessage::Progress {
+            player_id, chars, ..
+        } if player_id == anna_id => Some(chars),
+        _ => None,
+    })
+    .await;
+    assert_eq!(chars, 42);
+
+    send(
+        &mut anna,
+        ClientMessage::Finish {
+            wpm: 82.0,
+            accuracy: 97.0,
+        },
+    )
+    .await;
+    let place = expect(&mut bruno, |m| match m {
+        ServerMessage::Finished {
+            player_id, place, ..
+        } if player_id == anna_id => Some(place),
+        _ => None,
+    })
+    .await;
+    assert_eq!(place, 1);
+
+    send(
+        &mut bruno,
+        ClientMessage::Finish {
+            wpm: 64.0,
+            accuracy: 91.0,
+        },
+    )
+    .await;
+
+    for socket in [&mut anna, &mut bruno] {
+        let standings = expect(socket, |m| match m {
+            ServerMessage::RaceOver { standings } => Some(standings),
+            _ => None,
+        })
+        .await;
+        let placements: Vec<_> = standings.iter().map(|p| (p.id, p.place)).collect();
+        assert_eq!(placements, [(anna_id, Some(1)), (bruno_id, Some(2))]);
+        assert_eq!(standings[0].accuracy, Some(97.0));
+    }
+}
@@ -0,0 +1,239 @@
+// src/achievements_store.rs
+//
+// Obiettivi sbloccabili: valutati dopo ogni partita e salvati con la data di sblocco
+
+use crate::calendar::{local_day, streak_ending_at};
+use crate::migrations::Schema;
+use crate::profile_store::profile_key;
+use crate::stats_store::{GameMode, GameStats, SessionRecord};
+use crate::storage::{SharedBackend, StorageBackend, StorageError, load_versioned, save_versioned};
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+const ACHIEVEMENTS_STORAGE_KEY: &str = "litterix_achievements";
+
+const ACHIEVEMENTS_SCHEMA: Schema = Schema {
+    current_version: 1,
+    migrations: &[],
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Achievement {
+    FirstGame,
+    Phrase100Wpm,
+    Combo80,
+    Combo1000,
+    PerfectPhrases10,
+    DailyStreak7,
+    MarathonScore100,
+    MarathonScore250,
+    Games100,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 9] = [
+        Self::FirstGame,
+        Self::Phrase100Wpm,
+        Self::Combo80,
+        Self::Combo1000,
+        Self::PerfectPhrases10,
+        Self::DailyStreak7,
+        Self::MarathonScore100,
+        Self::MarathonScore250,
+        Self::Games100,
+    ];
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Self::FirstGame => "🎯",
+            Self::Phrase100Wpm => "⚡",
+            Self::Combo80 => "🚀",
+            Self::Combo1000 => "🏆",
+            Self::PerfectPhrases10 => "✨",
+            Self::DailyStreak7 => "📅",
+            Self::MarathonScore100 => "🏃",
+            Self::MarathonScore250 => "🥇",
+            Self::Games100 => "🎖️",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::FirstGame => "Primi Passi",
+            Self::Phrase100Wpm => "Centometrista",
+            Self::Combo80 => "Mega Combo",
+            Self::Combo1000 => "Inarrestabile",
+            Self::PerfectPhrases10 => "Precisione Chirurgica",
+            Self::DailyStreak7 => "Settimana di Fuoco",
+            Self::MarathonScore100 => "Maratoneta",
+            Self::MarathonScore250 => "Ultramaratoneta",
+            Self::Games100 => "Veterano",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::FirstGame => "Completa la tua prima partita.",
+            Self::Phrase100Wpm => "Completa una frase a 100 WPM o più.",
+            Self::Combo80 => "Raggiungi una combo di 80 parole.",
+            Self::Combo1000 => "Raggiungi una combo di 1000 parole.",
+            Self::PerfectPhrases10 => "Completa 10 frasi perfette di fila.",
+            Self::DailyStreak7 => "Gioca per 7 giorni consecutivi.",
+            Self::MarathonScore100 => "Ottieni almeno 100 punti in Marathon.",
+            Self::MarathonScore250 => "Ottieni almeno 250 punti in Marathon.",
+            Self::Games100 => "Gioca 100 partite.",
+        }
+    }
+
+    // Condizione di sblocco, valutata con la partita appena registrata
+    fn is_met(&self, record: &SessionRecord, stats: &GameStats, history: &[SessionRecord]) -> bool {
+        let marathon_score = match record.mode {
+            GameMode::Marathon => record.score.unwrap_or_default(),
+            _ => 0,
+        };
+        match self {
+            Self::FirstGame => stats.total_games_played >= 1,
+            Self::Phrase100Wpm => record.best_phrase_wpm >= 100.0,
+            Self::Combo80 => record.combo >= 80,
+            Self::Combo1000 => record.combo >= 1000,
+            Self::PerfectPhrases10 => record.perfect_streak >= 10,
+            Self::DailyStreak7 => {
+                let played_days: BTreeSet<i64> =
+                    history.iter().map(|r| local_day(r.timestamp)).collect();
+                streak_ending_at(&played_days, local_day(record.timestamp)) >= 7
+            }
+            Self::MarathonScore100 => marathon_score >= 100,
+            Self::MarathonScore250 => marathon_score >= 250,
+            Self::Games100 => stats.total_games_played >= 100,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UnlockedAchievement {
+    pub achievement: Achievement,
+    pub unlocked_at: f64, // millisecondi dall'epoch
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AchievementLog {
+    pub unlocked: Vec<UnlockedAchievement>,
+}
+
+impl AchievementLog {
+    pub fn load(backend: &dyn StorageBackend, profile: &str) -> Self {
+        load_versioned(
+            backend,
+            &profile_key(ACHIEVEMENTS_STORAGE_KEY, profile),
+            &ACHIEVEMENTS_SCHEMA,
+        )
+    }
+
+    pub fn save(&self, backend: &dyn StorageBackend, profile: &str) -> Result<(), StorageError> {
+        save_versioned(
+            backend,
+            &profile_key(ACHIEVEMENTS_STORAGE_KEY, profile),
+            &ACHIEVEMENTS_SCHEMA,
+            self,
+        )
+    }
+
+    pub fn clear(backend: &dyn StorageBackend, profile: &str) -> Result<(), StorageError> {
+        backend.remove(&profile_key(ACHIEVEMENTS_STORAGE_KEY, profile))
+    }
+
+    pub fn unlocked_at(&self, achievement: Achievement) -> Option<f64> {
+        self.unlocked
+            .iter()
+            .find(|u| u.achievement == achievement)
+            .map(|u| u.unlocked_at)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct AchievementsContext {
+    log: RwSignal<AchievementLog>,
+    // Sblocchi recenti in attesa di essere annunciati dal popup
+    announcements: RwSignal<Vec<Achievement>>,
+    backend: StoredValue<SharedBackend>,
+    profile: RwSignal<String>,
+}
+
+impl AchievementsContext {
+    pub fn with_backend(backend: SharedBackend, profile: &str) -> Self {
+        let log = AchievementLog::load(backend.as_ref(), profile);
+        Self {
+            log: RwSignal::new(log),
+            announcements: RwSignal::new(Vec::new()),
+            backend: StoredValue::new(backend),
+            profile: RwSignal::new(profile.to_string()),
+        }
+    }
+
+    pub fn switch_profile(&self, profile: &str) {
+        let log = self
+            .backend
+            .with_value(|b| AchievementLog::load(b.as_ref(), profile));
+        self.profile.set(profile.to_string());
+        self.log.set(log);
+        self.announcements.set(Vec::new());
+    }
+
+    pub fn delete_profile_data(&self, profile: &str) -> Result<(), StorageError> {
+        self.backend
+            .with_value(|b| AchievementLog::clear(b.as_ref(), profile))
+    }
+
+    pub fn get_log(&self) -> AchievementLog {
+        self.log.get()
+    }
+
+    pub fn announcements(&self) -> Vec<Achievement> {
+        self.announcements.get()
+    }
+
+    pub fn dismiss(&self, achievement: Achievement) {
+        self.announcements
+            .update(|a| a.retain(|pending| *pending != achievement));
+    }
+
+    // Sblocca gli obiettivi soddisfatti dalla partita appena registrata
+    pub fn evaluate(&self, record: &SessionRecord, stats: &GameStats, history: &[SessionRecord]) {
+        let log = self.log.get_untracked();
+        let newly_unlocked: Vec<Achievement> = Achievement::ALL
+            .into_iter()
+            .filter(|a| log.unlocked_at(*a).is_none() && a.is_met(record, stats, history))
+            .collect();
+        if newly_unlocked.is_empty() {
+            return;
+        }
+
+        self.log.update(|log| {
+            log.unlocked
+                .extend(newly_unlocked.iter().map(|a| UnlockedAchievement {
+                    achievement: *a,
+                    unlocked_at: record.timestamp,
+                }));
+        });
+        self.announcements
+            .update(|a| a.extend(newly_unlocked.iter().copied()));
+
+        let log = self.log.get_untracked();
+        let profile = self.profile.get_untracked();
+        let _ = self.backend.with_value(|b| log.save(b.as_ref(), &profile));
+    }
+
+    pub fn clear(&self) -> Result<(), StorageError> {
+        self.delete_profile_data(&self.profile.get_untracked())?;
+        self.log.set(AchievementLog::default());
+        self.announcements.set(Vec::new());
+        Ok(())
+    }
+}
+
+pub fn use_achievements() -> AchievementsContext {
+    use_context::<AchievementsContext>()
+        .expect("AchievementsContext deve essere fornito a livello di App")
+}
@@ -1,9 +1,14 @@
 // src/app.rs
 //
-use crate::components::Navbar;
-use crate::pages::{Dashboard, Home, Project, Settings};
+use crate::components::{AchievementPopup, Navbar};
+use crate::leaderboard_store::LeaderboardContext;
+use crate::pages::{
+    Dashboard, Home, Project, Race, Replays, Settings, SharedResult, TournamentPage,
+};
+use crate::profile_store::{ProfileContext, ProfileRegistry};
 use crate::settings_store::SettingsContext;
 use crate::stats_store::StatsContext;
+use crate::storage::default_backend;
 use leptos::context::Provider;
 use leptos::prelude::*;
 use leptos_router::components::{Route, Router, Routes};
@@ -11,9 +16,13 @@ use leptos_router::path;
 
 #[component]
 pub fn App() -> impl IntoView {
-    // Crea i context delle impostazioni e delle statistiche
-    let settings_ctx = SettingsContext::new();
-    let stats_ctx = StatsContext::new();
+    // Il profilo attivo decide da quale namespace caricare impostazioni e statistiche
+    let registry = ProfileRegistry::load(default_backend().as_ref());
+    let settings_ctx = SettingsContext::new(&registry.active);
+    let stats_ctx = StatsContext::new(&registry.active);
+    let profile_ctx = ProfileContext::new(registry, settings_ctx, stats_ctx);
+    // La classifica è unica e condivisa tra tutti i profili
+    let leaderboard_ctx = LeaderboardContext::new();
 
     view! {
         <Router>
@@ -21,15 +30,29 @@ pub fn App() -> impl IntoView {
             <Provider value=settings_ctx>
                 // Provider per le statistiche
                 <Provider value=stats_ctx>
-                    <Navbar />
-                    <main>
-                        <Routes fallback=|| view! { <p>"Pagina non trovata."</p> }>
-                            <Route path=path!("/") view=|| view! { <Home /> } />
-                            <Route path=path!("/project") view=|| view! { <Project /> } />
-                            <Route path=path!("/dashboard") view=|| view! { <Dashboard /> } />
-                            <Route path=path!("/settings") view=|| view! { <Settings /> } />
-                        </Routes>
-                    </main>
+                    // Provider per i profili giocatore
+                    <Provider value=profile_ctx>
+                        // Provider per la classifica locale
+                        <Provider value=leaderboard_ctx>
+                            // Provider per gli obiettivi del profilo attivo
+                            <Provider value=stats_ctx.achievements()>
+                                <Navbar />
+                                <main>
+                                    <Routes fallback=|| view! { <p>"Pagina non trovata."</p> }>
+                                        <Route path=path!("/") view=|| view! { <Home /> } />
+                                        <Route path=path!("/project") view=|| view! { <Project /> } />
+                                        <Route path=path!("/dashboard") view=|| view! { <Dashboard /> } />
+                                        <Route path=path!("/replay") view=|| view! { <Replays /> } />
+                                        <Route path=path!("/race") view=|| view! { <Race /> } />
+                                        <Route path=path!("/tournament") view=|| view! { <TournamentPage /> } />
+                                        <Route path=path!("/settings") view=|| view! { <Settings /> } />
+                                        <Route path=path!("/result") view=|| view! { <SharedResult /> } />
+                                    </Routes>
+                                </main>
+                                <AchievementPopup />
+                            </Provider>
+                        </Provider>
+                    </Provider>
                 </Provider>
             </Provider>
         </Router>
@@ -0,0 +1,153 @@
+// src/augment.rs
+//
+// Arricchimento delle frasi dei dataset con numeri, maiuscole e punteggiatura
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+// Probabilità per parola di ogni arricchimento
+const NUMBER_CHANCE: f64 = 0.12;
+const CAPITAL_CHANCE: f64 = 0.15;
+const COMMA_CHANCE: f64 = 0.15;
+const PERIOD_CHANCE: f64 = 0.06;
+const QUOTE_CHANCE: f64 = 0.06;
+const PARENTHESIS_CHANCE: f64 = 0.05;
+
+// Fine frase: il punto è il più frequente
+const SENTENCE_ENDS: [char; 4] = ['.', '.', '?', '!'];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Augmentation {
+    Numbers,
+    Capitals,
+    Punctuation,
+}
+
+impl Augmentation {
+    pub const ALL: [Augmentation; 3] = [Self::Numbers, Self::Capitals, Self::Punctuation];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Numbers => "Numeri",
+            Self::Capitals => "Maiuscole",
+            Self::Punctuation => "Punteggiatura",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Numbers => "Inserisce numeri tra le parole, per allenare la riga dei numeri.",
+            Self::Capitals => {
+                "Maiuscola a inizio frase e su qualche parola, per allenare lo shift."
+            }
+            Self::Punctuation => "Virgole, punti, virgolette e parentesi sparsi nelle frasi.",
+        }
+    }
+}
+
+// Arricchimenti attivi: tutti spenti sono le frasi originali del dataset
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Augmentations {
+    pub numbers: bool,
+    pub capitals: bool,
+    pub punctuation: bool,
+}
+
+impl Augmentations {
+    pub fn is_plain(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn is_enabled(&self, augmentation: Augmentation) -> bool {
+        match augmentation {
+            Augmentation::Numbers => self.numbers,
+            Augmentation::Capitals => self.capitals,
+            Augmentation::Punctuation => self.punctuation,
+        }
+    }
+
+    pub fn set(&mut self, augmentation: Augmentation, enabled: bool) {
+        match augmentation {
+            Augmentation::Numbers => self.numbers = enabled,
+            Augmentation::Capitals => self.capitals = enabled,
+            Augmentation::Punctuation => self.punctuation = enabled,
+        }
+    }
+
+    // Descrizione degli arricchimenti attivi, per etichettare i risultati
+    pub fn describe(&self) -> String {
+        Augmentation::ALL
+            .into_iter()
+            .filter(|augmentation| self.is_enabled(*augmentation))
+            .map(|augmentation| augmentation.label())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    // Arricchisce tutte le frasi; con un rng da seed il risultato è riproducibile
+    pub fn apply(&self, phrases: &[String], rng: &mut impl Rng) -> Vec<String> {
+        if self.is_plain() {
+            return phrases.to_vec();
+        }
+        phrases
+            .iter()
+            .map(|phrase| self.augment(phrase, rng))
+            .collect()
+    }
+
+    fn augment(&self, phrase: &str, rng: &mut impl Rng) -> String {
+        let words: Vec<&str> = phrase.split_whitespace().collect();
+        let mut augmented = Vec::with_capacity(words.len());
+        let mut sentence_start = true;
+
+        for (i, word) in words.iter().enumerate() {
+            let is_last = i + 1 == words.len();
+            if self.numbers && rng.gen_bool(NUMBER_CHANCE) {
+                augmented.push(random_number(rng));
+            }
+
+            let mut word = word.to_string();
+            if self.capitals && (sentence_start || rng.gen_bool(CAPITAL_CHANCE)) {
+                word = capitalize(&word);
+            }
+            sentence_start = false;
+
+            if self.punctuation {
+                if rng.gen_bool(QUOTE_CHANCE) {
+                    word = format!("\"{}\"", word);
+                } else if rng.gen_bool(PARENTHESIS_CHANCE) {
+                    word = format!("({})", word);
+                }
+                if is_last {
+                    word.push(*SENTENCE_ENDS.choose(rng).unwrap_or(&'.'));
+                } else if rng.gen_bool(COMMA_CHANCE) {
+                    word.push(',');
+                } else if rng.gen_bool(PERIOD_CHANCE) {
+                    word.push('.');
+                    sentence_start = true;
+                }
+            }
+            augmented.push(word);
+        }
+        augmented.join(" ")
+    }
+}
+
+// Metà numeri brevi, metà fino a quattro cifre (anni, quantità)
+fn random_number(rng: &mut impl Rng) -> String {
+    if rng.gen_bool(0.5) {
+        rng.gen_range(1..100).to_string()
+    } else {
+        rng.gen_range(100..10000).to_string()
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
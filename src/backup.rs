@@ -0,0 +1,384 @@
+// src/backup.rs
+//
+// Esportazione e importazione di statistiche, storico e impostazioni
+
+use crate::calendar::iso_timestamp;
+use crate::migrations::Schema;
+use crate::settings_store::AppSettings;
+use crate::stats_store::{GameStats, SessionRecord, migrate_stats_v1_to_v2};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// Il backup usa lo stesso envelope versionato dei dati salvati, così i file
+// esportati oggi restano importabili dopo future modifiche al formato
+const BACKUP_SCHEMA: Schema = Schema {
+    current_version: 2,
+    migrations: &[reject_backup_v0, migrate_backup_v1_to_v2],
+};
+
+// I backup sono esportati sempre con l'envelope: un oggetto senza non è un backup
+fn reject_backup_v0(_: Value) -> Result<Value, String> {
+    Err("migrazione mancante".to_string())
+}
+
+// Le statistiche incluse seguono la stessa migrazione di quelle salvate
+fn migrate_backup_v1_to_v2(mut data: Value) -> Result<Value, String> {
+    if let Some(stats) = data.get_mut("stats") {
+        *stats = migrate_stats_v1_to_v2(stats.take())?;
+    }
+    Ok(data)
+}
+
+const CSV_HEADER: &str = "data,modalita,difficolta,parole,caratteri,tempo_s,wpm,accuracy,combo,punteggio,regole,arricchimenti";
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupBundle {
+    pub exported_at: f64,
+    pub settings: AppSettings,
+    pub stats: GameStats,
+    pub history: Vec<SessionRecord>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImportStrategy {
+    // Aggiunge le partite nuove e mantiene le impostazioni correnti
+    Merge,
+    // Sostituisce tutto con il contenuto del backup
+    Replace,
+}
+
+// Riepilogo di cosa cambierà importando un backup
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportPreview {
+    pub backup_sessions: usize,
+    pub new_sessions: usize,
+    pub duplicate_sessions: usize,
+    pub current_games: u32,
+    pub games_after_merge: u32,
+    pub games_after_replace: u32,
+    // Differenze di impostazioni applicate solo con `Replace`
+    pub settings_changes: Vec<String>,
+}
+
+pub fn export_json(bundle: &BackupBundle) -> Result<String, String> {
+    BACKUP_SCHEMA.encode(bundle)
+}
+
+pub fn parse_backup(raw: &str) -> Result<BackupBundle, String> {
+    let bundle = BACKUP_SCHEMA
+        .decode::<BackupBundle>(raw)
+        .map_err(|e| e.to_string())?
+        .value;
+    validate(&bundle)?;
+    Ok(bundle)
+}
+
+fn validate(bundle: &BackupBundle) -> Result<(), String> {
+    let valid_percent = |v: f64| v.is_finite() && (0.0..=100.0).contains(&v);
+    let valid_amount = |v: f64| v.is_finite() && v >= 0.0;
+
+    let stats = &bundle.stats;
+    if !valid_amount(stats.best_wpm)
+        || !valid_amount(stats.average_wpm)
+        || !valid_amount(stats.total_time_played)
+        || !valid_percent(stats.best_accuracy)
+        || !valid_percent(stats.average_accuracy)
+        || stats.variants.iter().any(|bucket| {
+            !valid_amount(bucket.best_wpm)
+                || !valid_amount(bucket.average_wpm)
+                || !valid_percent(bucket.best_accuracy)
+                || !valid_percent(bucket.average_accuracy)
+        })
+    {
+        return Err("statistiche con valori non validi".to_string());
+    }
+
+    for (i, record) in bundle.history.iter().enumerate() {
+        if !valid_amount(record.timestamp)
+            || !valid_amount(record.time)
+            || !valid_amount(record.wpm)
+            || !valid_percent(record.accuracy)
+        {
+            return Err(format!("partita {} dello storico non valida", i + 1));
+        }
+    }
+    Ok(())
+}
+
+fn new_sessions<'a>(
+    history: &[SessionRecord],
+    bundle: &'a BackupBundle,
+) -> impl Iterator<Item = &'a SessionRecord> {
+    bundle
+        .history
+        .iter()
+        .filter(|r| !history.iter().any(|h| h.is_same_session(r)))
+}
+
+pub fn preview(
+    settings: &AppSettings,
+    stats: &GameStats,
+    history: &[SessionRecord],
+    bundle: &BackupBundle,
+) -> ImportPreview {
+    let new_sessions = new_sessions(history, bundle).count();
+
+    let mut settings_changes = Vec::new();
+    if settings.difficulty != bundle.settings.difficulty {
+        settings_changes.push(format!(
+            "difficoltà: {} → {}",
+            settings.difficulty.to_str(),
+            bundle.settings.difficulty.to_str()
+        ));
+    }
+    if settings.daily_goal != bundle.settings.daily_goal {
+        settings_changes.push(format!(
+            "obiettivo giornaliero: {} → {}",
+            settings.daily_goal.describe(),
+            bundle.settings.daily_goal.describe()
+        ));
+    }
+
+    ImportPreview {
+        backup_sessions: bundle.history.len(),
+        new_sessions,
+        duplicate_sessions: bundle.history.len() - new_sessions,
+        current_games: stats.total_games_played,
+        games_after_merge: stats.total_games_played + new_sessions as u32,
+        games_after_replace: bundle.stats.total_games_played,
+        settings_changes,
+    }
+}
+
+// Calcola i dati risultanti dall'importazione
+pub fn apply(
+    strategy: ImportStrategy,
+    settings: &AppSettings,
+    stats: &GameStats,
+    history: &[SessionRecord],
+    bundle: &BackupBundle,
+) -> (AppSettings, GameStats, Vec<SessionRecord>) {
+    match strategy {
+        ImportStrategy::Replace => (
+            bundle.settings.clone(),
+            bundle.stats.clone(),
+            bundle.history.clone(),
+        ),
+        ImportStrategy::Merge => {
+            // Solo le partite nuove alimentano i totali: reimportare lo
+            // stesso backup non raddoppia i contatori
+            let mut merged_stats = stats.clone();
+            let mut merged_history = history.to_vec();
+            for record in new_sessions(history, bundle) {
+                merged_stats.apply(record);
+                merged_history.push(record.clone());
+            }
+            merged_stats.merge_bests(&bundle.stats);
+            merged_history.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+            (settings.clone(), merged_stats, merged_history)
+        }
+    }
+}
+
+pub fn history_to_csv(history: &[SessionRecord]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+    for record in history {
+        csv.push_str(&csv_row(record, &iso_timestamp(record.timestamp)));
+    }
+    csv
+}
+
+fn csv_row(record: &SessionRecord, date: &str) -> String {
+    format!(
+        "{},{},{},{},{},{:.1},{:.1},{:.1},{},{},{},{}\n",
+        date,
+        record.mode.label(),
+        record.difficulty.to_str(),
+        record.words,
+        record.chars,
+        record.time,
+        record.wpm,
+        record.accuracy,
+        record.combo,
+        record.score.map(|s| s.to_string()).unwrap_or_default(),
+        csv_field(&record.match_rules.describe()),
+        csv_field(&record.augmentations.describe()),
+    )
+}
+
+// Campo testuale tra virgolette, con le virgolette interne raddoppiate
+fn csv_field(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+// Nome file con la data odierna, es. `litterix-backup-2025-01-31.json`
+pub fn export_filename(prefix: &str, extension: &str) -> String {
+    let date: String = iso_timestamp(js_sys::Date::now())
+        .chars()
+        .take(10)
+        .collect();
+    format!("{}-{}.{}", prefix, date, extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::augment::Augmentations;
+    use crate::components::typing::engine::MatchRules;
+    use crate::settings_store::DatasetDifficulty;
+    use crate::stats_store::GameMode;
+
+    fn record(timestamp: f64, mode: GameMode, wpm: f64) -> SessionRecord {
+        SessionRecord {
+            timestamp,
+            mode,
+            difficulty: DatasetDifficulty::Base,
+            words: 10,
+            chars: 50,
+            time: 20.0,
+            wpm,
+            accuracy: 95.0,
+            combo: 4,
+            score: None,
+            best_phrase_wpm: wpm,
+            perfect_streak: 0,
+            match_rules: MatchRules::default(),
+            augmentations: Augmentations::default(),
+            symbols: Vec::new(),
+        }
+    }
+
+    fn stats_of(history: &[SessionRecord]) -> GameStats {
+        let mut stats = GameStats::default();
+        for record in history {
+            stats.apply(record);
+        }
+        stats
+    }
+
+    fn bundle(history: Vec<SessionRecord>) -> BackupBundle {
+        BackupBundle {
+            exported_at: 0.0,
+            settings: AppSettings::default(),
+            stats: stats_of(&history),
+            history,
+        }
+    }
+
+    #[test]
+    fn merge_skips_sessions_already_in_history() {
+        let history = vec![record(1.0, GameMode::Rush, 40.0)];
+        let backup = bundle(vec![
+            record(1.0, GameMode::Rush, 40.0),
+            record(2.0, GameMode::Zen, 60.0),
+        ]);
+        let stats = stats_of(&history);
+
+        let preview = preview(&AppSettings::default(), &stats, &history, &backup);
+        assert_eq!(preview.new_sessions, 1);
+        assert_eq!(preview.duplicate_sessions, 1);
+
+        let (_, merged_stats, merged_history) = apply(
+            ImportStrategy::Merge,
+            &AppSettings::default(),
+            &stats,
+            &history,
+            &backup,
+        );
+        assert_eq!(merged_history.len(), 2);
+        assert_eq!(merged_stats.total_games_played, 2);
+
+        // Reimportare lo stesso backup non cambia nulla
+        let (_, again_stats, again_history) = apply(
+            ImportStrategy::Merge,
+            &AppSettings::default(),
+            &merged_stats,
+            &merged_history,
+            &backup,
+        );
+        assert_eq!(again_history, merged_history);
+        assert_eq!(again_stats.total_games_played, 2);
+    }
+
+    #[test]
+    fn same_timestamp_in_another_mode_is_a_new_session() {
+        let history = vec![record(1.0, GameMode::Rush, 40.0)];
+        let backup = bundle(vec![record(1.0, GameMode::Marathon, 40.0)]);
+        let stats = stats_of(&history);
+        let (_, _, merged) = apply(
+            ImportStrategy::Merge,
+            &AppSettings::default(),
+            &stats,
+            &history,
+            &backup,
+        );
+        assert_eq!(merged.len(), 2);
+    }
+
+    #[test]
+    fn replace_takes_the_backup_as_is() {
+        let history = vec![record(1.0, GameMode::Rush, 40.0)];
+        let backup = bundle(vec![record(2.0, GameMode::Zen, 60.0)]);
+        let (_, stats, replaced) = apply(
+            ImportStrategy::Replace,
+            &AppSettings::default(),
+            &stats_of(&history),
+            &history,
+            &backup,
+        );
+        assert_eq!(replaced, backup.history);
+        assert_eq!(stats.total_games_played, 1);
+    }
+
+    #[test]
+    fn exported_backup_parses_back() {
+        let backup = bundle(vec![record(1.0, GameMode::Rush, 40.0)]);
+        let parsed = parse_backup(&export_json(&backup).unwrap()).unwrap();
+        assert_eq!(parsed.history, backup.history);
+    }
+
+    #[test]
+    fn newer_backup_version_is_rejected() {
+        let raw = r#"{ "version": 3, "data": {} }"#;
+        assert!(parse_backup(raw).is_err());
+    }
+
+    #[test]
+    fn v1_backup_stats_count_every_game_as_plain() {
+        let raw = r#"{ "version": 1, "data": { "stats": { "total_games_played": 3 } } }"#;
+        assert_eq!(parse_backup(raw).unwrap().stats.plain_games, 3);
+    }
+
+    #[test]
+    fn malformed_backup_is_rejected() {
+        assert!(parse_backup("[1, 2, 3]").is_err());
+        assert!(parse_backup(r#"{ "version": 1, "data": { "history": 3 } }"#).is_err());
+    }
+
+    #[test]
+    fn out_of_range_values_are_rejected() {
+        let mut backup = bundle(vec![record(1.0, GameMode::Rush, 40.0)]);
+        backup.history[0].accuracy = 150.0;
+        assert!(parse_backup(&export_json(&backup).unwrap()).is_err());
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_and_escaped() {
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("dice \"ciao\""), "\"dice \"\"ciao\"\"\"");
+    }
+
+    #[test]
+    fn csv_row_keeps_comma_separated_rules_in_one_field() {
+        let mut session = record(1.0, GameMode::Rush, 40.0);
+        session.match_rules.ignore_case = true;
+        session.match_rules.ignore_diacritics = true;
+        let row = csv_row(&session, "2025-01-31T10:00:00.000Z");
+        assert_eq!(
+            row,
+            "2025-01-31T10:00:00.000Z,Rush,base,10,50,20.0,40.0,95.0,4,,\"Ignora gli accenti, Ignora maiuscole e minuscole\",\"\"\n"
+        );
+    }
+}
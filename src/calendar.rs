@@ -0,0 +1,65 @@
+// src/calendar.rs
+//
+// Utility per date e orari delle partite (timestamp in millisecondi dall'epoch)
+
+use std::collections::BTreeSet;
+use wasm_bindgen::JsValue;
+
+fn js_date(timestamp: f64) -> js_sys::Date {
+    js_sys::Date::new(&JsValue::from_f64(timestamp))
+}
+
+// Data e ora in formato ISO 8601 (UTC)
+pub fn iso_timestamp(timestamp: f64) -> String {
+    js_date(timestamp).to_iso_string().into()
+}
+
+// Data locale nel formato gg/mm/aaaa
+pub fn format_date(timestamp: f64) -> String {
+    let date = js_date(timestamp);
+    format!(
+        "{:02}/{:02}/{}",
+        date.get_date(),
+        date.get_month() + 1,
+        date.get_full_year()
+    )
+}
+
+const MS_PER_DAY: f64 = 86_400_000.0;
+
+// Giorno locale della partita, come numero di giorni dall'epoch nel fuso del browser
+pub fn local_day(timestamp: f64) -> i64 {
+    let offset_minutes = js_date(timestamp).get_timezone_offset();
+    ((timestamp - offset_minutes * 60_000.0) / MS_PER_DAY).floor() as i64
+}
+
+// Giorni consecutivi con almeno una partita, contando all'indietro da `last_day`
+pub fn streak_ending_at(played_days: &BTreeSet<i64>, last_day: i64) -> u32 {
+    let mut streak = 0;
+    while played_days.contains(&(last_day - streak as i64)) {
+        streak += 1;
+    }
+    streak
+}
+
+// Mezzanotte locale del giorno indicato, come timestamp
+pub fn day_start(day: i64) -> f64 {
+    let utc_midnight = day as f64 * MS_PER_DAY;
+    utc_midnight + js_date(utc_midnight).get_timezone_offset() * 60_000.0
+}
+
+// Giorno della settimana con lunedì = 0 (il giorno 0 dell'epoch è un giovedì)
+pub fn weekday(day: i64) -> i64 {
+    (day + 3).rem_euclid(7)
+}
+
+// Data e ora locali nel formato gg/mm/aaaa hh:mm
+pub fn format_datetime(timestamp: f64) -> String {
+    let date = js_date(timestamp);
+    format!(
+        "{} {:02}:{:02}",
+        format_date(timestamp),
+        date.get_hours(),
+        date.get_minutes()
+    )
+}
@@ -0,0 +1,35 @@
+// src/components/achievement_popup.rs
+//
+use crate::achievements_store::use_achievements;
+use leptos::prelude::*;
+use std::time::Duration;
+
+// Annuncia gli obiettivi appena sbloccati, uno sotto l'altro
+#[component]
+pub fn AchievementPopup() -> impl IntoView {
+    let achievements_ctx = use_achievements();
+
+    view! {
+        <div class="achievement-popup">
+            <For
+                each=move || achievements_ctx.announcements()
+                key=|achievement| *achievement
+                children=move |achievement| {
+                    set_timeout(
+                        move || achievements_ctx.dismiss(achievement),
+                        Duration::from_millis(4000),
+                    );
+                    view! {
+                        <div class="achievement-popup__item">
+                            <span class="achievement-popup__icon">{achievement.icon()}</span>
+                            <div class="achievement-popup__text">
+                                <span class="achievement-popup__label">"Obiettivo sbloccato"</span>
+                                <span class="achievement-popup__title">{achievement.title()}</span>
+                            </div>
+                        </div>
+                    }
+                }
+            />
+        </div>
+    }
+}
@@ -1,8 +1,8 @@
 // src/components/game.rs
 //
-use crate::components::{MarathonMode, RushMode, ZenMode};
+use crate::components::{CodeMode, MarathonMode, RushMode, SurvivalMode, ZenMode};
 use leptos::prelude::*;
-use web_sys::window;
+use leptos_router::hooks::use_location;
 
 // Un enum per rappresentare lo stato in modo pulito e sicuro
 #[derive(Clone, Copy, PartialEq)]
@@ -10,22 +10,21 @@ enum GameMode {
     Zen,
     Rush,
     Marathon,
+    Survival,
+    Code,
 }
 
 #[component]
 pub fn Game() -> impl IntoView {
     // Leggiamo il frammento (#) dall'URL per impostare lo stato iniziale
-    let get_initial_mode = || {
-        if let Some(win) = window() {
-            if let Ok(hash) = win.location().hash() {
-                return match hash.as_str() {
-                    "#rush" => GameMode::Rush,
-                    "#marathon" => GameMode::Marathon,
-                    _ => GameMode::Zen,
-                };
-            }
-        }
-        GameMode::Zen
+    // (dal router, già aggiornato anche durante una navigazione interna)
+    let location = use_location();
+    let get_initial_mode = || match location.hash.get_untracked().as_str() {
+        "#rush" => GameMode::Rush,
+        "#marathon" => GameMode::Marathon,
+        "#survival" => GameMode::Survival,
+        "#code" => GameMode::Code,
+        _ => GameMode::Zen,
     };
 
     let (active_mode, set_active_mode) = signal(get_initial_mode());
@@ -58,6 +57,22 @@ pub fn Game() -> impl IntoView {
                     >
                         "Marathon"
                     </a>
+                    <a
+                        href="#survival"
+                        class="game-tab"
+                        class:active=move || active_mode.get() == GameMode::Survival
+                        on:click=move |_| set_active_mode.set(GameMode::Survival)
+                    >
+                        "Survival"
+                    </a>
+                    <a
+                        href="#code"
+                        class="game-tab"
+                        class:active=move || active_mode.get() == GameMode::Code
+                        on:click=move |_| set_active_mode.set(GameMode::Code)
+                    >
+                        "Codice"
+                    </a>
                 </div>
             </div>
 
@@ -66,6 +81,8 @@ pub fn Game() -> impl IntoView {
                     GameMode::Zen => view! { <ZenMode /> }.into_any(),
                     GameMode::Rush => view! { <RushMode /> }.into_any(),
                     GameMode::Marathon => view! { <MarathonMode /> }.into_any(),
+                    GameMode::Survival => view! { <SurvivalMode /> }.into_any(),
+                    GameMode::Code => view! { <CodeMode /> }.into_any(),
                 }}
             </div>
         </div>
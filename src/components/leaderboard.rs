@@ -0,0 +1,114 @@
+// src/components/leaderboard.rs
+//
+use crate::augment::Augmentations;
+use crate::calendar::format_date;
+use crate::components::typing::combo_badge;
+use crate::components::typing::engine::MatchRules;
+use crate::leaderboard_store::use_leaderboard;
+use crate::profile_store::use_profiles;
+use crate::settings_store::DatasetDifficulty;
+use crate::stats_store::GameMode;
+use leptos::prelude::*;
+
+// Tabella delle migliori partite per modalità, difficoltà, durata, regole e arricchimenti.
+// `highlight` è il timestamp della partita appena conclusa, evidenziata se presente
+#[component]
+pub fn Leaderboard(
+    mode: GameMode,
+    #[prop(into)] difficulty: Signal<DatasetDifficulty>,
+    duration: u32,
+    #[prop(into)] match_rules: Signal<MatchRules>,
+    #[prop(into)] augmentations: Signal<Augmentations>,
+    #[prop(optional, into)] highlight: Option<Signal<Option<f64>>>,
+) -> impl IntoView {
+    let leaderboard_ctx = use_leaderboard();
+    let profile_ctx = use_profiles();
+
+    let entries = Memo::new(move |_| {
+        leaderboard_ctx.entries(
+            mode,
+            difficulty.get(),
+            duration,
+            match_rules.get(),
+            augmentations.get(),
+        )
+    });
+    let highlighted = move || highlight.and_then(|h| h.get());
+
+    view! {
+        <div class="leaderboard">
+            <div class="leaderboard__header">
+                <span class="leaderboard__title">{mode.label()}</span>
+                <span class="leaderboard__meta">
+                    {move || {
+                        let augmentations = augmentations.get();
+                        if augmentations.is_plain() {
+                            format!("{} s", duration)
+                        } else {
+                            format!("{} s · {}", duration, augmentations.describe())
+                        }
+                    }}
+                    // Classifica delle partite con regole di confronto permissive
+                    {move || {
+                        let match_rules = match_rules.get();
+                        (!match_rules.is_strict()).then(|| view! {
+                            <span class="leaderboard__lenient" title=match_rules.describe()>
+                                "≈"
+                            </span>
+                        })
+                    }}
+                </span>
+            </div>
+            <Show
+                when=move || !entries.get().is_empty()
+                fallback=|| view! {
+                    <p class="leaderboard__empty">"Nessuna partita in classifica."</p>
+                }
+            >
+                <table class="leaderboard__table">
+                    <thead>
+                        <tr>
+                            <th>"#"</th>
+                            <th>"Giocatore"</th>
+                            <th>"Punteggio"</th>
+                            <th>"WPM"</th>
+                            <th>"Accuracy"</th>
+                            <th>"Combo"</th>
+                            <th>"Data"</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {move || {
+                            entries
+                                .get()
+                                .into_iter()
+                                .enumerate()
+                                .map(|(index, entry)| {
+                                    let is_current = highlighted() == Some(entry.timestamp);
+                                    let (emoji, _label) = combo_badge(entry.combo);
+                                    let player = profile_ctx
+                                        .name_of(&entry.profile_id)
+                                        .unwrap_or_else(|| "Profilo eliminato".to_string());
+                                    view! {
+                                        <tr
+                                            class="leaderboard__row"
+                                            class:leaderboard__row--current=is_current
+                                        >
+                                            <td class="leaderboard__rank">{index + 1}</td>
+                                            <td>{player}</td>
+                                            <td class="leaderboard__score">{entry.score}</td>
+                                            <td>{format!("{:.0}", entry.wpm)}</td>
+                                            <td>{format!("{:.1}%", entry.accuracy)}</td>
+                                            <td>{format!("{} {}", emoji, entry.combo)}</td>
+                                            <td>{format_date(entry.timestamp)}</td>
+                                        </tr>
+                                    }
+                                })
+                                .collect_view()
+                        }}
+                    </tbody>
+                </table>
+            </Show>
+        </div>
+    }
+}
@@ -1,9 +1,23 @@
 // src/components/mod.rs
 //
+pub mod achievement_popup;
 pub mod game;
+pub mod leaderboard;
 pub mod navbar;
+pub mod profile_switcher;
+pub mod race_track;
+pub mod result_card_download;
+pub mod share_button;
+pub mod tournament_results;
 pub mod typing;
 
+pub use achievement_popup::AchievementPopup;
 pub use game::Game;
+pub use leaderboard::Leaderboard;
 pub use navbar::Navbar;
-pub use typing::{MarathonMode, RushMode, ZenMode};
+pub use profile_switcher::ProfileSwitcher;
+pub use race_track::RaceTrack;
+pub use result_card_download::ResultCardDownload;
+pub use share_button::ShareButton;
+pub use tournament_results::TournamentResults;
+pub use typing::{CodeMode, MarathonMode, RushMode, SurvivalMode, ZenMode};
@@ -1,5 +1,6 @@
 // src/components/navbar.rs
 //
+use crate::components::ProfileSwitcher;
 use leptos::prelude::*;
 use leptos_router::components::A;
 use leptos_router::hooks::use_location;
@@ -29,6 +30,30 @@ pub fn Navbar() -> impl IntoView {
                         "Info"
                     </A>
 
+                    <A
+                        href="/replay"
+                        attr:class="navbar__link"
+                        class:navbar__link--active=move || location.pathname.get() == "/replay"
+                    >
+                        "Replay"
+                    </A>
+
+                    <A
+                        href="/race"
+                        attr:class="navbar__link"
+                        class:navbar__link--active=move || location.pathname.get() == "/race"
+                    >
+                        "Gara"
+                    </A>
+
+                    <A
+                        href="/tournament"
+                        attr:class="navbar__link"
+                        class:navbar__link--active=move || location.pathname.get() == "/tournament"
+                    >
+                        "Torneo"
+                    </A>
+
                 </div>
 
                 // --- Center: Logo ---
@@ -41,6 +66,8 @@ pub fn Navbar() -> impl IntoView {
 
                 // --- Right: Settings button ---
                 <div class="navbar__right">
+                    <ProfileSwitcher />
+
                     <A
                         href="/dashboard"
                         attr:class="navbar__link"
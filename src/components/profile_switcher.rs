@@ -0,0 +1,150 @@
+// src/components/profile_switcher.rs
+//
+use crate::profile_store::use_profiles;
+use leptos::ev::SubmitEvent;
+use leptos::prelude::*;
+
+#[component]
+pub fn ProfileSwitcher() -> impl IntoView {
+    let profile_ctx = use_profiles();
+
+    let (is_open, set_is_open) = signal(false);
+    let (new_name, set_new_name) = signal(String::new());
+    let (rename_value, set_rename_value) = signal(String::new());
+    let (confirm_delete, set_confirm_delete) = signal(false);
+    let (error_message, set_error_message) = signal::<Option<String>>(None);
+
+    let active = Signal::derive(move || profile_ctx.active());
+
+    let toggle_menu = move |_| {
+        let opening = !is_open.get();
+        set_is_open.set(opening);
+        if opening {
+            set_rename_value.set(active.get().name);
+            set_confirm_delete.set(false);
+            set_error_message.set(None);
+        }
+    };
+
+    let handle_switch = move |id: String| match profile_ctx.switch(&id) {
+        Ok(_) => set_is_open.set(false),
+        Err(e) => set_error_message.set(Some(e)),
+    };
+
+    let handle_create = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        match profile_ctx.create(&new_name.get()) {
+            Ok(profile) => {
+                set_new_name.set(String::new());
+                handle_switch(profile.id);
+            }
+            Err(e) => set_error_message.set(Some(e)),
+        }
+    };
+
+    let handle_rename = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        match profile_ctx.rename(&active.get().id, &rename_value.get()) {
+            Ok(_) => set_error_message.set(None),
+            Err(e) => set_error_message.set(Some(e)),
+        }
+    };
+
+    let handle_delete = move |_| match profile_ctx.delete(&active.get().id) {
+        Ok(_) => {
+            set_confirm_delete.set(false);
+            set_rename_value.set(active.get().name);
+            set_error_message.set(None);
+        }
+        Err(e) => set_error_message.set(Some(e)),
+    };
+
+    view! {
+        <div class="profile-switcher">
+            <button class="profile-switcher__toggle" on:click=toggle_menu title="Cambia profilo">
+                <span class="profile-switcher__avatar">
+                    {move || active.get().name.chars().next().unwrap_or('?').to_uppercase().to_string()}
+                </span>
+                <span class="profile-switcher__name">{move || active.get().name}</span>
+            </button>
+
+            <Show when=move || is_open.get()>
+                <div class="profile-switcher__menu">
+                    <div class="profile-switcher__label">"Profili"</div>
+                    <ul class="profile-switcher__list">
+                        <For
+                            each=move || profile_ctx.profiles()
+                            key=|p| (p.id.clone(), p.name.clone())
+                            children=move |p| {
+                                let id = p.id.clone();
+                                let is_active = move || active.get().id == id;
+                                let switch_id = p.id.clone();
+                                view! {
+                                    <li>
+                                        <button
+                                            class="profile-switcher__item"
+                                            class:profile-switcher__item--active=is_active
+                                            on:click=move |_| handle_switch(switch_id.clone())
+                                        >
+                                            {p.name}
+                                        </button>
+                                    </li>
+                                }
+                            }
+                        />
+                    </ul>
+
+                    <form class="profile-switcher__form" on:submit=handle_create>
+                        <input
+                            class="profile-switcher__input"
+                            type="text"
+                            placeholder="Nuovo profilo"
+                            prop:value=move || new_name.get()
+                            on:input=move |ev| set_new_name.set(event_target_value(&ev))
+                        />
+                        <button class="profile-switcher__button" type="submit">"Crea"</button>
+                    </form>
+
+                    <form class="profile-switcher__form" on:submit=handle_rename>
+                        <input
+                            class="profile-switcher__input"
+                            type="text"
+                            prop:value=move || rename_value.get()
+                            on:input=move |ev| set_rename_value.set(event_target_value(&ev))
+                        />
+                        <button class="profile-switcher__button" type="submit">"Rinomina"</button>
+                    </form>
+
+                    <Show
+                        when=move || confirm_delete.get()
+                        fallback=move || view! {
+                            <button
+                                class="profile-switcher__delete"
+                                disabled=move || profile_ctx.profiles().len() <= 1
+                                on:click=move |_| set_confirm_delete.set(true)
+                            >
+                                "Elimina profilo"
+                            </button>
+                        }
+                    >
+                        <div class="profile-switcher__confirm">
+                            <span>{move || format!("Eliminare \"{}\" e tutti i suoi dati?", active.get().name)}</span>
+                            <div class="profile-switcher__confirm-actions">
+                                <button class="profile-switcher__button" on:click=move |_| set_confirm_delete.set(false)>
+                                    "Annulla"
+                                </button>
+                                <button class="profile-switcher__delete" on:click=handle_delete>
+                                    "Elimina"
+                                </button>
+                            </div>
+                        </div>
+                    </Show>
+
+                    {move || error_message.get().map(|e| view! {
+                        <div class="profile-switcher__error">{e}</div>
+                    })}
+                </div>
+            </Show>
+        </div>
+    }
+}
@@ -0,0 +1,53 @@
+// src/components/race_track.rs
+//
+use leptos::prelude::*;
+use race_protocol::{PlayerId, PlayerInfo};
+
+// Una corsia per giocatore: barra di avanzamento con il cursore in testa
+#[component]
+pub fn RaceTrack(
+    #[prop(into)] players: Signal<Vec<PlayerInfo>>,
+    #[prop(into)] total_chars: Signal<usize>,
+    #[prop(into)] me: Signal<Option<PlayerId>>,
+) -> impl IntoView {
+    view! {
+        <div class="race-track">
+            <For
+                each=move || players.get()
+                key=|player| player.id
+                children=move |player| {
+                    let id = player.id;
+                    let current = move || players.with(|p| p.iter().find(|p| p.id == id).cloned());
+                    let percent = move || {
+                        let total = total_chars.get().max(1);
+                        current().map_or(0.0, |p| (p.chars.min(total) as f64 / total as f64) * 100.0)
+                    };
+                    view! {
+                        <div
+                            class="race-track__lane"
+                            class:race-track__lane--me=move || me.get() == Some(id)
+                        >
+                            <span class="race-track__name">{player.name.clone()}</span>
+                            <div class="race-track__bar">
+                                <div
+                                    class="race-track__fill"
+                                    style:width=move || format!("{:.1}%", percent())
+                                ></div>
+                                <span
+                                    class="race-track__caret"
+                                    style:left=move || format!("{:.1}%", percent())
+                                ></span>
+                            </div>
+                            <span class="race-track__stats">
+                                {move || current().map(|p| match p.place {
+                                    Some(place) => format!("#{} · {:.0} wpm", place, p.wpm),
+                                    None => format!("{:.0} wpm", p.wpm),
+                                })}
+                            </span>
+                        </div>
+                    }
+                }
+            />
+        </div>
+    }
+}
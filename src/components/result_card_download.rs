@@ -0,0 +1,57 @@
+// src/components/result_card_download.rs
+//
+use crate::backup::export_filename;
+use crate::file_io::{download_file, download_url};
+use crate::result_card::{ResultCard, svg_to_png};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+// Scarica la scheda del risultato in SVG o PNG, da postare in chat
+#[component]
+pub fn ResultCardDownload(#[prop(into)] card: Signal<Option<ResultCard>>) -> impl IntoView {
+    let (error_message, set_error_message) = signal::<Option<String>>(None);
+
+    let download_svg = move |_| {
+        let Some(card) = card.get() else {
+            return;
+        };
+        let result = download_file(
+            &export_filename("litterix-risultato", "svg"),
+            "image/svg+xml",
+            &card.to_svg(),
+        );
+        set_error_message.set(
+            result
+                .err()
+                .map(|_| "✗ Impossibile scaricare la scheda.".to_string()),
+        );
+    };
+
+    let download_png = move |_| {
+        let Some(card) = card.get() else {
+            return;
+        };
+        spawn_local(async move {
+            let result = match svg_to_png(&card.to_svg()).await {
+                Ok(png) => download_url(&export_filename("litterix-risultato", "png"), &png),
+                Err(e) => Err(e),
+            };
+            set_error_message.set(
+                result
+                    .err()
+                    .map(|_| "✗ Impossibile generare il PNG.".to_string()),
+            );
+        });
+    };
+
+    view! {
+        <div class="result-card-download">
+            <span class="result-card-download__label">"Scheda risultato"</span>
+            <button class="result-card-download__button" on:click=download_svg>"SVG"</button>
+            <button class="result-card-download__button" on:click=download_png>"PNG"</button>
+            {move || error_message.get().map(|message| view! {
+                <span class="result-card-download__error">{message}</span>
+            })}
+        </div>
+    }
+}
@@ -0,0 +1,69 @@
+// src/components/share_button.rs
+//
+use crate::share::{ResultSummary, copy_to_clipboard};
+use leptos::ev::FocusEvent;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use std::time::Duration;
+use web_sys::HtmlInputElement;
+
+#[derive(Clone, PartialEq)]
+enum CopyState {
+    Idle,
+    Copied,
+    // Appunti non disponibili: il link viene mostrato per la copia manuale
+    Failed(String),
+}
+
+// Copia negli appunti il link al risultato della partita appena conclusa
+#[component]
+pub fn ShareButton(#[prop(into)] summary: Signal<Option<ResultSummary>>) -> impl IntoView {
+    let (copy_state, set_copy_state) = signal(CopyState::Idle);
+
+    let copy_link = move |_| {
+        let Some(url) = summary.get().and_then(|s| s.url()) else {
+            return;
+        };
+        spawn_local(async move {
+            match copy_to_clipboard(&url).await {
+                Ok(_) => {
+                    set_copy_state.set(CopyState::Copied);
+                    set_timeout(
+                        move || set_copy_state.set(CopyState::Idle),
+                        Duration::from_millis(2000),
+                    );
+                }
+                Err(_) => set_copy_state.set(CopyState::Failed(url)),
+            }
+        });
+    };
+
+    let select_all = |ev: FocusEvent| event_target::<HtmlInputElement>(&ev).select();
+
+    view! {
+        <div class="share-button">
+            <button
+                class="share-button__action"
+                class:share-button__action--copied=move || copy_state.get() == CopyState::Copied
+                on:click=copy_link
+            >
+                {move || match copy_state.get() {
+                    CopyState::Copied => "✓ Link copiato!",
+                    _ => "🔗 Copia link al risultato",
+                }}
+            </button>
+            {move || match copy_state.get() {
+                CopyState::Failed(url) => Some(view! {
+                    <input
+                        class="share-button__fallback"
+                        type="text"
+                        readonly=true
+                        prop:value=url
+                        on:focus=select_all
+                    />
+                }),
+                _ => None,
+            }}
+        </div>
+    }
+}
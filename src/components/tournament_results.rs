@@ -0,0 +1,123 @@
+// src/components/tournament_results.rs
+//
+use crate::components::typing::combo_badge;
+use crate::tournament::{Heat, Tournament, TournamentFormat};
+use leptos::prelude::*;
+
+// Classifica del torneo, o tabellone a eliminazione diretta
+#[component]
+pub fn TournamentResults(#[prop(into)] tournament: Signal<Option<Tournament>>) -> impl IntoView {
+    move || {
+        tournament.get().map(|tournament| match tournament.format {
+            TournamentFormat::Table => results_table(&tournament).into_any(),
+            TournamentFormat::Knockout => bracket(&tournament).into_any(),
+        })
+    }
+}
+
+fn player_name(tournament: &Tournament, player: usize) -> String {
+    tournament
+        .players
+        .get(player)
+        .map(|p| p.name.clone())
+        .unwrap_or_default()
+}
+
+fn results_table(tournament: &Tournament) -> impl IntoView {
+    let ranking = tournament
+        .rounds
+        .first()
+        .and_then(|round| round.first())
+        .map(Heat::ranking)
+        .unwrap_or_default();
+
+    view! {
+        <table class="tournament-results__table">
+            <thead>
+                <tr>
+                    <th>"#"</th>
+                    <th>"Giocatore"</th>
+                    <th>"Punteggio"</th>
+                    <th>"WPM"</th>
+                    <th>"Accuracy"</th>
+                    <th>"Combo"</th>
+                </tr>
+            </thead>
+            <tbody>
+                {ranking
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, result)| {
+                        let (emoji, _label) = combo_badge(result.combo);
+                        view! {
+                            <tr class="tournament-results__row" class:tournament-results__row--winner=index == 0>
+                                <td>{index + 1}</td>
+                                <td>{player_name(tournament, result.player)}</td>
+                                <td class="tournament-results__score">{result.score}</td>
+                                <td>{format!("{:.0}", result.wpm)}</td>
+                                <td>{format!("{:.1}%", result.accuracy)}</td>
+                                <td>{format!("{} {}", emoji, result.combo)}</td>
+                            </tr>
+                        }
+                    })
+                    .collect_view()}
+            </tbody>
+        </table>
+    }
+}
+
+fn bracket(tournament: &Tournament) -> impl IntoView {
+    let total_rounds = tournament.rounds.len();
+    view! {
+        <div class="tournament-results__bracket">
+            {tournament
+                .rounds
+                .iter()
+                .enumerate()
+                .map(|(index, round)| {
+                    let is_final = index + 1 == total_rounds && round.len() == 1 && !round[0].is_bye();
+                    let title = if is_final {
+                        "Finale".to_string()
+                    } else {
+                        format!("Turno {}", index + 1)
+                    };
+                    view! {
+                        <div class="tournament-results__round">
+                            <span class="tournament-results__round-title">{title}</span>
+                            {round.iter().map(|heat| heat_card(tournament, heat)).collect_view()}
+                        </div>
+                    }
+                })
+                .collect_view()}
+        </div>
+    }
+}
+
+fn heat_card(tournament: &Tournament, heat: &Heat) -> impl IntoView {
+    let winner = heat.winner();
+    view! {
+        <div class="tournament-results__heat">
+            {heat
+                .players
+                .iter()
+                .map(|player| {
+                    let score = heat
+                        .results
+                        .iter()
+                        .find(|r| r.player == *player)
+                        .map(|r| r.score.to_string())
+                        .unwrap_or_else(|| if heat.is_bye() { "bye".to_string() } else { "-".to_string() });
+                    view! {
+                        <div
+                            class="tournament-results__entry"
+                            class:tournament-results__entry--winner=winner == Some(*player)
+                        >
+                            <span>{player_name(tournament, *player)}</span>
+                            <span class="tournament-results__score">{score}</span>
+                        </div>
+                    }
+                })
+                .collect_view()}
+        </div>
+    }
+}
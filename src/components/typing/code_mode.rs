@@ -0,0 +1,419 @@
+// src/components/typing/code_mode.rs
+//
+use crate::augment::Augmentations;
+use crate::components::typing::engine::{KeyInput, graphemes};
+use crate::components::typing::{ComboPopup, MetricsBar, TypingEngine, combo_popup::ComboType};
+use crate::profile_store::use_profiles;
+use crate::recording::{Replay, RunRecorder};
+use crate::settings_store::use_settings;
+use crate::stats_store::{GameMode as StatsGameMode, SessionRecord, SymbolAccuracy, use_stats};
+use leptos::prelude::*;
+use rand::rngs::OsRng;
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use web_sys::window;
+
+// Simboli di più caratteri contati come uno solo, cercati prima dei singoli
+const COMPOUND_SYMBOLS: [&str; 13] = [
+    "::", "->", "=>", "==", "!=", "<=", ">=", "&&", "||", "..", "+=", "-=", "*=",
+];
+
+// Parentesi e operatori di cui si misura la precisione
+const SYMBOLS: [&str; 23] = [
+    "(", ")", "[", "]", "{", "}", "<", ">", "+", "-", "*", "/", "%", "=", "!", "&", "|", "^", "?",
+    ":", ";", ",", ".",
+];
+
+// Simboli peggiori mostrati durante la sessione
+const SESSION_SYMBOLS_SHOWN: usize = 8;
+
+#[derive(Deserialize)]
+struct SnippetsData {
+    snippets: Vec<String>,
+}
+
+fn load_snippets() -> Vec<String> {
+    let data: SnippetsData =
+        serde_json::from_str(include_str!("../../../assets/datasets/rust-snippets.json"))
+            .expect("Errore nel parsing degli snippet JSON");
+    data.snippets
+}
+
+fn shuffle_snippets(snippets: &[String]) -> Vec<String> {
+    let mut shuffled = snippets.to_vec();
+    shuffled.shuffle(&mut OsRng);
+    shuffled
+}
+
+// Simbolo a cui appartiene ogni grafema dello snippet, se ne fa parte
+fn symbol_map(text: &str) -> Vec<Option<&'static str>> {
+    let graphemes = graphemes(text);
+    let mut symbols = vec![None; graphemes.len()];
+    let mut i = 0;
+    while i < graphemes.len() {
+        let pair = graphemes
+            .get(i + 1)
+            .map(|next| format!("{}{}", graphemes[i], next));
+        if let Some(symbol) = COMPOUND_SYMBOLS
+            .into_iter()
+            .find(|s| pair.as_deref() == Some(*s))
+        {
+            symbols[i] = Some(symbol);
+            symbols[i + 1] = Some(symbol);
+            i += 2;
+            continue;
+        }
+        symbols[i] = SYMBOLS.into_iter().find(|s| *s == graphemes[i]);
+        i += 1;
+    }
+    symbols
+}
+
+// Simboli della sessione, dal meno preciso
+fn ranked_symbols(symbols: &BTreeMap<&'static str, SymbolAccuracy>) -> Vec<SymbolAccuracy> {
+    let mut ranked: Vec<SymbolAccuracy> = symbols.values().cloned().collect();
+    ranked.sort_by(|a, b| a.accuracy().total_cmp(&b.accuracy()));
+    ranked
+}
+
+#[component]
+pub fn CodeMode() -> impl IntoView {
+    let settings_ctx = use_settings();
+    let stats_ctx = use_stats();
+    let profile_ctx = use_profiles();
+
+    let snippets = StoredValue::new(load_snippets());
+
+    let (shuffled_snippets, set_shuffled_snippets) =
+        signal(snippets.with_value(|s| shuffle_snippets(s)));
+    let (snippet_index, set_snippet_index) = signal(0_usize);
+    let (last_wpm, set_last_wpm) = signal(0.0);
+    let (last_accuracy, set_last_accuracy) = signal(100.0);
+    let (chars_typed, set_chars_typed) = signal(0_usize);
+    let (words_typed, set_words_typed) = signal(0_usize);
+    let (is_transitioning, set_is_transitioning) = signal(false);
+
+    // Combo: parole consecutive senza errori
+    let (consecutive_correct_words, set_consecutive_correct_words) = signal(0_usize);
+    let (combo_trigger, set_combo_trigger) = signal::<Option<ComboType>>(None);
+    let (snippet_has_errors, set_snippet_has_errors) = signal(false);
+    let (highest_combo, set_highest_combo) = signal(0_usize);
+
+    // Record della sessione per gli obiettivi
+    let (best_snippet_wpm, set_best_snippet_wpm) = signal(0.0);
+    let (perfect_streak, set_perfect_streak) = signal(0_u32);
+    let (best_perfect_streak, set_best_perfect_streak) = signal(0_u32);
+
+    // Tracking sessione per stats
+    let (session_started, set_session_started) = signal(false);
+    let (session_start_time, set_session_start_time) = signal(0.0);
+    let (total_session_words, set_total_session_words) = signal(0_u32);
+    let (total_session_chars, set_total_session_chars) = signal(0_u32);
+    let (accuracy_sum, set_accuracy_sum) = signal(0.0);
+    let (wpm_sum, set_wpm_sum) = signal(0.0);
+    let (snippets_completed, set_snippets_completed) = signal(0_u32);
+    let (session_symbols, set_session_symbols) =
+        signal(BTreeMap::<&'static str, SymbolAccuracy>::new());
+
+    // Regole del profilo, senza mai saltare i simboli di cui si misura la precisione
+    let match_rules = Memo::new(move |_| settings_ctx.get_match_rules().for_code());
+
+    let recorder = RunRecorder::new();
+    Effect::new(move |_| {
+        recorder.set_rules(
+            settings_ctx.get_error_mode(),
+            match_rules.get(),
+            settings_ctx.get_input_model(),
+        )
+    });
+
+    let current_snippet = Memo::new(move |_| {
+        let snippets = shuffled_snippets.get();
+        snippets[snippet_index.get() % snippets.len()].clone()
+    });
+    let current_symbols = Memo::new(move |_| symbol_map(&current_snippet.get()));
+
+    let next_snippet = move || {
+        set_snippet_index.update(|i| {
+            *i += 1;
+            if *i >= shuffled_snippets.with_untracked(|s| s.len()) {
+                set_shuffled_snippets.set(snippets.with_value(|s| shuffle_snippets(s)));
+                *i = 0;
+            }
+        });
+    };
+
+    let on_key_result = Callback::new(move |(index, correct): (usize, bool)| {
+        let Some(symbol) = current_symbols.with_untracked(|s| s.get(index).copied().flatten())
+        else {
+            return;
+        };
+        set_session_symbols.update(|symbols| {
+            let entry = symbols.entry(symbol).or_insert_with(|| SymbolAccuracy {
+                symbol: symbol.to_string(),
+                ..SymbolAccuracy::default()
+            });
+            entry.attempts += 1;
+            if !correct {
+                entry.errors += 1;
+            }
+        });
+    });
+
+    let on_char_error = Callback::new(move |_: ()| {
+        if consecutive_correct_words.get() >= 5 {
+            set_combo_trigger.set(Some(ComboType::ComboBroken));
+        }
+        set_consecutive_correct_words.set(0);
+        set_snippet_has_errors.set(true);
+    });
+
+    let on_complete = Callback::new(move |(wpm, accuracy, _): (f64, f64, usize)| {
+        set_last_wpm.set(wpm);
+        set_last_accuracy.set(accuracy);
+        set_accuracy_sum.update(|sum| *sum += accuracy);
+        set_wpm_sum.update(|sum| *sum += wpm);
+        set_snippets_completed.update(|count| *count += 1);
+        set_best_snippet_wpm.update(|best| *best = f64::max(*best, wpm));
+
+        if !snippet_has_errors.get() {
+            set_combo_trigger.set(Some(ComboType::PerfectPhrase));
+            set_perfect_streak.update(|streak| *streak += 1);
+            set_best_perfect_streak.update(|best| *best = (*best).max(perfect_streak.get()));
+        } else {
+            set_perfect_streak.set(0);
+        }
+
+        set_is_transitioning.set(true);
+        set_timeout(
+            move || {
+                next_snippet();
+                set_chars_typed.set(0);
+                set_words_typed.set(0);
+                set_snippet_has_errors.set(false);
+                set_is_transitioning.set(false);
+            },
+            std::time::Duration::from_millis(400),
+        );
+    });
+
+    let on_keystroke = Callback::new(move |(input, _): (KeyInput, usize)| {
+        recorder.record(
+            snippets_completed.get_untracked() as usize,
+            &current_snippet.get_untracked(),
+            input,
+        );
+    });
+
+    let on_char_typed = Callback::new(move |_: ()| {
+        if !session_started.get() {
+            set_session_started.set(true);
+            if let Some(win) = window()
+                && let Some(perf) = win.performance()
+            {
+                set_session_start_time.set(perf.now());
+            }
+        }
+
+        set_chars_typed.update(|c| *c += 1);
+        set_total_session_chars.update(|c| *c += 1);
+    });
+
+    let on_word_typed = Callback::new(move |_: ()| {
+        set_words_typed.update(|w| *w += 1);
+        set_total_session_words.update(|w| *w += 1);
+        set_consecutive_correct_words.update(|count| {
+            *count += 1;
+            if *count > highest_combo.get() {
+                set_highest_combo.set(*count);
+            }
+            match *count {
+                5 => set_combo_trigger.set(Some(ComboType::Streak5)),
+                10 => set_combo_trigger.set(Some(ComboType::Streak10)),
+                20 => set_combo_trigger.set(Some(ComboType::Streak20)),
+                40 => set_combo_trigger.set(Some(ComboType::Streak40)),
+                _ => {}
+            }
+        });
+    });
+
+    let on_word_deleted = Callback::new(move |_: ()| {
+        set_words_typed.update(|w| *w = w.saturating_sub(1));
+        set_total_session_words.update(|w| *w = w.saturating_sub(1));
+        if consecutive_correct_words.get() >= 5 {
+            set_combo_trigger.set(Some(ComboType::ComboBroken));
+        }
+        set_consecutive_correct_words.set(0);
+        set_snippet_has_errors.set(true);
+    });
+
+    // Termina la sessione e salva le stats, compresa la precisione per simbolo
+    let end_session = move || {
+        if session_started.get() && snippets_completed.get() > 0 {
+            let session_time = window()
+                .and_then(|win| win.performance())
+                .map_or(0.0, |perf| (perf.now() - session_start_time.get()) / 1000.0);
+            let completed = snippets_completed.get() as f64;
+
+            let record = SessionRecord {
+                timestamp: js_sys::Date::now(),
+                mode: StatsGameMode::Code,
+                difficulty: settings_ctx.get_difficulty(),
+                words: total_session_words.get(),
+                chars: total_session_chars.get(),
+                time: session_time,
+                wpm: wpm_sum.get() / completed,
+                accuracy: accuracy_sum.get() / completed,
+                combo: highest_combo.get(),
+                score: None,
+                best_phrase_wpm: best_snippet_wpm.get(),
+                perfect_streak: best_perfect_streak.get(),
+                match_rules: match_rules.get(),
+                augmentations: Augmentations::default(),
+                symbols: session_symbols.with(|s| s.values().cloned().collect()),
+            };
+            let _ = stats_ctx.save_replay(Replay::new(
+                &record,
+                &profile_ctx.active().name,
+                None,
+                recorder.recording(),
+            ));
+            stats_ctx.record_game(record);
+        }
+
+        set_session_started.set(false);
+        recorder.reset();
+        set_total_session_words.set(0);
+        set_total_session_chars.set(0);
+        set_accuracy_sum.set(0.0);
+        set_wpm_sum.set(0.0);
+        set_snippets_completed.set(0);
+        set_session_symbols.set(BTreeMap::new());
+        set_highest_combo.set(0);
+        set_best_snippet_wpm.set(0.0);
+        set_perfect_streak.set(0);
+        set_best_perfect_streak.set(0);
+        set_consecutive_correct_words.set(0);
+
+        set_shuffled_snippets.set(snippets.with_value(|s| shuffle_snippets(s)));
+        set_snippet_index.set(0);
+    };
+
+    // Morte improvvisa: la sessione si chiude e si riparte da uno snippet nuovo
+    let on_fail = Callback::new(move |_: ()| {
+        end_session();
+        set_is_transitioning.set(true);
+        set_timeout(
+            move || {
+                set_chars_typed.set(0);
+                set_words_typed.set(0);
+                set_snippet_has_errors.set(false);
+                set_is_transitioning.set(false);
+            },
+            std::time::Duration::from_millis(400),
+        );
+    });
+
+    view! {
+        <div class="code-mode">
+            <ComboPopup trigger=Signal::derive(move || combo_trigger.get()) />
+
+            <div class="code-header">
+                <span class="code-icon">
+                    <svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round"><polyline points="16 18 22 12 16 6"/><polyline points="8 6 2 12 8 18"/></svg>
+                </span>
+                <h2 class="code-title">"Codice"</h2>
+                <p class="code-description">
+                    "Snippet di Rust da ricopiare: Invio a fine riga, Tab per l'indentazione. "
+                    "Parentesi e operatori hanno una precisione tutta loro."
+                </p>
+            </div>
+
+            <MetricsBar
+                wpm=Signal::derive(move || last_wpm.get())
+                accuracy=Signal::derive(move || last_accuracy.get())
+                chars_typed=Signal::derive(move || chars_typed.get())
+                words_typed=Signal::derive(move || words_typed.get())
+                current_phrase=Signal::derive(move || snippet_index.get() + 1)
+            />
+
+            {move || {
+                if is_transitioning.get() {
+                    view! {
+                        <div class="code-transition code-transition--fading">
+                            <div class="typing-display" style="min-height: 200px;"></div>
+                        </div>
+                    }
+                    .into_any()
+                } else {
+                    view! {
+                        <div class="code-transition">
+                            <TypingEngine
+                                text=current_snippet.get()
+                                on_complete=on_complete
+                                on_char_typed=on_char_typed
+                                on_char_error=on_char_error
+                                on_word_typed=on_word_typed
+                                on_word_deleted=on_word_deleted
+                                on_keystroke=on_keystroke
+                                on_key_result=on_key_result
+                                error_mode=settings_ctx.get_error_mode()
+                                match_rules=match_rules.get()
+                                input_model=settings_ctx.get_input_model()
+                                caret_style=settings_ctx.get_caret_style()
+                                on_fail=on_fail
+                            />
+                        </div>
+                    }
+                    .into_any()
+                }
+            }}
+
+            // Simboli meno precisi della sessione
+            <Show when=move || session_symbols.with(|s| !s.is_empty())>
+                <div class="code-symbols">
+                    {move || {
+                        session_symbols
+                            .with(ranked_symbols)
+                            .into_iter()
+                            .take(SESSION_SYMBOLS_SHOWN)
+                            .map(|symbol| view! {
+                                <div
+                                    class="code-symbols__item"
+                                    class:code-symbols__item--weak={symbol.accuracy() < 90.0}
+                                    title=format!("{} tasti, {} errori", symbol.attempts, symbol.errors)
+                                >
+                                    <span class="code-symbols__symbol">{symbol.symbol.clone()}</span>
+                                    <span class="code-symbols__value">{format!("{:.0}%", symbol.accuracy())}</span>
+                                </div>
+                            })
+                            .collect_view()
+                    }}
+                </div>
+            </Show>
+
+            <Show when=move || session_started.get()>
+                <div class="code-session-controls">
+                    <button class="code-end-session-button" on:click=move |_| end_session()>
+                        <svg xmlns="http://www.w3.org/2000/svg" width="18" height="18" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
+                            <path d="M9 9h6v6H9z"/>
+                            <path d="M3 12a9 9 0 1 0 18 0 9 9 0 1 0-18 0"/>
+                        </svg>
+                        "Termina Sessione e Salva"
+                    </button>
+                    <div class="code-session-info">
+                        <span class="code-session-stat">
+                            "Snippet completati: "
+                            <strong>{move || snippets_completed.get()}</strong>
+                        </span>
+                        <span class="code-session-stat">
+                            "Parole totali: "
+                            <strong>{move || total_session_words.get()}</strong>
+                        </span>
+                    </div>
+                </div>
+            </Show>
+        </div>
+    }
+}
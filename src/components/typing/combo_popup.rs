@@ -17,6 +17,8 @@ pub enum ComboType {
     Streak1000,
     PerfectPhrase,
     ComboBroken,
+    LifeLost,
+    LifeGained,
 }
 
 impl ComboType {
@@ -34,6 +36,8 @@ impl ComboType {
             Self::Streak1000 => "🏆 UNSTOPPABLE +1000!",
             Self::PerfectPhrase => "✨ Frase Perfetta!",
             Self::ComboBroken => "💔 Combo Interrotta!",
+            Self::LifeLost => "🩸 Vita Persa!",
+            Self::LifeGained => "❤️ Vita Extra!",
         }
     }
 
@@ -51,10 +55,29 @@ impl ComboType {
             Self::Streak1000 => "#ffd700", // Oro brillante
             Self::PerfectPhrase => "#f74c00",
             Self::ComboBroken => "#666666",
+            Self::LifeLost => "#ca4754",
+            Self::LifeGained => "#4caf50",
         }
     }
 }
 
+// Helper per ottenere il badge combo in base al punteggio
+pub fn combo_badge(combo: usize) -> (&'static str, &'static str) {
+    match combo {
+        0..=4 => ("", "Nessun Combo"),
+        5..=9 => ("🔥", "Combo"),
+        10..=14 => ("⚡", "Combo"),
+        15..=19 => ("💫", "Combo"),
+        20..=39 => ("🌟", "Grande Combo"),
+        40..=79 => ("💥", "Mega Combo"),
+        80..=159 => ("🚀", "Mega Combo"),
+        160..=319 => ("⭐", "Ultra Combo"),
+        320..=639 => ("👑", "Legendary"),
+        640..=999 => ("🔱", "Godlike"),
+        _ => ("🏆", "Unstoppable"),
+    }
+}
+
 #[component]
 pub fn ComboPopup(#[prop(into)] trigger: Signal<Option<ComboType>>) -> impl IntoView {
     let (visible, set_visible) = signal(false);
@@ -1,95 +1,631 @@
 // src/components/typing/engine.rs (AGGIORNATO)
 //
-use leptos::ev::KeyboardEvent;
+use crate::components::typing::typing_text::{CaretStyle, TextCells, TypingText};
+use leptos::ev::{CompositionEvent, KeyboardEvent};
 use leptos::html::Input;
 use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+use unicode_segmentation::UnicodeSegmentation;
 use web_sys::window;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum CharStatus {
     Pending,
     Correct,
     Incorrect,
+    // Saltato con lo spazio a metà parola (modello `WordAware`)
+    Missed,
+}
+
+// Singolo input ricevuto dall'engine, registrabile per ghost e replay
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum KeyInput {
+    Char(char),
+    // Grafema composto da più code point (es. emoji con ZWJ, bandiere)
+    Grapheme(String),
+    Backspace,
+    // Backspace con modificatore: cancella l'intera parola
+    DeleteWord,
+}
+
+impl KeyInput {
+    pub fn from_grapheme(grapheme: &str) -> Self {
+        let mut chars = grapheme.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Self::Char(c),
+            _ => Self::Grapheme(grapheme.to_string()),
+        }
+    }
+}
+
+// Testo e input vengono confrontati in forma NFC: "e" + accento combinante vale "è"
+pub fn normalize(text: &str) -> String {
+    text.nfc().collect()
+}
+
+// Grafemi estesi del testo normalizzato: l'unità di battitura dell'engine
+pub fn graphemes(text: &str) -> Vec<String> {
+    normalize(text)
+        .graphemes(true)
+        .map(str::to_string)
+        .collect()
+}
+
+pub fn grapheme_count(text: &str) -> usize {
+    normalize(text).graphemes(true).count()
+}
+
+// Come l'engine reagisce a un tasto sbagliato
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ErrorMode {
+    // Il cursore avanza comunque e l'errore resta da correggere
+    #[default]
+    Advance,
+    // Il cursore resta fermo finché non arriva il tasto giusto
+    StopOnError,
+    // Il primo errore chiude la partita
+    SuddenDeath,
+}
+
+impl ErrorMode {
+    pub const ALL: [ErrorMode; 3] = [Self::Advance, Self::StopOnError, Self::SuddenDeath];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Advance => "Normale",
+            Self::StopOnError => "Blocca sull'errore",
+            Self::SuddenDeath => "Morte improvvisa",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Advance => {
+                "Il cursore avanza anche sui tasti sbagliati: puoi tornare indietro e correggere."
+            }
+            Self::StopOnError => {
+                "Il cursore non avanza finché non premi il tasto giusto. Ogni tasto sbagliato pesa sull'accuracy."
+            }
+            Self::SuddenDeath => {
+                "Il primo errore chiude la partita. Solo per chi cerca la precisione assoluta."
+            }
+        }
+    }
 }
 
-#[derive(Clone, Debug)]
+// Come l'engine allinea i tasti al testo
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum InputModel {
+    // Ogni tasto occupa il grafema successivo, spazio compreso
+    #[default]
+    Linear,
+    // Le lettere in più restano sulla parola corrente e lo spazio salta alla successiva
+    WordAware,
+}
+
+impl InputModel {
+    pub const ALL: [InputModel; 2] = [Self::Linear, Self::WordAware];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Linear => "Carattere per carattere",
+            Self::WordAware => "Per parole",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Linear => {
+                "Ogni tasto prende il posto del carattere successivo: una lettera in più sposta tutta la parola."
+            }
+            Self::WordAware => {
+                "Le lettere in più restano in coda alla parola e lo spazio a metà parola salta alla successiva, segnando il resto come mancato. Solo con la gestione errori Normale."
+            }
+        }
+    }
+
+    // Con `StopOnError` e `SuddenDeath` il tasto sbagliato non avanza mai:
+    // lettere in più e parole saltate non esistono
+    pub fn is_available(&self, error_mode: ErrorMode) -> bool {
+        *self == Self::Linear || error_mode == ErrorMode::Advance
+    }
+
+    // Modello effettivamente usato con la gestione errori indicata
+    pub fn effective(self, error_mode: ErrorMode) -> Self {
+        if self.is_available(error_mode) {
+            self
+        } else {
+            Self::Linear
+        }
+    }
+}
+
+// Lettere in più accettate in coda a una parola
+const MAX_OVERFLOW: usize = 20;
+
+// Spazi per livello di indentazione, inseriti da Tab nei testi su più righe
+const TAB_WIDTH: usize = 4;
+
+// Regola di confronto permissiva tra il grafema digitato e quello atteso
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MatchRule {
+    IgnoreDiacritics,
+    IgnoreCase,
+    AsciiPunctuation,
+    SkipPunctuation,
+    SkipIndent,
+}
+
+impl MatchRule {
+    pub const ALL: [MatchRule; 5] = [
+        Self::IgnoreDiacritics,
+        Self::IgnoreCase,
+        Self::AsciiPunctuation,
+        Self::SkipPunctuation,
+        Self::SkipIndent,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::IgnoreDiacritics => "Ignora gli accenti",
+            Self::IgnoreCase => "Ignora maiuscole e minuscole",
+            Self::AsciiPunctuation => "Virgolette e trattini semplici",
+            Self::SkipPunctuation => "Salta la punteggiatura",
+            Self::SkipIndent => "Salta l'indentazione",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::IgnoreDiacritics => {
+                "\"e\" vale come \"è\" o \"é\": utile su tastiere senza accenti a portata di mano."
+            }
+            Self::IgnoreCase => "Una lettera minuscola vale come la sua maiuscola e viceversa.",
+            Self::AsciiPunctuation => {
+                "Virgolette tipografiche, apostrofi curvi e lineette si digitano con ' \" e -."
+            }
+            Self::SkipPunctuation => {
+                "Il cursore salta da solo i segni di punteggiatura, tranne nella modalità Codice."
+            }
+            Self::SkipIndent => {
+                "Nella modalità Codice il cursore salta da solo gli spazi a inizio riga."
+            }
+        }
+    }
+}
+
+// Regole di confronto attive: tutte spente è il confronto esatto
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatchRules {
+    pub ignore_diacritics: bool,
+    pub ignore_case: bool,
+    pub ascii_punctuation: bool,
+    pub skip_punctuation: bool,
+    pub skip_indent: bool,
+}
+
+impl MatchRules {
+    pub fn is_strict(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn is_enabled(&self, rule: MatchRule) -> bool {
+        match rule {
+            MatchRule::IgnoreDiacritics => self.ignore_diacritics,
+            MatchRule::IgnoreCase => self.ignore_case,
+            MatchRule::AsciiPunctuation => self.ascii_punctuation,
+            MatchRule::SkipPunctuation => self.skip_punctuation,
+            MatchRule::SkipIndent => self.skip_indent,
+        }
+    }
+
+    pub fn set(&mut self, rule: MatchRule, enabled: bool) {
+        match rule {
+            MatchRule::IgnoreDiacritics => self.ignore_diacritics = enabled,
+            MatchRule::IgnoreCase => self.ignore_case = enabled,
+            MatchRule::AsciiPunctuation => self.ascii_punctuation = enabled,
+            MatchRule::SkipPunctuation => self.skip_punctuation = enabled,
+            MatchRule::SkipIndent => self.skip_indent = enabled,
+        }
+    }
+
+    // Descrizione delle regole attive, per etichettare i risultati
+    pub fn describe(&self) -> String {
+        MatchRule::ALL
+            .into_iter()
+            .filter(|rule| self.is_enabled(*rule))
+            .map(|rule| rule.label())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    // Nella modalità Codice la punteggiatura è ciò che si allena: non si salta mai
+    pub fn for_code(self) -> Self {
+        Self {
+            skip_punctuation: false,
+            ..self
+        }
+    }
+
+    pub fn matches(&self, typed: &str, expected: &str) -> bool {
+        typed == expected || self.fold(typed) == self.fold(expected)
+    }
+
+    // Forma canonica di un grafema secondo le regole attive
+    fn fold(&self, grapheme: &str) -> String {
+        let mut folded: String = grapheme.to_string();
+        if self.ascii_punctuation {
+            folded = folded.chars().map(ascii_equivalent).collect();
+        }
+        if self.ignore_diacritics {
+            folded = folded
+                .nfd()
+                .filter(|c| !is_combining_mark(*c))
+                .nfc()
+                .collect();
+        }
+        if self.ignore_case {
+            folded = folded.to_lowercase();
+        }
+        folded
+    }
+}
+
+fn ascii_equivalent(c: char) -> char {
+    match c {
+        '‘' | '’' | '‚' | '‛' | '′' => '\'',
+        '“' | '”' | '„' | '‟' | '«' | '»' | '″' => '"',
+        '‐' | '‑' | '‒' | '–' | '—' | '―' | '−' => '-',
+        _ => c,
+    }
+}
+
+// Spazi e a capo separano le parole
+pub fn is_separator(grapheme: &str) -> bool {
+    grapheme == " " || grapheme == "\n"
+}
+
+// Segni di punteggiatura, ASCII e tipografici
+pub fn is_punctuation(grapheme: &str) -> bool {
+    !grapheme.is_empty()
+        && grapheme
+            .chars()
+            .all(|c| c.is_ascii_punctuation() || "‘’‚‛“”„‟«»‹›‐‑‒–—―…¡¿·".contains(c))
+}
+
+pub fn performance_now() -> Option<f64> {
+    window()
+        .and_then(|win| win.performance())
+        .map(|perf| perf.now())
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct TypingState {
+    // Testo in forma NFC
     pub text: String,
+    pub graphemes: Vec<String>,
+    // Indice del grafema corrente e stato di ogni grafema
     pub current_index: usize,
     pub char_statuses: Vec<CharStatus>,
     pub is_complete: bool,
     pub started: bool,
     pub start_time: Option<f64>,
     pub end_time: Option<f64>,
+    pub error_mode: ErrorMode,
+    pub match_rules: MatchRules,
+    pub input_model: InputModel,
+    // Lettere in più digitate prima del grafema in quella posizione (lo spazio
+    // che chiude la parola), solo nel modello `WordAware`
+    pub overflow: Vec<Vec<String>>,
+    // Tasti sbagliati respinti in modalità `StopOnError`
+    pub mistakes: usize,
+    // Errore fatale in modalità `SuddenDeath`: la frase non accetta altri tasti
+    pub failed: bool,
 }
 
 impl TypingState {
     pub fn new(text: String) -> Self {
-        let char_count = text.chars().count();
+        let text = normalize(&text);
+        let graphemes = graphemes(&text);
         Self {
+            char_statuses: vec![CharStatus::Pending; graphemes.len()],
+            overflow: vec![Vec::new(); graphemes.len()],
             text,
+            graphemes,
             current_index: 0,
-            char_statuses: vec![CharStatus::Pending; char_count],
             is_complete: false,
             started: false,
             start_time: None,
             end_time: None,
+            error_mode: ErrorMode::default(),
+            match_rules: MatchRules::default(),
+            input_model: InputModel::default(),
+            mistakes: 0,
+            failed: false,
         }
     }
 
+    pub fn with_rules(
+        text: String,
+        error_mode: ErrorMode,
+        match_rules: MatchRules,
+        input_model: InputModel,
+    ) -> Self {
+        let mut state = Self {
+            error_mode,
+            match_rules,
+            input_model,
+            ..Self::new(text)
+        };
+        state.skip_ahead();
+        state
+    }
+
+    // Il cursore oltrepassa da solo i grafemi saltati dalle regole attive
+    fn skip_ahead(&mut self) {
+        while self.is_skipped(self.current_index) {
+            self.char_statuses[self.current_index] = CharStatus::Correct;
+            self.current_index += 1;
+        }
+    }
+
+    fn is_skipped(&self, index: usize) -> bool {
+        let Some(grapheme) = self.graphemes.get(index) else {
+            return false;
+        };
+        (self.match_rules.skip_punctuation && is_punctuation(grapheme))
+            || (self.match_rules.skip_indent && self.is_indent(index))
+    }
+
+    // Spazio a inizio riga, prima di qualsiasi altro grafema
+    fn is_indent(&self, index: usize) -> bool {
+        self.graphemes.get(index).is_some_and(|g| g == " ")
+            && self.graphemes[..index]
+                .iter()
+                .rev()
+                .take_while(|g| *g != "\n")
+                .all(|g| g == " ")
+    }
+
+    // Spazi mancanti al prossimo punto di tabulazione, a partire dal cursore
+    pub fn tab_width(&self) -> usize {
+        let column = self.graphemes[..self.current_index]
+            .iter()
+            .rev()
+            .take_while(|g| *g != "\n")
+            .count();
+        TAB_WIDTH - column % TAB_WIDTH
+    }
+
+    // Uno spazio a inizio testo è un tasto di troppo, tranne quando apre l'indentazione
+    pub fn ignores_key(&self, key: &str) -> bool {
+        self.failed || (self.current_index == 0 && key == " " && !self.is_indent(0))
+    }
+
+    // Testo inserito nel campo in un colpo solo (IME, tasti morti, incolla):
+    // normalizzato e passato a `type_grapheme` un grafema alla volta
+    pub fn commit_text(&mut self, text: &str, mut type_grapheme: impl FnMut(&mut Self, &str)) {
+        for grapheme in normalize(text).graphemes(true) {
+            type_grapheme(self, grapheme);
+            // Il resto del testo non appartiene a questa frase
+            if self.is_complete || self.failed {
+                break;
+            }
+        }
+    }
+
+    // `key` è un singolo grafema digitato.
     // Ritorna true se il carattere è corretto, false se è sbagliato
     pub fn handle_key(&mut self, key: &str) -> bool {
-        if self.is_complete {
+        self.handle_key_at(key, performance_now())
+    }
+
+    // Come `handle_key`, con l'istante del tasto fornito dall'esterno
+    // (es. riproduzione di una partita registrata)
+    pub fn handle_key_at(&mut self, key: &str, now: Option<f64>) -> bool {
+        if self.is_complete || self.failed {
             return true;
         }
 
         if !self.started {
             self.started = true;
-            if let Some(win) = window() {
-                if let Some(perf) = win.performance() {
-                    self.start_time = Some(perf.now());
-                }
-            }
+            self.start_time = now;
         }
 
-        let chars: Vec<char> = self.text.chars().collect();
-
-        if self.current_index >= chars.len() {
+        if self.current_index >= self.graphemes.len() {
             return true;
         }
 
-        let expected_char = chars[self.current_index];
-        let input_char = key.chars().next().unwrap_or('\0');
+        let key = normalize(key);
+        let is_correct = self
+            .match_rules
+            .matches(&key, &self.graphemes[self.current_index]);
 
-        let is_correct = input_char == expected_char;
+        // Negli altri modi di gestione errori decide `error_mode`
+        if !is_correct && self.input_model.effective(self.error_mode) == InputModel::WordAware {
+            if is_separator(&self.graphemes[self.current_index]) {
+                if self.overflow[self.current_index].len() < MAX_OVERFLOW {
+                    self.overflow[self.current_index].push(key);
+                }
+                return false;
+            }
+            if key == " " {
+                return self.skip_word(now);
+            }
+        }
 
         if is_correct {
             self.char_statuses[self.current_index] = CharStatus::Correct;
         } else {
             self.char_statuses[self.current_index] = CharStatus::Incorrect;
+            match self.error_mode {
+                ErrorMode::Advance => {}
+                ErrorMode::StopOnError => {
+                    self.mistakes += 1;
+                    return false;
+                }
+                ErrorMode::SuddenDeath => {
+                    self.failed = true;
+                    self.end_time = now;
+                }
+            }
         }
 
         self.current_index += 1;
+        if !self.failed {
+            self.skip_ahead();
+        }
 
-        if self.current_index >= chars.len() {
+        if self.current_index >= self.graphemes.len() {
             self.is_complete = true;
-            if let Some(win) = window() {
-                if let Some(perf) = win.performance() {
-                    self.end_time = Some(perf.now());
-                }
-            }
+            self.end_time = now;
         }
 
         is_correct
     }
 
+    // Spazio a metà parola: il resto della parola è mancato e si passa alla successiva
+    fn skip_word(&mut self, now: Option<f64>) -> bool {
+        let mut start = self.current_index;
+        while start > 0 && self.is_skipped(start - 1) {
+            start -= 1;
+        }
+        // A inizio parola lo spazio non salta nulla
+        if start == 0 || is_separator(&self.graphemes[start - 1]) {
+            return true;
+        }
+
+        while self.current_index < self.graphemes.len()
+            && !is_separator(&self.graphemes[self.current_index])
+        {
+            self.char_statuses[self.current_index] = CharStatus::Missed;
+            self.current_index += 1;
+        }
+        if self.current_index < self.graphemes.len() {
+            self.char_statuses[self.current_index] = CharStatus::Correct;
+            self.current_index += 1;
+            self.skip_ahead();
+        }
+
+        if self.current_index >= self.graphemes.len() {
+            self.is_complete = true;
+            self.end_time = now;
+        }
+
+        false
+    }
+
+    // Il separatore in `index` chiude una parola digitata: né saltata né fatta
+    // solo di spazi e a capo, come l'indentazione dopo un a capo
+    pub fn completes_word(&self, index: usize) -> bool {
+        if !is_separator(&self.graphemes[index])
+            || self.char_statuses[index] != CharStatus::Correct
+            || self.is_skipped(index)
+        {
+            return false;
+        }
+        // La punteggiatura saltata in coda alla parola non è stata digitata
+        let mut before = index;
+        while before > 0 && self.is_skipped(before - 1) {
+            before -= 1;
+        }
+        before > 0
+            && !is_separator(&self.graphemes[before - 1])
+            && matches!(
+                self.char_statuses[before - 1],
+                CharStatus::Correct | CharStatus::Incorrect
+            )
+    }
+
+    // Grafema che un backspace cancellerebbe, oltre la punteggiatura saltata.
+    // Nessuno se prima vanno cancellate le lettere in più
+    pub fn backspace_target(&self) -> Option<usize> {
+        if self
+            .overflow
+            .get(self.current_index)
+            .is_some_and(|extra| !extra.is_empty())
+        {
+            return None;
+        }
+        let mut index = self.current_index;
+        while index > 0 && self.is_skipped(index - 1) {
+            index -= 1;
+        }
+        let mut target = index.checked_sub(1)?;
+        // Tornando su una parola saltata si riparte dal primo grafema mancato
+        while target > 0 && self.char_statuses[target - 1] == CharStatus::Missed {
+            target -= 1;
+        }
+        Some(target)
+    }
+
+    // Inizio della parola che Ctrl/Alt+Backspace cancellerebbe: gli spazi
+    // subito prima del cursore e la parola che li precede
+    pub fn word_backspace_target(&self) -> Option<usize> {
+        let mut index = self.current_index;
+        while index > 0 && (is_separator(&self.graphemes[index - 1]) || self.is_skipped(index - 1))
+        {
+            index -= 1;
+        }
+        while index > 0 && !is_separator(&self.graphemes[index - 1]) {
+            index -= 1;
+        }
+        // La punteggiatura saltata a inizio parola resta già superata
+        while index < self.current_index && self.is_skipped(index) {
+            index += 1;
+        }
+        (index < self.current_index).then_some(index)
+    }
+
     pub fn handle_backspace(&mut self) -> bool {
-        if self.current_index > 0 {
-            self.current_index -= 1;
-            self.char_statuses[self.current_index] = CharStatus::Pending;
-            true
-        } else {
-            false
+        if !self.failed
+            && let Some(extra) = self.overflow.get_mut(self.current_index)
+            && extra.pop().is_some()
+        {
+            return true;
+        }
+        let target = self.backspace_target();
+        self.delete_to(target)
+    }
+
+    pub fn handle_word_backspace(&mut self) -> bool {
+        if self.failed {
+            return false;
+        }
+        let had_overflow = self
+            .overflow
+            .get_mut(self.current_index)
+            .is_some_and(|extra| !std::mem::take(extra).is_empty());
+        let target = self.word_backspace_target();
+        if let Some(target) = target {
+            for extra in &mut self.overflow[target..self.current_index] {
+                extra.clear();
+            }
+        }
+        self.delete_to(target) || had_overflow
+    }
+
+    // Riporta il cursore su `target`, azzerando i grafemi cancellati
+    fn delete_to(&mut self, target: Option<usize>) -> bool {
+        if self.failed {
+            return false;
+        }
+        // Il tasto sbagliato respinto sotto il cursore non resta evidenziato
+        if let Some(status) = self.char_statuses.get_mut(self.current_index) {
+            *status = CharStatus::Pending;
+        }
+        match target {
+            Some(target) => {
+                for status in &mut self.char_statuses[target..self.current_index] {
+                    *status = CharStatus::Pending;
+                }
+                self.current_index = target;
+                true
+            }
+            None => false,
         }
     }
 
@@ -104,8 +640,19 @@ impl TypingState {
         }
     }
 
+    // Grafemi rimasti sbagliati o mancati, senza contare quelli corretti dopo
+    pub fn uncorrected_errors(&self) -> usize {
+        self.char_statuses
+            .iter()
+            .filter(|s| matches!(s, CharStatus::Incorrect | CharStatus::Missed))
+            .count()
+    }
+
+    // I tasti respinti e le lettere in più contano come tentativi sbagliati,
+    // i grafemi mancati come grafemi non corretti
     pub fn get_accuracy(&self) -> f64 {
-        let total = self.char_statuses.len() as f64;
+        let extra: usize = self.overflow.iter().map(Vec::len).sum();
+        let total = (self.char_statuses.len() + self.mistakes + extra) as f64;
         if total == 0.0 {
             return 100.0;
         }
@@ -121,106 +668,211 @@ impl TypingState {
 #[component]
 pub fn TypingEngine(
     text: String,
-    #[prop(optional)] on_complete: Option<Callback<(f64, f64)>>,
+    // Frase conclusa: WPM, accuracy ed errori rimasti nel testo
+    #[prop(optional)] on_complete: Option<Callback<(f64, f64, usize)>>,
     #[prop(optional)] on_char_typed: Option<Callback<()>>,
     #[prop(optional)] on_char_error: Option<Callback<()>>,
     #[prop(optional)] on_word_typed: Option<Callback<()>>,
     #[prop(optional)] on_word_deleted: Option<Callback<()>>,
+    // Ogni input accettato, con la posizione del cursore dopo l'input
+    #[prop(optional)] on_keystroke: Option<Callback<(KeyInput, usize)>>,
+    // Posizione del cursore fantasma nella frase, se presente
+    #[prop(optional, into)] ghost_index: Option<Signal<Option<usize>>>,
+    // Reazione ai tasti sbagliati, `Advance` se non indicata
+    #[prop(optional)] error_mode: ErrorMode,
+    // Regole di confronto permissive, confronto esatto se non indicate
+    #[prop(optional)] match_rules: MatchRules,
+    // Allineamento dei tasti al testo, `Linear` se non indicato
+    #[prop(optional)] input_model: InputModel,
+    // Aspetto del cursore, una linea se non indicato
+    #[prop(optional)] caret_style: CaretStyle,
+    // Notificato quando un errore chiude la frase in modalità `SuddenDeath`
+    #[prop(optional)] on_fail: Option<Callback<()>>,
+    // Esito di ogni tasto: indice del grafema atteso e se è stato digitato giusto
+    #[prop(optional)] on_key_result: Option<Callback<(usize, bool)>>,
 ) -> impl IntoView {
-    let (state, set_state) = signal(TypingState::new(text.clone()));
+    let multiline = text.contains('\n');
+
+    // Lo stato non è reattivo: a ogni tasto si aggiornano solo le celle cambiate
+    let initial = TypingState::with_rules(text.clone(), error_mode, match_rules, input_model);
+    let cells = TextCells::new(&initial);
+    let state = StoredValue::new(initial);
+
+    // Applica un input allo stato e riallinea le celle tra il vecchio e il nuovo cursore
+    let update_state = move |apply: &mut dyn FnMut(&mut TypingState)| {
+        let from = state.with_value(|s| s.current_index);
+        state.update_value(|s| apply(s));
+        state.with_value(|s| cells.sync(s, from));
+    };
     let input_ref = NodeRef::<Input>::new();
 
-    Effect::new({
-        let input_ref = input_ref.clone();
-        move || {
-            if let Some(input) = input_ref.get() {
-                let _ = input.focus();
-            }
+    Effect::new(move || {
+        if let Some(input) = input_ref.get() {
+            let _ = input.focus();
         }
     });
 
-    let handle_input = move |_| {
+    // Elabora un singolo grafema digitato, come se fosse un tasto
+    let type_grapheme = move |s: &mut TypingState, grapheme: &str| {
+        if s.ignores_key(grapheme) {
+            return;
+        }
+
+        let was_complete = s.is_complete;
+        let index_before = s.current_index;
+        let is_correct = s.handle_key(grapheme);
+
+        // A frase conclusa i tasti non hanno effetto e non vanno registrati
+        if !was_complete && let Some(callback) = on_keystroke {
+            callback.run((KeyInput::from_grapheme(grapheme), s.current_index));
+        }
+
+        if !was_complete && let Some(callback) = on_key_result {
+            callback.run((index_before, is_correct));
+        }
+
+        if let Some(callback) = on_char_typed {
+            callback.run(());
+        }
+
+        // Nuovo: notifica se il carattere è sbagliato
+        if !is_correct && let Some(callback) = on_char_error {
+            callback.run(());
+        }
+
+        if s.failed {
+            if let Some(callback) = on_fail {
+                callback.run(());
+            }
+            return;
+        }
+
+        // Con `StopOnError` un tasto respinto non completa parole
+        if let Some(callback) = on_word_typed {
+            (index_before..s.current_index)
+                .filter(|&i| s.completes_word(i))
+                .for_each(|_| callback.run(()));
+        }
+
+        if s.is_complete {
+            if !s.text.ends_with(char::is_whitespace)
+                && s.char_statuses.last() != Some(&CharStatus::Missed)
+                && let Some(callback) = on_word_typed
+            {
+                callback.run(());
+            }
+
+            if let Some(callback) = on_complete
+                && let Some(wpm) = s.get_wpm()
+            {
+                let accuracy = s.get_accuracy();
+                callback.run((wpm, accuracy, s.uncorrected_errors()));
+            }
+        }
+    };
+
+    // Durante una composizione (IME o tasti morti) il testo nel campo è provvisorio
+    let composing = StoredValue::new(false);
+
+    // Passa all'engine il testo inserito nel campo, grafema per grafema, e lo svuota
+    let commit_input = move || {
         if let Some(input) = input_ref.get() {
             let value = input.value();
             if value.is_empty() {
                 return;
             }
 
-            let last_char = value.chars().last().unwrap();
+            update_state(&mut |s| s.commit_text(&value, type_grapheme));
 
-            set_state.update(|s| {
-                if s.current_index == 0 && last_char == ' ' {
-                    return;
-                }
+            input.set_value("");
+        }
+    };
 
-                let is_correct = s.handle_key(&last_char.to_string());
+    let handle_input = move |_| {
+        if composing.get_value() {
+            return;
+        }
+        commit_input();
+    };
 
-                if let Some(callback) = on_char_typed {
-                    callback.run(());
-                }
+    let handle_composition_start = move |_: CompositionEvent| {
+        composing.set_value(true);
+    };
 
-                // Nuovo: notifica se il carattere è sbagliato
-                if !is_correct {
-                    if let Some(callback) = on_char_error {
-                        callback.run(());
-                    }
-                }
+    // A composizione conclusa il campo contiene il carattere definitivo (es. "é")
+    let handle_composition_end = move |_: CompositionEvent| {
+        composing.set_value(false);
+        commit_input();
+    };
 
-                if s.current_index > 0 {
-                    let processed_char_index = s.current_index - 1;
-                    let text_char = s.text.chars().nth(processed_char_index).unwrap();
-                    let char_status = &s.char_statuses[processed_char_index];
+    let handle_keydown = move |ev: KeyboardEvent| {
+        // Tasti morti e IME gestiscono da soli i tasti durante la composizione
+        if composing.get_value() || ev.is_composing() {
+            return;
+        }
 
-                    if text_char == ' ' && *char_status == CharStatus::Correct {
-                        if let Some(callback) = on_word_typed {
-                            callback.run(());
-                        }
-                    }
+        // Nei testi su più righe Invio digita l'a capo e Tab l'indentazione
+        if multiline && (ev.key() == "Enter" || ev.key() == "Tab") {
+            ev.prevent_default();
+            let tab = ev.key() == "Tab";
+            update_state(&mut |s| {
+                if !tab {
+                    type_grapheme(s, "\n");
+                    return;
                 }
-
-                if s.is_complete {
-                    if !s.text.ends_with(' ') {
-                        if let Some(callback) = on_word_typed {
-                            callback.run(());
-                        }
+                // Spazi fino al prossimo punto di tabulazione, finché il testo ne prevede
+                for typed in 0..s.tab_width() {
+                    if typed > 0 && s.graphemes.get(s.current_index).is_none_or(|g| g != " ") {
+                        break;
                     }
-
-                    if let Some(callback) = on_complete {
-                        if let Some(wpm) = s.get_wpm() {
-                            let accuracy = s.get_accuracy();
-                            callback.run((wpm, accuracy));
-                        }
+                    type_grapheme(s, " ");
+                    if s.is_complete || s.failed {
+                        break;
                     }
                 }
             });
-
-            input.set_value("");
         }
-    };
 
-    let handle_keydown = move |ev: KeyboardEvent| {
         if ev.key() == "Backspace" {
             ev.prevent_default();
-            set_state.update(|s| {
-                if s.current_index > 0 {
-                    let index_to_delete = s.current_index - 1;
-                    let char_to_delete = s.text.chars().nth(index_to_delete).unwrap();
-                    let status_of_char = &s.char_statuses[index_to_delete];
-
-                    if char_to_delete == ' ' && *status_of_char == CharStatus::Correct {
-                        if let Some(callback) = on_word_deleted {
-                            callback.run(());
-                        }
-                    }
+            // Ctrl+Backspace (Windows, Linux) o Alt+Backspace (macOS) cancellano la parola
+            let whole_word = ev.ctrl_key() || ev.alt_key();
+            update_state(&mut |s| {
+                if s.failed {
+                    return;
+                }
+
+                let target = if whole_word {
+                    s.word_backspace_target()
+                } else {
+                    s.backspace_target()
+                };
+
+                // Ogni spazio corretto cancellato annulla una parola completata
+                if let Some(target) = target
+                    && let Some(callback) = on_word_deleted
+                {
+                    (target..s.current_index)
+                        .filter(|&i| s.completes_word(i))
+                        .for_each(|_| callback.run(()));
                 }
 
-                s.handle_backspace();
+                let input = if whole_word {
+                    s.handle_word_backspace();
+                    KeyInput::DeleteWord
+                } else {
+                    s.handle_backspace();
+                    KeyInput::Backspace
+                };
+
+                if let Some(callback) = on_keystroke {
+                    callback.run((input, s.current_index));
+                }
             });
         }
 
         if ev.repeat() {
             ev.prevent_default();
-            return;
         }
     };
 
@@ -239,65 +891,450 @@ pub fn TypingEngine(
                     class="typing-input"
                     on:input=handle_input
                     on:keydown=handle_keydown
+                    on:compositionstart=handle_composition_start
+                    on:compositionend=handle_composition_end
                     autocomplete="off"
                     autocapitalize="off"
                     spellcheck="false"
                 />
-                <div class="typing-text">
-                    {move || {
-                        let s = state.get();
-                        let chars: Vec<char> = s.text.chars().collect();
-
-                        let mut words = Vec::new();
-                        let mut current_word_chars = Vec::new();
-                        let mut char_index = 0;
-
-                        for ch in chars.iter() {
-                            if *ch == ' ' {
-                                if !current_word_chars.is_empty() {
-                                    words.push((current_word_chars.clone(), false));
-                                    current_word_chars.clear();
-                                }
-                                words.push((vec![(char_index, ' ')], true));
-                                char_index += 1;
-                            } else {
-                                current_word_chars.push((char_index, *ch));
-                                char_index += 1;
-                            }
-                        }
-
-                        if !current_word_chars.is_empty() {
-                            words.push((current_word_chars, false));
-                        }
-
-                        words.into_iter().map(|(word_chars, _)| {
-                            view! {
-                                <span class="typing-word">
-                                    {word_chars.into_iter().map(|(i, ch)| {
-                                        let status = &s.char_statuses[i];
-                                        let is_current = i == s.current_index;
-
-                                        let mut class = match status {
-                                            CharStatus::Pending => "typing-char typing-char--pending",
-                                            CharStatus::Correct => "typing-char typing-char--correct",
-                                            CharStatus::Incorrect => "typing-char typing-char--incorrect",
-                                        }
-                                        .to_string();
-
-                                        if is_current {
-                                            class.push_str(" typing-char--current");
-                                        }
-
-                                        let ch_display = if ch == ' ' { '\u{00A0}' } else { ch };
-
-                                        view! { <span class=class>{ch_display}</span> }
-                                    }).collect_view()}
-                                </span>
-                            }
-                        }).collect_view()
-                    }}
-                </div>
+                <TypingText
+                    cells=cells
+                    caret_style=caret_style
+                    multiline=multiline
+                    ghost_index=Signal::derive(move || ghost_index.and_then(|g| g.get()))
+                />
             </div>
         </div>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SNIPPET: &str = "fn main() {\n    x;\n}";
+
+    fn indent_state(text: &str) -> TypingState {
+        TypingState::with_rules(
+            text.to_string(),
+            ErrorMode::default(),
+            MatchRules {
+                skip_indent: true,
+                ..MatchRules::default()
+            },
+            InputModel::default(),
+        )
+    }
+
+    #[test]
+    fn newline_is_never_skipped_as_indent() {
+        let state = indent_state(SNIPPET);
+        assert!(!state.is_skipped(11));
+        assert!(!state.is_skipped(18));
+    }
+
+    #[test]
+    fn leading_spaces_are_skipped_as_indent() {
+        let state = indent_state(SNIPPET);
+        assert!((12..16).all(|index| state.is_skipped(index)));
+        assert!(!state.is_skipped(16));
+    }
+
+    #[test]
+    fn inner_spaces_are_not_indent() {
+        let state = indent_state(SNIPPET);
+        assert!(!state.is_skipped(2));
+        assert!(!state.is_skipped(9));
+    }
+
+    #[test]
+    fn leading_spaces_of_the_first_line_are_skipped() {
+        let state = indent_state("  x");
+        assert_eq!(state.current_index, 2);
+    }
+
+    // Digita i tasti come `TypingEngine` e conta le parole completate
+    fn type_keys(state: &mut TypingState, keys: &str) -> usize {
+        let mut words = 0;
+        for key in keys.graphemes(true) {
+            if state.ignores_key(key) {
+                continue;
+            }
+            let before = state.current_index;
+            state.handle_key_at(key, None);
+            words += (before..state.current_index)
+                .filter(|&i| state.completes_word(i))
+                .count();
+        }
+        words
+    }
+
+    #[test]
+    fn enter_with_skipped_indent_completes_one_word() {
+        let mut state = indent_state(SNIPPET);
+        type_keys(&mut state, "fn main() {");
+        assert_eq!(type_keys(&mut state, "\n"), 1);
+        assert_eq!(state.current_index, 16);
+    }
+
+    #[test]
+    fn typed_indent_does_not_complete_words() {
+        let mut state = TypingState::new(SNIPPET.to_string());
+        type_keys(&mut state, "fn main() {");
+        assert_eq!(type_keys(&mut state, "\n    "), 1);
+        assert_eq!(state.current_index, 16);
+    }
+
+    #[test]
+    fn code_rules_never_skip_punctuation() {
+        let rules = MatchRules {
+            skip_punctuation: true,
+            skip_indent: true,
+            ..MatchRules::default()
+        }
+        .for_code();
+        assert!(!rules.skip_punctuation);
+        assert!(rules.skip_indent);
+
+        let state = TypingState::with_rules(
+            "{x}".to_string(),
+            ErrorMode::default(),
+            rules,
+            InputModel::default(),
+        );
+        assert_eq!(state.current_index, 0);
+    }
+
+    #[test]
+    fn indent_can_be_typed_on_the_first_line() {
+        let mut state = TypingState::new("  x".to_string());
+        assert!(!state.ignores_key(" "));
+        assert_eq!(type_keys(&mut state, "  x"), 0);
+        assert!(state.is_complete);
+
+        let state = TypingState::new("x y".to_string());
+        assert!(state.ignores_key(" "));
+    }
+
+    fn state_with(text: &str, error_mode: ErrorMode) -> TypingState {
+        TypingState::with_rules(
+            text.to_string(),
+            error_mode,
+            MatchRules::default(),
+            InputModel::default(),
+        )
+    }
+
+    #[test]
+    fn stop_on_error_keeps_the_caret_on_a_rejected_key() {
+        let mut state = state_with("ab", ErrorMode::StopOnError);
+        assert!(!state.handle_key_at("x", Some(0.0)));
+        assert_eq!(state.current_index, 0);
+        assert_eq!(state.mistakes, 1);
+        assert_eq!(state.char_statuses[0], CharStatus::Incorrect);
+
+        // Il tasto giusto riprende dallo stesso grafema
+        assert!(state.handle_key_at("a", Some(1.0)));
+        assert!(state.handle_key_at("b", Some(2.0)));
+        assert!(state.is_complete);
+        assert_eq!(state.char_statuses, [CharStatus::Correct; 2]);
+        assert!(state.get_accuracy() < 100.0);
+    }
+
+    #[test]
+    fn sudden_death_fails_on_the_first_error_and_stops_input() {
+        let mut state = state_with("abc", ErrorMode::SuddenDeath);
+        assert!(state.handle_key_at("a", Some(0.0)));
+        assert!(!state.handle_key_at("x", Some(5.0)));
+        assert!(state.failed);
+        assert_eq!(state.end_time, Some(5.0));
+        assert!(!state.is_complete);
+
+        let index = state.current_index;
+        state.handle_key_at("c", Some(6.0));
+        assert_eq!(state.current_index, index);
+        assert!(!state.handle_backspace());
+        assert!(!state.handle_word_backspace());
+        assert!(state.ignores_key("c"));
+    }
+
+    fn commit(state: &mut TypingState, text: &str) -> Vec<String> {
+        let mut typed = Vec::new();
+        state.commit_text(text, |s, grapheme| {
+            typed.push(grapheme.to_string());
+            s.handle_key_at(grapheme, None);
+        });
+        typed
+    }
+
+    #[test]
+    fn dead_key_accent_matches_in_either_form() {
+        for accent in ["e\u{301}", "\u{e9}"] {
+            let mut state = TypingState::new("perch\u{e9}".to_string());
+            commit(&mut state, "perch");
+            assert_eq!(commit(&mut state, accent), ["\u{e9}"]);
+            assert!(state.is_complete);
+            assert!(
+                state
+                    .char_statuses
+                    .iter()
+                    .all(|s| *s == CharStatus::Correct)
+            );
+        }
+    }
+
+    #[test]
+    fn decomposed_text_is_normalized_to_one_cell_per_letter() {
+        let state = TypingState::new("e\u{300} qui".to_string());
+        assert_eq!(state.graphemes.len(), 5);
+        assert_eq!(state.graphemes[0], "\u{e8}");
+    }
+
+    #[test]
+    fn emoji_zwj_sequence_fills_one_cell() {
+        let coder = "\u{1f469}\u{200d}\u{1f4bb}";
+        let mut state = TypingState::new(format!("io {}", coder));
+        assert_eq!(state.graphemes.len(), 4);
+
+        assert_eq!(commit(&mut state, &format!("io {}", coder)).len(), 4);
+        assert!(state.is_complete);
+        assert_eq!(state.char_statuses[3], CharStatus::Correct);
+        assert_eq!(
+            KeyInput::from_grapheme(coder),
+            KeyInput::Grapheme(coder.into())
+        );
+    }
+
+    #[test]
+    fn commit_stops_at_the_end_of_the_phrase() {
+        let mut state = TypingState::new("ok".to_string());
+        assert_eq!(commit(&mut state, "ok altro"), ["o", "k"]);
+    }
+
+    fn skip_punctuation_state(text: &str) -> TypingState {
+        TypingState::with_rules(
+            text.to_string(),
+            ErrorMode::default(),
+            MatchRules {
+                skip_punctuation: true,
+                ..MatchRules::default()
+            },
+            InputModel::default(),
+        )
+    }
+
+    #[test]
+    fn word_backspace_in_the_middle_of_a_word() {
+        let mut state = TypingState::new("ciao mondo".to_string());
+        type_keys(&mut state, "ciao mo");
+        assert_eq!(state.word_backspace_target(), Some(5));
+        assert!(state.handle_word_backspace());
+        assert_eq!(state.current_index, 5);
+        assert!(
+            state.char_statuses[5..]
+                .iter()
+                .all(|s| *s == CharStatus::Pending)
+        );
+        assert_eq!(state.char_statuses[4], CharStatus::Correct);
+    }
+
+    #[test]
+    fn word_backspace_right_after_a_space_deletes_the_previous_word() {
+        let mut state = TypingState::new("ciao mondo".to_string());
+        type_keys(&mut state, "ciao ");
+        assert_eq!(state.word_backspace_target(), Some(0));
+        assert!(state.handle_word_backspace());
+        assert_eq!(state.current_index, 0);
+    }
+
+    #[test]
+    fn word_backspace_crosses_skipped_punctuation() {
+        let mut state = skip_punctuation_state("«ciao», mondo");
+        assert_eq!(state.current_index, 1);
+        type_keys(&mut state, "ciao ");
+        assert_eq!(state.current_index, 8);
+
+        // La punteggiatura saltata a inizio parola resta superata
+        assert_eq!(state.word_backspace_target(), Some(1));
+        assert!(state.handle_word_backspace());
+        assert_eq!(state.current_index, 1);
+        assert_eq!(state.char_statuses[0], CharStatus::Correct);
+    }
+
+    #[test]
+    fn word_backspace_at_the_start_does_nothing() {
+        let mut state = TypingState::new("ciao".to_string());
+        assert_eq!(state.word_backspace_target(), None);
+        assert!(!state.handle_word_backspace());
+        assert_eq!(state.current_index, 0);
+    }
+
+    fn rules_state(text: &str, rules: MatchRules) -> TypingState {
+        TypingState::with_rules(
+            text.to_string(),
+            ErrorMode::default(),
+            rules,
+            InputModel::default(),
+        )
+    }
+
+    #[test]
+    fn ignore_diacritics_accepts_the_bare_letter() {
+        let rules = MatchRules {
+            ignore_diacritics: true,
+            ..MatchRules::default()
+        };
+        assert!(rules_state("è", rules).handle_key_at("e", None));
+        assert!(!rules_state("è", MatchRules::default()).handle_key_at("e", None));
+        assert!(!rules_state("è", rules).handle_key_at("E", None));
+    }
+
+    #[test]
+    fn ignore_case_accepts_either_case() {
+        let rules = MatchRules {
+            ignore_case: true,
+            ..MatchRules::default()
+        };
+        assert!(rules_state("Roma", rules).handle_key_at("r", None));
+        assert!(rules_state("roma", rules).handle_key_at("R", None));
+        assert!(!rules_state("Roma", MatchRules::default()).handle_key_at("r", None));
+    }
+
+    #[test]
+    fn ascii_punctuation_accepts_plain_quotes_and_dashes() {
+        let rules = MatchRules {
+            ascii_punctuation: true,
+            ..MatchRules::default()
+        };
+        for (expected, typed) in [("’", "'"), ("«", "\""), ("—", "-")] {
+            assert!(rules_state(expected, rules).handle_key_at(typed, None));
+            assert!(!rules_state(expected, MatchRules::default()).handle_key_at(typed, None));
+        }
+    }
+
+    #[test]
+    fn skip_punctuation_marks_punctuation_as_typed() {
+        let mut state = skip_punctuation_state("sì, no!");
+        type_keys(&mut state, "sì no");
+        assert!(state.is_complete);
+        assert_eq!(state.char_statuses[2], CharStatus::Correct);
+        assert_eq!(state.get_accuracy(), 100.0);
+    }
+
+    #[test]
+    fn rules_combine() {
+        let rules = MatchRules {
+            ignore_diacritics: true,
+            ignore_case: true,
+            ascii_punctuation: true,
+            skip_punctuation: false,
+            skip_indent: false,
+        };
+        let mut state = rules_state("Perché l’Età", rules);
+        type_keys(&mut state, "perche l'eta");
+        assert!(state.is_complete);
+        assert!(
+            state
+                .char_statuses
+                .iter()
+                .all(|s| *s == CharStatus::Correct)
+        );
+    }
+
+    fn word_aware_state(text: &str, error_mode: ErrorMode) -> TypingState {
+        TypingState::with_rules(
+            text.to_string(),
+            error_mode,
+            MatchRules::default(),
+            InputModel::WordAware,
+        )
+    }
+
+    #[test]
+    fn word_aware_keeps_extra_letters_on_the_word() {
+        let mut state = word_aware_state("ciao mondo", ErrorMode::Advance);
+        type_keys(&mut state, "ciaooo");
+        assert_eq!(state.current_index, 4);
+        assert_eq!(state.overflow[4], ["o", "o"]);
+
+        // Lo spazio chiude la parola, che resta completata
+        assert_eq!(type_keys(&mut state, " "), 1);
+        assert_eq!(state.current_index, 5);
+        assert!(state.get_accuracy() < 100.0);
+    }
+
+    #[test]
+    fn word_aware_overflow_is_capped() {
+        let mut state = word_aware_state("o k", ErrorMode::Advance);
+        type_keys(&mut state, "o");
+        type_keys(&mut state, &"x".repeat(MAX_OVERFLOW + 5));
+        assert_eq!(state.overflow[1].len(), MAX_OVERFLOW);
+    }
+
+    #[test]
+    fn space_mid_word_marks_the_rest_as_missed() {
+        let mut state = word_aware_state("ciao mondo", ErrorMode::Advance);
+        assert_eq!(type_keys(&mut state, "ci "), 0);
+        assert_eq!(state.current_index, 5);
+        assert_eq!(
+            state.char_statuses[..5],
+            [
+                CharStatus::Correct,
+                CharStatus::Correct,
+                CharStatus::Missed,
+                CharStatus::Missed,
+                CharStatus::Correct,
+            ]
+        );
+
+        // Backspace torna sul primo grafema mancato
+        assert_eq!(state.backspace_target(), Some(2));
+    }
+
+    #[test]
+    fn backspace_removes_extra_letters_first() {
+        let mut state = word_aware_state("ciao mondo", ErrorMode::Advance);
+        type_keys(&mut state, "ciaoxy");
+        assert_eq!(state.backspace_target(), None);
+
+        assert!(state.handle_backspace());
+        assert_eq!(state.overflow[4], ["x"]);
+        assert!(state.handle_backspace());
+        assert!(state.overflow[4].is_empty());
+        assert_eq!(state.current_index, 4);
+
+        // Senza lettere in più il backspace torna sulla parola
+        assert!(state.handle_backspace());
+        assert_eq!(state.current_index, 3);
+    }
+
+    #[test]
+    fn word_aware_needs_the_advance_error_mode() {
+        assert_eq!(
+            InputModel::WordAware.effective(ErrorMode::StopOnError),
+            InputModel::Linear
+        );
+        let mut state = word_aware_state("ab", ErrorMode::StopOnError);
+        type_keys(&mut state, "a ");
+        assert_eq!(state.current_index, 1);
+        assert!(state.overflow.iter().all(Vec::is_empty));
+        assert_eq!(state.mistakes, 1);
+    }
+
+    #[test]
+    fn uncorrected_errors_count_cells_not_keystrokes() {
+        // Errore corretto con backspace: non resta nulla
+        let mut state = TypingState::new("ciao".to_string());
+        type_keys(&mut state, "cx");
+        state.handle_backspace();
+        type_keys(&mut state, "iao");
+        assert_eq!(state.uncorrected_errors(), 0);
+        assert_eq!(state.get_accuracy(), 100.0);
+
+        // Lettere in più e grafemi mancati: solo i secondi sono errori nel testo
+        let mut state = word_aware_state("ciao mondo", ErrorMode::Advance);
+        type_keys(&mut state, "ciaoxx mx ");
+        assert!(state.is_complete);
+        assert_eq!(state.uncorrected_errors(), 4);
+    }
+}
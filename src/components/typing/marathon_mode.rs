@@ -1,10 +1,22 @@
 // src/components/typing/marathon_mode.rs
 //
-use crate::components::typing::{ComboPopup, ComboType, MetricsBar, TypingEngine};
+use crate::augment::Augmentations;
+use crate::components::typing::engine::KeyInput;
+use crate::components::typing::{ComboPopup, ComboType, MetricsBar, TypingEngine, combo_badge};
+use crate::components::{Leaderboard, ResultCardDownload, ShareButton};
+use crate::leaderboard_store::use_leaderboard;
+use crate::profile_store::use_profiles;
+use crate::recording::{
+    GhostDelta, GhostRun, GhostTrack, Replay, RunRecorder, load_ghost, phrase_offsets, save_ghost,
+};
+use crate::result_card::ResultCard;
 use crate::settings_store::use_settings;
-use crate::stats_store::{GameMode as StatsGameMode, use_stats};
+use crate::share::{ResultSummary, random_seed};
+use crate::stats_store::{GameMode as StatsGameMode, SessionRecord, run_score, use_stats};
 use leptos::prelude::*;
-use rand::rngs::OsRng;
+use leptos_router::hooks::use_query_map;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 use serde::Deserialize;
 use std::time::Duration;
@@ -20,13 +32,15 @@ fn load_phrases_from_content(json_content: &str) -> Vec<String> {
     data.phrases
 }
 
-fn shuffle_phrases(phrases: &[String]) -> Vec<String> {
+// Lo stesso seed produce la stessa sequenza, per rigiocare una partita condivisa
+fn shuffle_phrases(phrases: &[String], seed: u64, augmentations: Augmentations) -> Vec<String> {
+    let mut rng = StdRng::seed_from_u64(seed);
     let mut shuffled = phrases.to_vec();
-    shuffled.shuffle(&mut OsRng);
-    shuffled
+    shuffled.shuffle(&mut rng);
+    augmentations.apply(&shuffled, &mut rng)
 }
 
-const MARATHON_TIME: f64 = 120.0; // 2 minuti
+pub const MARATHON_TIME: f64 = 120.0; // 2 minuti
 
 #[derive(Clone, PartialEq, Debug)]
 enum GameState {
@@ -35,27 +49,17 @@ enum GameState {
     Finished,
 }
 
-// Helper per ottenere il badge combo
-fn get_combo_badge(combo: usize) -> (&'static str, &'static str) {
-    match combo {
-        0..=4 => ("", "Nessun Combo"),
-        5..=9 => ("🔥", "Combo"),
-        10..=14 => ("⚡", "Combo"),
-        15..=19 => ("💫", "Combo"),
-        20..=39 => ("🌟", "Grande Combo"),
-        40..=79 => ("💥", "Mega Combo"),
-        80..=159 => ("🚀", "Mega Combo"),
-        160..=319 => ("⭐", "Ultra Combo"),
-        320..=639 => ("👑", "Legendary"),
-        640..=999 => ("🔱", "Godlike"),
-        _ => ("🏆", "Unstoppable"),
-    }
-}
-
 #[component]
-pub fn MarathonMode() -> impl IntoView {
+pub fn MarathonMode(
+    // Sequenza di frasi fissata dall'esterno (es. torneo in locale)
+    #[prop(optional)] seed: Option<u64>,
+    // Notificato a fine partita con il record appena registrato
+    #[prop(optional)] on_finish: Option<Callback<SessionRecord>>,
+) -> impl IntoView {
     let settings_ctx = use_settings();
     let stats_ctx = use_stats();
+    let leaderboard_ctx = use_leaderboard();
+    let profile_ctx = use_profiles();
 
     let base_phrases = Memo::new(move |_| {
         let difficulty = settings_ctx.get_difficulty();
@@ -63,6 +67,16 @@ pub fn MarathonMode() -> impl IntoView {
         load_phrases_from_content(json_content)
     });
 
+    // Numeri, maiuscole e punteggiatura aggiunti alle frasi del dataset
+    let augmentations = Memo::new(move |_| settings_ctx.get_augmentations());
+
+    // Seed della sequenza di frasi: dal link condiviso, se presente
+    let query = use_query_map();
+    let shared_seed =
+        query.with_untracked(|q| q.get("seed").and_then(|seed| seed.parse::<u64>().ok()));
+    let fixed_seed = seed.or(shared_seed);
+    let (seed, set_seed) = signal(fixed_seed.unwrap_or_else(random_seed));
+
     let (shuffled_phrases, set_shuffled_phrases) = signal(Vec::<String>::new());
     let (game_state, set_game_state) = signal(GameState::Pending);
     let (phrase_index, set_phrase_index) = signal(0_usize);
@@ -82,60 +96,224 @@ pub fn MarathonMode() -> impl IntoView {
     let (last_combo_milestone, set_last_combo_milestone) = signal(0_usize);
     let (highest_combo, set_highest_combo) = signal(0_usize);
 
+    // Record della partita per gli obiettivi
+    let (best_phrase_wpm, set_best_phrase_wpm) = signal(0.0);
+    let (perfect_streak, set_perfect_streak) = signal(0_u32);
+    let (best_perfect_streak, set_best_perfect_streak) = signal(0_u32);
+
+    // Ultima partita conclusa e posizione ottenuta in classifica
+    let (last_run, set_last_run) = signal::<Option<f64>>(None);
+    let (leaderboard_rank, set_leaderboard_rank) = signal::<Option<usize>>(None);
+    let (last_summary, set_last_summary) = signal::<Option<ResultSummary>>(None);
+    let (last_card, set_last_card) = signal::<Option<ResultCard>>(None);
+
+    // WPM di ogni frase, per il grafico della scheda risultato
+    let (phrase_wpms, set_phrase_wpms) = signal(Vec::<f64>::new());
+
+    // Partita chiusa da un errore in modalità morte improvvisa
+    let (failed, set_failed) = signal(false);
+
+    // Registrazione della partita in corso, per salvarla come nuovo fantasma
+    let recorder = RunRecorder::new();
     Effect::new(move |_| {
-        let phrases = base_phrases.get();
-        set_shuffled_phrases.set(shuffle_phrases(&phrases));
+        recorder.set_rules(
+            settings_ctx.get_error_mode(),
+            settings_ctx.get_match_rules(),
+            settings_ctx.get_input_model(),
+        )
     });
+    let (elapsed_ms, set_elapsed_ms) = signal(0.0);
+    let (live_index, set_live_index) = signal(0_usize);
+
+    // Fantasma del record personale per difficoltà, regole e arricchimenti correnti
+    // Con un seed fissato il fantasma è spento: le sue frasi cambierebbero la sequenza
+    let (race_ghost, set_race_ghost) = signal(fixed_seed.is_none());
+    let (ghost_prefix, set_ghost_prefix) = signal(false);
+    let (ghost_saved, set_ghost_saved) = signal(0_u32);
+    let best_ghost = Memo::new(move |_| {
+        ghost_saved.track();
+        let archive = stats_ctx.archive()?;
+        load_ghost(
+            archive.as_ref(),
+            &stats_ctx.profile(),
+            StatsGameMode::Marathon,
+            settings_ctx.get_difficulty(),
+            settings_ctx.get_match_rules(),
+            augmentations.get(),
+        )
+    });
+    let ghost_track = Memo::new(move |_| {
+        if !race_ghost.get() {
+            return None;
+        }
+        best_ghost
+            .get()
+            .map(|ghost| GhostTrack::from_recording(&ghost.recording))
+    });
+    let offsets = Memo::new(move |_| phrase_offsets(&shuffled_phrases.get()));
+
+    // In gara col fantasma si ripercorre la sua stessa sequenza di frasi
+    let prepare_phrases = move || {
+        let mut phrases = if race_ghost.get_untracked() {
+            best_ghost
+                .get_untracked()
+                .map(|ghost| ghost.recording.phrases)
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        set_ghost_prefix.set(!phrases.is_empty());
+        phrases.extend(shuffle_phrases(
+            &base_phrases.get_untracked(),
+            seed.get_untracked(),
+            augmentations.get_untracked(),
+        ));
+        set_shuffled_phrases.set(phrases);
+    };
 
-    // Timer countdown con registrazione stats
-    Effect::new({
-        let stats_ctx = stats_ctx;
-        move |_| {
-            if game_state.get() == GameState::Running {
-                let handle = set_interval_with_handle(
-                    move || {
-                        set_time_remaining.update(|t| *t -= 0.1);
-                        if time_remaining.get() <= 0.0 {
-                            set_time_remaining.set(0.0);
-                            set_game_state.set(GameState::Finished);
-
-                            // Registra le statistiche
-                            let words = total_words_typed.get();
-                            let chars = total_chars_typed.get();
-                            let combo = highest_combo.get();
-                            let score = words + (combo as u32 / 5);
-
-                            let avg_wpm = if phrases_completed.get() > 0 {
-                                wpm_sum.get() / phrases_completed.get() as f64
-                            } else {
-                                0.0
-                            };
-
-                            let avg_accuracy = if phrases_completed.get() > 0 {
-                                accuracy_sum.get() / phrases_completed.get() as f64
-                            } else {
-                                100.0
-                            };
-
-                            stats_ctx.record_game(
-                                StatsGameMode::Marathon,
-                                words,
-                                chars,
-                                MARATHON_TIME,
-                                avg_wpm,
-                                avg_accuracy,
-                                combo,
-                                Some(score),
-                            );
-                        }
-                    },
-                    Duration::from_millis(100),
-                )
-                .unwrap();
+    Effect::new(move |_| {
+        base_phrases.track();
+        augmentations.track();
+        best_ghost.track();
+        race_ghost.track();
+        if game_state.get_untracked() == GameState::Pending {
+            prepare_phrases();
+        }
+    });
 
-                on_cleanup(move || handle.clear());
-            }
+    let ghost_index = Signal::derive(move || {
+        let track = ghost_track.get()?;
+        if !recorder.is_started() {
+            return None;
+        }
+        let sample = track.position_at(elapsed_ms.get())?;
+        (sample.phrase == phrase_index.get()).then_some(sample.index)
+    });
+
+    let ghost_delta = Signal::derive(move || {
+        let track = ghost_track.get()?;
+        if !recorder.is_started() {
+            return Some(GhostDelta {
+                chars: 0,
+                seconds: None,
+            });
         }
+        let offset = offsets.with(|o| o.get(phrase_index.get()).copied().unwrap_or_default());
+        let progress = offset + live_index.get();
+        Some(GhostDelta::compute(&track, progress, elapsed_ms.get()))
+    });
+
+    // Fine della partita: tempo scaduto o errore fatale in modalità morte improvvisa
+    let finish_game = move || {
+        set_game_state.set(GameState::Finished);
+
+        // Registra le statistiche
+        let words = total_words_typed.get();
+        let chars = total_chars_typed.get();
+        let combo = highest_combo.get();
+
+        let avg_wpm = if phrases_completed.get() > 0 {
+            wpm_sum.get() / phrases_completed.get() as f64
+        } else {
+            0.0
+        };
+
+        let avg_accuracy = if phrases_completed.get() > 0 {
+            accuracy_sum.get() / phrases_completed.get() as f64
+        } else {
+            100.0
+        };
+
+        let record = SessionRecord {
+            timestamp: js_sys::Date::now(),
+            mode: StatsGameMode::Marathon,
+            difficulty: settings_ctx.get_difficulty(),
+            words,
+            chars,
+            time: MARATHON_TIME - time_remaining.get(),
+            wpm: avg_wpm,
+            accuracy: avg_accuracy,
+            combo,
+            score: Some(run_score(words, combo)),
+            best_phrase_wpm: best_phrase_wpm.get(),
+            perfect_streak: best_perfect_streak.get(),
+            match_rules: settings_ctx.get_match_rules(),
+            augmentations: augmentations.get(),
+            symbols: Vec::new(),
+        };
+
+        // Una partita migliore del fantasma diventa il nuovo fantasma
+        let score = run_score(words, combo);
+        let is_best = best_ghost.get_untracked().is_none_or(|g| score > g.score);
+        if is_best && let Some(archive) = stats_ctx.archive() {
+            let ghost = GhostRun {
+                score,
+                recorded_at: record.timestamp,
+                recording: recorder.recording(),
+                augmentations: record.augmentations,
+            };
+            let _ = save_ghost(
+                archive.as_ref(),
+                &stats_ctx.profile(),
+                StatsGameMode::Marathon,
+                record.difficulty,
+                &ghost,
+            );
+            set_ghost_saved.update(|v| *v += 1);
+        }
+
+        let profile = profile_ctx.active();
+        let _ = stats_ctx.save_replay(Replay::new(
+            &record,
+            &profile.name,
+            Some(MARATHON_TIME),
+            recorder.recording(),
+        ));
+        set_last_run.set(Some(record.timestamp));
+        set_last_card.set(Some(ResultCard::from_record(
+            &record,
+            phrase_wpms.get_untracked(),
+        )));
+        // Le frasi del fantasma non sono riproducibili dal solo seed
+        let shared_seed = (!ghost_prefix.get_untracked()).then(|| seed.get_untracked());
+        set_last_summary.set(Some(ResultSummary::from_record(&record, shared_seed)));
+        set_leaderboard_rank.set(leaderboard_ctx.submit(
+            &record,
+            MARATHON_TIME as u32,
+            &profile.id,
+        ));
+        if let Some(callback) = on_finish {
+            callback.run(record.clone());
+        }
+        stats_ctx.record_game(record);
+    };
+
+    // Timer countdown con registrazione stats
+    Effect::new(move |_| {
+        if game_state.get() == GameState::Running {
+            let handle = set_interval_with_handle(
+                move || {
+                    if let Some(elapsed) = recorder.elapsed() {
+                        set_elapsed_ms.set(elapsed);
+                    }
+                    set_time_remaining.update(|t| *t -= 0.1);
+                    if time_remaining.get() <= 0.0 {
+                        set_time_remaining.set(0.0);
+                        finish_game();
+                    }
+                },
+                Duration::from_millis(100),
+            )
+            .unwrap();
+
+            on_cleanup(move || handle.clear());
+        }
+    });
+
+    // Morte improvvisa: il primo errore chiude la partita
+    let on_fail = Callback::new(move |_: ()| {
+        set_failed.set(true);
+        finish_game();
     });
 
     let on_char_typed = Callback::new(move |_: ()| {
@@ -145,6 +323,13 @@ pub fn MarathonMode() -> impl IntoView {
         set_total_chars_typed.update(|c| *c += 1);
     });
 
+    let on_keystroke = Callback::new(move |(input, index): (KeyInput, usize)| {
+        let phrase = phrase_index.get_untracked();
+        let text = shuffled_phrases.with_untracked(|p| p[phrase % p.len()].clone());
+        recorder.record(phrase, &text, input);
+        set_live_index.set(index);
+    });
+
     let on_char_error = Callback::new(move |_: ()| {
         let current_combo = consecutive_correct_words.get();
         if current_combo >= 5 {
@@ -217,23 +402,32 @@ pub fn MarathonMode() -> impl IntoView {
         set_last_combo_milestone.set(0);
     });
 
-    let on_complete = Callback::new(move |(wpm, accuracy): (f64, f64)| {
+    let on_complete = Callback::new(move |(wpm, accuracy, _): (f64, f64, usize)| {
         set_last_wpm.set(wpm);
         set_last_accuracy.set(accuracy);
         set_accuracy_sum.update(|sum| *sum += accuracy);
         set_wpm_sum.update(|sum| *sum += wpm);
         set_phrases_completed.update(|count| *count += 1);
 
+        set_best_phrase_wpm.update(|best| *best = f64::max(*best, wpm));
+        set_phrase_wpms.update(|wpms| wpms.push(wpm));
+
         if !phrase_has_errors.get() {
             set_combo_trigger.set(Some(ComboType::PerfectPhrase));
+            set_perfect_streak.update(|streak| *streak += 1);
+            set_best_perfect_streak.update(|best| *best = (*best).max(perfect_streak.get()));
+        } else {
+            set_perfect_streak.set(0);
         }
 
         set_phrase_index.update(|i| *i += 1);
+        set_live_index.set(0);
         set_phrase_has_errors.set(false);
     });
 
     let restart_game = move |_| {
         set_game_state.set(GameState::Pending);
+        set_failed.set(false);
         set_time_remaining.set(MARATHON_TIME);
         set_phrase_index.set(0);
         set_total_words_typed.set(0);
@@ -247,9 +441,17 @@ pub fn MarathonMode() -> impl IntoView {
         set_phrase_has_errors.set(false);
         set_last_combo_milestone.set(0);
         set_highest_combo.set(0);
-
-        let phrases = base_phrases.get();
-        set_shuffled_phrases.set(shuffle_phrases(&phrases));
+        set_best_phrase_wpm.set(0.0);
+        set_perfect_streak.set(0);
+        set_best_perfect_streak.set(0);
+        set_phrase_wpms.set(Vec::new());
+        set_leaderboard_rank.set(None);
+        recorder.reset();
+        set_elapsed_ms.set(0.0);
+        set_live_index.set(0);
+
+        set_seed.set(random_seed());
+        prepare_phrases();
     };
 
     view! {
@@ -272,23 +474,40 @@ pub fn MarathonMode() -> impl IntoView {
                 chars_typed=Signal::derive(move || total_chars_typed.get() as usize)
                 words_typed=Signal::derive(move || total_words_typed.get() as usize)
                 timer=Signal::derive(move || time_remaining.get())
+                ghost_delta=ghost_delta
             />
 
+            <Show when=move || {
+                fixed_seed.is_none() && game_state.get() == GameState::Pending && best_ghost.get().is_some()
+            }>
+                <label class="marathon-ghost-toggle">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || race_ghost.get()
+                        on:change=move |ev| set_race_ghost.set(event_target_checked(&ev))
+                    />
+                    {move || {
+                        let score = best_ghost.get().map(|g| g.score).unwrap_or_default();
+                        format!("👻 Sfida il tuo record ({} punti)", score)
+                    }}
+                </label>
+            </Show>
+
             <div class="marathon-typing-area">
                 <Show
                     when=move || game_state.get() == GameState::Finished
                     fallback=|| ()
                 >
                     <div class="marathon-game-over">
-                        <h3 class="marathon-game-over-title">"Marathon Completata!"</h3>
+                        <h3 class="marathon-game-over-title">
+                            {move || if failed.get() { "Errore Fatale!" } else { "Marathon Completata!" }}
+                        </h3>
                         <div class="marathon-final-stats">
                             <div class="marathon-stat-item marathon-stat-item--highlight">
                                 <span class="marathon-stat-label">"Punteggio Totale"</span>
                                 <span class="marathon-stat-value marathon-stat-value--score">
                                     {move || {
-                                        let words = total_words_typed.get();
-                                        let combo_bonus = highest_combo.get() as u32 / 5;
-                                        let score = words + combo_bonus;
+                                        let score = run_score(total_words_typed.get(), highest_combo.get());
                                         format!("{}", score)
                                     }}
                                 </span>
@@ -306,7 +525,7 @@ pub fn MarathonMode() -> impl IntoView {
                                 <span class="marathon-stat-value marathon-stat-value--combo">
                                     {move || {
                                         let combo = highest_combo.get();
-                                        let (emoji, _label) = get_combo_badge(combo);
+                                        let (emoji, _label) = combo_badge(combo);
                                         format!("{} {}", emoji, combo)
                                     }}
                                 </span>
@@ -338,9 +557,27 @@ pub fn MarathonMode() -> impl IntoView {
                                 </span>
                             </div>
                         </div>
-                        <button class="marathon-play-again-button" on:click=restart_game>
-                            "Gioca Ancora"
-                        </button>
+                        {move || leaderboard_rank.get().map(|rank| view! {
+                            <p class="marathon-leaderboard-badge">
+                                {format!("🏅 Nuovo ingresso in classifica: #{}", rank)}
+                            </p>
+                        })}
+                        <ShareButton summary=last_summary />
+                        <ResultCardDownload card=last_card />
+                        <Leaderboard
+                            mode=StatsGameMode::Marathon
+                            difficulty=Signal::derive(move || settings_ctx.get_difficulty())
+                            match_rules=Signal::derive(move || settings_ctx.get_match_rules())
+                            augmentations=augmentations
+                            duration=MARATHON_TIME as u32
+                            highlight=Signal::derive(move || last_run.get())
+                        />
+                        // Nel torneo si passa al giocatore successivo, non si rigioca
+                        {on_finish.is_none().then(|| view! {
+                            <button class="marathon-play-again-button" on:click=restart_game>
+                                "Gioca Ancora"
+                            </button>
+                        })}
                     </div>
                 </Show>
 
@@ -366,6 +603,13 @@ pub fn MarathonMode() -> impl IntoView {
                                     on_char_error=on_char_error
                                     on_word_typed=on_word_typed
                                     on_word_deleted=on_word_deleted
+                                    on_keystroke=on_keystroke
+                                    error_mode=settings_ctx.get_error_mode()
+                                    match_rules=settings_ctx.get_match_rules()
+                                    input_model=settings_ctx.get_input_model()
+                                    caret_style=settings_ctx.get_caret_style()
+                                    on_fail=on_fail
+                                    ghost_index=ghost_index
                                 />
                             }.into_any()
                         }
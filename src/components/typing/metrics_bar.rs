@@ -1,3 +1,4 @@
+use crate::recording::GhostDelta;
 use leptos::prelude::*;
 
 #[component]
@@ -9,6 +10,8 @@ pub fn MetricsBar(
     #[prop(optional)] current_phrase: Option<Signal<usize>>,
     #[prop(optional)] total_phrases: Option<usize>,
     #[prop(optional)] timer: Option<Signal<f64>>,
+    // Distacco dal fantasma del record personale, se in gara
+    #[prop(optional)] ghost_delta: Option<Signal<Option<GhostDelta>>>,
 ) -> impl IntoView {
     view! {
         <div class="metrics-bar">
@@ -30,6 +33,26 @@ pub fn MetricsBar(
                     <div class="metrics-bar__label">"acc"</div>
                     <div class="metrics-bar__value">{move || format!("{:.1}%", accuracy.get())}</div>
                 </div>
+                {move || ghost_delta.map(|delta| view! {
+                    <Show when=move || delta.get().is_some()>
+                    <div
+                        class="metrics-bar__stat metrics-bar__stat--ghost"
+                        class:metrics-bar__stat--ahead=move || delta.get().is_some_and(|d| d.chars > 0)
+                        class:metrics-bar__stat--behind=move || delta.get().is_some_and(|d| d.chars < 0)
+                    >
+                        <div class="metrics-bar__label">"fantasma"</div>
+                        <div class="metrics-bar__value">
+                            {move || match delta.get() {
+                                Some(GhostDelta { chars, seconds: Some(seconds) }) => {
+                                    format!("{:+} car · {:+.1}s", chars, seconds)
+                                }
+                                Some(GhostDelta { chars, seconds: None }) => format!("{:+} car", chars),
+                                None => "-".to_string(),
+                            }}
+                        </div>
+                    </div>
+                    </Show>
+                })}
             </div>
 
             // --- Gruppo Destro: Metriche di Progresso ---
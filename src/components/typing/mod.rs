@@ -1,15 +1,21 @@
 // src/components/typing/mod.rs
 //
+pub mod code_mode;
 pub mod combo_popup;
 pub mod engine;
 pub mod marathon_mode;
 pub mod metrics_bar;
 pub mod rush_mode;
+pub mod survival_mode;
+pub mod typing_text;
 pub mod zen_mode;
 
-pub use combo_popup::{ComboPopup, ComboType};
+pub use code_mode::CodeMode;
+pub use combo_popup::{ComboPopup, ComboType, combo_badge};
 pub use engine::TypingEngine;
 pub use marathon_mode::MarathonMode;
 pub use metrics_bar::MetricsBar;
 pub use rush_mode::RushMode;
+pub use survival_mode::SurvivalMode;
+pub use typing_text::{TextCells, TypingText};
 pub use zen_mode::ZenMode;
@@ -1,10 +1,20 @@
 // src/components/typing/rush_mode.rs
 //
-use crate::components::typing::{ComboPopup, ComboType, MetricsBar, TypingEngine};
+use crate::augment::Augmentations;
+use crate::components::typing::engine::KeyInput;
+use crate::components::typing::{ComboPopup, ComboType, MetricsBar, TypingEngine, combo_badge};
+use crate::components::{Leaderboard, ResultCardDownload, ShareButton};
+use crate::leaderboard_store::use_leaderboard;
+use crate::profile_store::use_profiles;
+use crate::recording::{Replay, RunRecorder};
+use crate::result_card::ResultCard;
 use crate::settings_store::use_settings;
-use crate::stats_store::{GameMode as StatsGameMode, use_stats};
+use crate::share::{ResultSummary, random_seed};
+use crate::stats_store::{GameMode as StatsGameMode, SessionRecord, run_score, use_stats};
 use leptos::prelude::*;
-use rand::rngs::OsRng;
+use leptos_router::hooks::use_query_map;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 use serde::Deserialize;
 use std::time::Duration;
@@ -20,13 +30,15 @@ fn load_phrases_from_content(json_content: &str) -> Vec<String> {
     data.phrases
 }
 
-fn shuffle_phrases(phrases: &[String]) -> Vec<String> {
+// Lo stesso seed produce la stessa sequenza, per rigiocare una partita condivisa
+fn shuffle_phrases(phrases: &[String], seed: u64, augmentations: Augmentations) -> Vec<String> {
+    let mut rng = StdRng::seed_from_u64(seed);
     let mut shuffled = phrases.to_vec();
-    shuffled.shuffle(&mut OsRng);
-    shuffled
+    shuffled.shuffle(&mut rng);
+    augmentations.apply(&shuffled, &mut rng)
 }
 
-const INITIAL_TIME: f64 = 20.0;
+pub const INITIAL_TIME: f64 = 20.0;
 const ACCURACY_BONUS_PERFECT: f64 = 5.0;
 const ACCURACY_BONUS_HIGH: f64 = 3.0;
 const ACCURACY_BONUS_MEDIUM: f64 = 2.0;
@@ -39,27 +51,17 @@ enum GameState {
     Finished,
 }
 
-// Helper per ottenere il badge combo in base al punteggio
-fn get_combo_badge(combo: usize) -> (&'static str, &'static str) {
-    match combo {
-        0..=4 => ("", "Nessun Combo"),
-        5..=9 => ("🔥", "Combo"),
-        10..=14 => ("⚡", "Combo"),
-        15..=19 => ("💫", "Combo"),
-        20..=39 => ("🌟", "Grande Combo"),
-        40..=79 => ("💥", "Mega Combo"),
-        80..=159 => ("🚀", "Mega Combo"),
-        160..=319 => ("⭐", "Ultra Combo"),
-        320..=639 => ("👑", "Legendary"),
-        640..=999 => ("🔱", "Godlike"),
-        _ => ("🏆", "Unstoppable"),
-    }
-}
-
 #[component]
-pub fn RushMode() -> impl IntoView {
+pub fn RushMode(
+    // Sequenza di frasi fissata dall'esterno (es. torneo in locale)
+    #[prop(optional)] seed: Option<u64>,
+    // Notificato a fine partita con il record appena registrato
+    #[prop(optional)] on_finish: Option<Callback<SessionRecord>>,
+) -> impl IntoView {
     let settings_ctx = use_settings();
     let stats_ctx = use_stats();
+    let leaderboard_ctx = use_leaderboard();
+    let profile_ctx = use_profiles();
 
     let base_phrases = Memo::new(move |_| {
         let difficulty = settings_ctx.get_difficulty();
@@ -67,6 +69,16 @@ pub fn RushMode() -> impl IntoView {
         load_phrases_from_content(json_content)
     });
 
+    // Numeri, maiuscole e punteggiatura aggiunti alle frasi del dataset
+    let augmentations = Memo::new(move |_| settings_ctx.get_augmentations());
+
+    // Seed della sequenza di frasi: dal link condiviso, se presente
+    let query = use_query_map();
+    let shared_seed =
+        query.with_untracked(|q| q.get("seed").and_then(|seed| seed.parse::<u64>().ok()));
+    let fixed_seed = seed.or(shared_seed);
+    let (seed, set_seed) = signal(fixed_seed.unwrap_or_else(random_seed));
+
     let (shuffled_phrases, set_shuffled_phrases) = signal(Vec::<String>::new());
     let (game_state, set_game_state) = signal(GameState::Pending);
     let (phrase_index, set_phrase_index) = signal(0_usize);
@@ -87,59 +99,127 @@ pub fn RushMode() -> impl IntoView {
     let (last_combo_milestone, set_last_combo_milestone) = signal(0_usize);
     let (highest_combo, set_highest_combo) = signal(0_usize);
 
+    // Record della partita per gli obiettivi
+    let (best_phrase_wpm, set_best_phrase_wpm) = signal(0.0);
+    let (perfect_streak, set_perfect_streak) = signal(0_u32);
+    let (best_perfect_streak, set_best_perfect_streak) = signal(0_u32);
+
+    // Ultima partita conclusa e posizione ottenuta in classifica
+    let (last_run, set_last_run) = signal::<Option<f64>>(None);
+    let (leaderboard_rank, set_leaderboard_rank) = signal::<Option<usize>>(None);
+    let (last_summary, set_last_summary) = signal::<Option<ResultSummary>>(None);
+    let (last_card, set_last_card) = signal::<Option<ResultCard>>(None);
+
+    // WPM di ogni frase, per il grafico della scheda risultato
+    let (phrase_wpms, set_phrase_wpms) = signal(Vec::<f64>::new());
+
+    // Partita chiusa da un errore in modalità morte improvvisa
+    let (failed, set_failed) = signal(false);
+
+    // Registrazione dei tasti per il replay
+    let recorder = RunRecorder::new();
+    Effect::new(move |_| {
+        recorder.set_rules(
+            settings_ctx.get_error_mode(),
+            settings_ctx.get_match_rules(),
+            settings_ctx.get_input_model(),
+        )
+    });
+
     Effect::new(move |_| {
         let phrases = base_phrases.get();
-        set_shuffled_phrases.set(shuffle_phrases(&phrases));
+        set_shuffled_phrases.set(shuffle_phrases(
+            &phrases,
+            seed.get_untracked(),
+            augmentations.get(),
+        ));
     });
 
-    Effect::new({
-        let stats_ctx = stats_ctx;
-        move |_| {
-            if game_state.get() == GameState::Running {
-                let handle = set_interval_with_handle(
-                    move || {
-                        set_time_remaining.update(|t| *t -= 0.1);
-                        if time_remaining.get() <= 0.0 {
-                            set_time_remaining.set(0.0);
-                            set_game_state.set(GameState::Finished);
-
-                            // Registra le statistiche
-                            let time_played = INITIAL_TIME - time_remaining.get();
-                            let words = total_words_typed.get();
-                            let chars = total_chars_typed.get();
-                            let combo = highest_combo.get();
-
-                            let avg_wpm = if phrases_completed.get() > 0 {
-                                wpm_sum.get() / phrases_completed.get() as f64
-                            } else {
-                                0.0
-                            };
-
-                            let avg_accuracy = if phrases_completed.get() > 0 {
-                                accuracy_sum.get() / phrases_completed.get() as f64
-                            } else {
-                                100.0
-                            };
-
-                            stats_ctx.record_game(
-                                StatsGameMode::Rush,
-                                words,
-                                chars,
-                                time_played,
-                                avg_wpm,
-                                avg_accuracy,
-                                combo,
-                                None,
-                            );
-                        }
-                    },
-                    Duration::from_millis(100),
-                )
-                .unwrap();
+    // Fine della partita: tempo scaduto o errore fatale in modalità morte improvvisa
+    let finish_game = move || {
+        set_game_state.set(GameState::Finished);
 
-                on_cleanup(move || handle.clear());
-            }
+        // Registra le statistiche
+        let time_played = INITIAL_TIME - time_remaining.get();
+        let words = total_words_typed.get();
+        let chars = total_chars_typed.get();
+        let combo = highest_combo.get();
+
+        let avg_wpm = if phrases_completed.get() > 0 {
+            wpm_sum.get() / phrases_completed.get() as f64
+        } else {
+            0.0
+        };
+
+        let avg_accuracy = if phrases_completed.get() > 0 {
+            accuracy_sum.get() / phrases_completed.get() as f64
+        } else {
+            100.0
+        };
+
+        let record = SessionRecord {
+            timestamp: js_sys::Date::now(),
+            mode: StatsGameMode::Rush,
+            difficulty: settings_ctx.get_difficulty(),
+            words,
+            chars,
+            time: time_played,
+            wpm: avg_wpm,
+            accuracy: avg_accuracy,
+            combo,
+            score: Some(run_score(words, combo)),
+            best_phrase_wpm: best_phrase_wpm.get(),
+            perfect_streak: best_perfect_streak.get(),
+            match_rules: settings_ctx.get_match_rules(),
+            augmentations: augmentations.get(),
+            symbols: Vec::new(),
+        };
+
+        let profile = profile_ctx.active();
+        let _ = stats_ctx.save_replay(Replay::new(
+            &record,
+            &profile.name,
+            Some(INITIAL_TIME),
+            recorder.recording(),
+        ));
+        set_last_run.set(Some(record.timestamp));
+        set_last_card.set(Some(ResultCard::from_record(
+            &record,
+            phrase_wpms.get_untracked(),
+        )));
+        set_last_summary.set(Some(ResultSummary::from_record(
+            &record,
+            Some(seed.get_untracked()),
+        )));
+        set_leaderboard_rank.set(leaderboard_ctx.submit(&record, INITIAL_TIME as u32, &profile.id));
+        if let Some(callback) = on_finish {
+            callback.run(record.clone());
         }
+        stats_ctx.record_game(record);
+    };
+
+    Effect::new(move |_| {
+        if game_state.get() == GameState::Running {
+            let handle = set_interval_with_handle(
+                move || {
+                    set_time_remaining.update(|t| *t -= 0.1);
+                    if time_remaining.get() <= 0.0 {
+                        set_time_remaining.set(0.0);
+                        finish_game();
+                    }
+                },
+                Duration::from_millis(100),
+            )
+            .unwrap();
+
+            on_cleanup(move || handle.clear());
+        }
+    });
+
+    // Morte improvvisa: il primo errore chiude la partita
+    let on_fail = Callback::new(move |_: ()| {
+        set_failed.set(true);
+        finish_game();
     });
 
     let on_char_typed = Callback::new(move |_: ()| {
@@ -150,6 +230,12 @@ pub fn RushMode() -> impl IntoView {
         set_total_chars_typed.update(|c| *c += 1);
     });
 
+    let on_keystroke = Callback::new(move |(input, _): (KeyInput, usize)| {
+        let phrase = phrase_index.get_untracked();
+        let text = shuffled_phrases.with_untracked(|p| p[phrase % p.len()].clone());
+        recorder.record(phrase, &text, input);
+    });
+
     let on_char_error = Callback::new(move |_: ()| {
         let current_combo = consecutive_correct_words.get();
         if current_combo >= 5 {
@@ -222,19 +308,26 @@ pub fn RushMode() -> impl IntoView {
         set_last_combo_milestone.set(0);
     });
 
-    let on_complete = Callback::new(move |(wpm, accuracy): (f64, f64)| {
+    let on_complete = Callback::new(move |(wpm, accuracy, _): (f64, f64, usize)| {
         set_last_wpm.set(wpm);
         set_last_accuracy.set(accuracy);
         set_accuracy_sum.update(|sum| *sum += accuracy);
         set_wpm_sum.update(|sum| *sum += wpm);
         set_phrases_completed.update(|count| *count += 1);
 
+        set_best_phrase_wpm.update(|best| *best = f64::max(*best, wpm));
+        set_phrase_wpms.update(|wpms| wpms.push(wpm));
+
         if !phrase_has_errors.get() {
             set_combo_trigger.set(Some(ComboType::PerfectPhrase));
+            set_perfect_streak.update(|streak| *streak += 1);
+            set_best_perfect_streak.update(|best| *best = (*best).max(perfect_streak.get()));
+        } else {
+            set_perfect_streak.set(0);
         }
 
         let time_bonus = match accuracy {
-            a if a == 100.0 => ACCURACY_BONUS_PERFECT,
+            100.0 => ACCURACY_BONUS_PERFECT,
             a if a > 75.0 => ACCURACY_BONUS_HIGH,
             a if a > 50.0 => ACCURACY_BONUS_MEDIUM,
             a if a > 25.0 => ACCURACY_BONUS_LOW,
@@ -247,6 +340,7 @@ pub fn RushMode() -> impl IntoView {
 
     let restart_game = move |_| {
         set_game_state.set(GameState::Pending);
+        set_failed.set(false);
         set_time_remaining.set(INITIAL_TIME);
         set_phrase_index.set(0);
         set_total_words_typed.set(0);
@@ -260,9 +354,17 @@ pub fn RushMode() -> impl IntoView {
         set_phrase_has_errors.set(false);
         set_last_combo_milestone.set(0);
         set_highest_combo.set(0);
+        set_best_phrase_wpm.set(0.0);
+        set_perfect_streak.set(0);
+        set_best_perfect_streak.set(0);
+        set_phrase_wpms.set(Vec::new());
+        set_leaderboard_rank.set(None);
+        recorder.reset();
 
         let phrases = base_phrases.get();
-        set_shuffled_phrases.set(shuffle_phrases(&phrases));
+        let next_seed = random_seed();
+        set_seed.set(next_seed);
+        set_shuffled_phrases.set(shuffle_phrases(&phrases, next_seed, augmentations.get()));
     };
 
     view! {
@@ -293,7 +395,9 @@ pub fn RushMode() -> impl IntoView {
                     fallback=|| ()
                 >
                     <div class="rush-game-over">
-                        <h3 class="rush-game-over-title">"Tempo Scaduto!"</h3>
+                        <h3 class="rush-game-over-title">
+                            {move || if failed.get() { "Errore Fatale!" } else { "Tempo Scaduto!" }}
+                        </h3>
                         <div class="rush-final-stats">
                             <div class="rush-stat-item">
                                 <span class="rush-stat-label">"Parole Totali"</span>
@@ -308,7 +412,7 @@ pub fn RushMode() -> impl IntoView {
                                 <span class="rush-stat-value rush-stat-value--combo">
                                     {move || {
                                         let combo = highest_combo.get();
-                                        let (emoji, _label) = get_combo_badge(combo);
+                                        let (emoji, _label) = combo_badge(combo);
                                         format!("{} {}", emoji, combo)
                                     }}
                                 </span>
@@ -340,9 +444,27 @@ pub fn RushMode() -> impl IntoView {
                                 </span>
                             </div>
                         </div>
-                        <button class="rush-play-again-button" on:click=restart_game>
-                            "Gioca Ancora"
-                        </button>
+                        {move || leaderboard_rank.get().map(|rank| view! {
+                            <p class="rush-leaderboard-badge">
+                                {format!("🏅 Nuovo ingresso in classifica: #{}", rank)}
+                            </p>
+                        })}
+                        <ShareButton summary=last_summary />
+                        <ResultCardDownload card=last_card />
+                        <Leaderboard
+                            mode=StatsGameMode::Rush
+                            difficulty=Signal::derive(move || settings_ctx.get_difficulty())
+                            match_rules=Signal::derive(move || settings_ctx.get_match_rules())
+                            augmentations=augmentations
+                            duration=INITIAL_TIME as u32
+                            highlight=Signal::derive(move || last_run.get())
+                        />
+                        // Nel torneo si passa al giocatore successivo, non si rigioca
+                        {on_finish.is_none().then(|| view! {
+                            <button class="rush-play-again-button" on:click=restart_game>
+                                "Gioca Ancora"
+                            </button>
+                        })}
                     </div>
                 </Show>
 
@@ -368,6 +490,12 @@ pub fn RushMode() -> impl IntoView {
                                     on_char_error=on_char_error
                                     on_word_typed=on_word_typed
                                     on_word_deleted=on_word_deleted
+                                    on_keystroke=on_keystroke
+                                    error_mode=settings_ctx.get_error_mode()
+                                    match_rules=settings_ctx.get_match_rules()
+                                    input_model=settings_ctx.get_input_model()
+                                    caret_style=settings_ctx.get_caret_style()
+                                    on_fail=on_fail
                                 />
                             }.into_any()
                         }
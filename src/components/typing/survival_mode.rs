@@ -0,0 +1,553 @@
+// src/components/typing/survival_mode.rs
+//
+use crate::augment::Augmentations;
+use crate::components::typing::engine::KeyInput;
+use crate::components::typing::{ComboPopup, ComboType, MetricsBar, TypingEngine, combo_badge};
+use crate::components::{ResultCardDownload, ShareButton};
+use crate::profile_store::use_profiles;
+use crate::recording::{Replay, RunRecorder};
+use crate::result_card::ResultCard;
+use crate::settings_store::use_settings;
+use crate::share::{ResultSummary, random_seed};
+use crate::stats_store::{GameMode as StatsGameMode, SessionRecord, survival_score, use_stats};
+use leptos::prelude::*;
+use leptos_router::hooks::use_query_map;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct PhrasesData {
+    phrases: Vec<String>,
+}
+
+fn load_phrases_from_content(json_content: &str) -> Vec<String> {
+    let data: PhrasesData =
+        serde_json::from_str(json_content).expect("Errore nel parsing del dataset JSON");
+    data.phrases
+}
+
+// Lo stesso seed produce la stessa sequenza, per rigiocare una partita condivisa
+fn shuffle_phrases(phrases: &[String], seed: u64, augmentations: Augmentations) -> Vec<String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut shuffled = phrases.to_vec();
+    shuffled.shuffle(&mut rng);
+    augmentations.apply(&shuffled, &mut rng)
+}
+
+const INITIAL_LIVES: u32 = 3;
+const MAX_LIVES: u32 = 5;
+// Sotto questa accuracy dei tasti premuti la frase costa una vita
+const ACCURACY_THRESHOLD: f64 = 90.0;
+// Frasi perfette consecutive per recuperare una vita
+const PERFECT_STREAK_FOR_LIFE: u32 = 3;
+
+// Una vita per ogni errore lasciato nel testo, più una se la frase è stata sporca
+fn lives_lost(errors: usize, keystroke_accuracy: f64) -> u32 {
+    errors as u32 + u32::from(keystroke_accuracy < ACCURACY_THRESHOLD)
+}
+
+// Ogni `PERFECT_STREAK_FOR_LIFE` frasi perfette di fila si recupera una vita
+fn regains_life(lives: u32, perfect_streak: u32) -> bool {
+    perfect_streak > 0
+        && perfect_streak.is_multiple_of(PERFECT_STREAK_FOR_LIFE)
+        && lives < MAX_LIVES
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum GameState {
+    Pending,
+    Running,
+    Finished,
+}
+
+#[component]
+pub fn SurvivalMode(
+    // Sequenza di frasi fissata dall'esterno (es. torneo in locale)
+    #[prop(optional)] seed: Option<u64>,
+    // Notificato a fine partita con il record appena registrato
+    #[prop(optional)] on_finish: Option<Callback<SessionRecord>>,
+) -> impl IntoView {
+    let settings_ctx = use_settings();
+    let stats_ctx = use_stats();
+    let profile_ctx = use_profiles();
+
+    let base_phrases = Memo::new(move |_| {
+        let difficulty = settings_ctx.get_difficulty();
+        let json_content = difficulty.get_dataset_content();
+        load_phrases_from_content(json_content)
+    });
+
+    // Numeri, maiuscole e punteggiatura aggiunti alle frasi del dataset
+    let augmentations = Memo::new(move |_| settings_ctx.get_augmentations());
+
+    // Seed della sequenza di frasi: dal link condiviso, se presente
+    let query = use_query_map();
+    let shared_seed =
+        query.with_untracked(|q| q.get("seed").and_then(|seed| seed.parse::<u64>().ok()));
+    let fixed_seed = seed.or(shared_seed);
+    let (seed, set_seed) = signal(fixed_seed.unwrap_or_else(random_seed));
+
+    let (shuffled_phrases, set_shuffled_phrases) = signal(Vec::<String>::new());
+    let (game_state, set_game_state) = signal(GameState::Pending);
+    let (phrase_index, set_phrase_index) = signal(0_usize);
+    let (lives, set_lives) = signal(INITIAL_LIVES);
+    let (total_words_typed, set_total_words_typed) = signal(0_u32);
+    let (total_chars_typed, set_total_chars_typed) = signal(0_u32);
+    let (last_wpm, set_last_wpm) = signal(0.0);
+    let (last_accuracy, set_last_accuracy) = signal(100.0);
+    let (accuracy_sum, set_accuracy_sum) = signal(0.0);
+    let (wpm_sum, set_wpm_sum) = signal(0.0);
+    let (phrases_completed, set_phrases_completed) = signal(0_u32);
+    let (game_start_time, set_game_start_time) = signal(0.0);
+
+    // Tasti premuti ed errori nella frase corrente, anche se poi corretti
+    let (phrase_keystrokes, set_phrase_keystrokes) = signal(0_u32);
+    let (phrase_mistakes, set_phrase_mistakes) = signal(0_u32);
+
+    // Sistema combo
+    let (consecutive_correct_words, set_consecutive_correct_words) = signal(0_usize);
+    let (combo_trigger, set_combo_trigger) = signal::<Option<ComboType>>(None);
+    let (phrase_has_errors, set_phrase_has_errors) = signal(false);
+    let (last_combo_milestone, set_last_combo_milestone) = signal(0_usize);
+    let (highest_combo, set_highest_combo) = signal(0_usize);
+
+    // Record della partita per gli obiettivi
+    let (best_phrase_wpm, set_best_phrase_wpm) = signal(0.0);
+    let (perfect_streak, set_perfect_streak) = signal(0_u32);
+    let (best_perfect_streak, set_best_perfect_streak) = signal(0_u32);
+
+    // Ultima partita conclusa
+    let (final_score, set_final_score) = signal(0_u32);
+    let (last_summary, set_last_summary) = signal::<Option<ResultSummary>>(None);
+    let (last_card, set_last_card) = signal::<Option<ResultCard>>(None);
+
+    // WPM di ogni frase, per il grafico della scheda risultato
+    let (phrase_wpms, set_phrase_wpms) = signal(Vec::<f64>::new());
+
+    // Registrazione dei tasti per il replay
+    let recorder = RunRecorder::new();
+    Effect::new(move |_| {
+        recorder.set_rules(
+            settings_ctx.get_error_mode(),
+            settings_ctx.get_match_rules(),
+            settings_ctx.get_input_model(),
+        )
+    });
+
+    Effect::new(move |_| {
+        let phrases = base_phrases.get();
+        set_shuffled_phrases.set(shuffle_phrases(
+            &phrases,
+            seed.get_untracked(),
+            augmentations.get(),
+        ));
+    });
+
+    // Vite esaurite: la partita finisce e viene registrata
+    let finish_game = move || {
+        set_game_state.set(GameState::Finished);
+
+        let now = js_sys::Date::now();
+        let time_played = (now - game_start_time.get_untracked()) / 1000.0;
+        let words = total_words_typed.get_untracked();
+        let chars = total_chars_typed.get_untracked();
+        let combo = highest_combo.get_untracked();
+        let completed = phrases_completed.get_untracked();
+
+        let avg_wpm = if completed > 0 {
+            wpm_sum.get_untracked() / completed as f64
+        } else {
+            0.0
+        };
+
+        let avg_accuracy = if completed > 0 {
+            accuracy_sum.get_untracked() / completed as f64
+        } else {
+            100.0
+        };
+
+        let score = survival_score(completed, words, combo);
+        set_final_score.set(score);
+
+        let record = SessionRecord {
+            timestamp: now,
+            mode: StatsGameMode::Survival,
+            difficulty: settings_ctx.get_difficulty(),
+            words,
+            chars,
+            time: time_played,
+            wpm: avg_wpm,
+            accuracy: avg_accuracy,
+            combo,
+            score: Some(score),
+            best_phrase_wpm: best_phrase_wpm.get_untracked(),
+            perfect_streak: best_perfect_streak.get_untracked(),
+            match_rules: settings_ctx.get_match_rules(),
+            augmentations: augmentations.get(),
+            symbols: Vec::new(),
+        };
+
+        let profile = profile_ctx.active();
+        let _ = stats_ctx.save_replay(Replay::new(
+            &record,
+            &profile.name,
+            None,
+            recorder.recording(),
+        ));
+        set_last_card.set(Some(ResultCard::from_record(
+            &record,
+            phrase_wpms.get_untracked(),
+        )));
+        set_last_summary.set(Some(ResultSummary::from_record(
+            &record,
+            Some(seed.get_untracked()),
+        )));
+        if let Some(callback) = on_finish {
+            callback.run(record.clone());
+        }
+        stats_ctx.record_game(record);
+    };
+
+    // Morte improvvisa: il primo errore costa tutte le vite
+    let on_fail = Callback::new(move |_: ()| {
+        set_lives.set(0);
+        finish_game();
+    });
+
+    let on_char_typed = Callback::new(move |_: ()| {
+        if game_state.get() == GameState::Pending {
+            set_game_state.set(GameState::Running);
+            set_game_start_time.set(js_sys::Date::now());
+        }
+        set_total_chars_typed.update(|c| *c += 1);
+        set_phrase_keystrokes.update(|k| *k += 1);
+    });
+
+    let on_keystroke = Callback::new(move |(input, _): (KeyInput, usize)| {
+        let phrase = phrase_index.get_untracked();
+        let text = shuffled_phrases.with_untracked(|p| p[phrase % p.len()].clone());
+        recorder.record(phrase, &text, input);
+    });
+
+    let on_char_error = Callback::new(move |_: ()| {
+        let current_combo = consecutive_correct_words.get();
+        if current_combo >= 5 {
+            set_combo_trigger.set(Some(ComboType::ComboBroken));
+        }
+        set_consecutive_correct_words.set(0);
+        set_phrase_has_errors.set(true);
+        set_phrase_mistakes.update(|m| *m += 1);
+        set_last_combo_milestone.set(0);
+    });
+
+    let on_word_typed = Callback::new(move |_: ()| {
+        set_total_words_typed.update(|w| *w += 1);
+
+        set_consecutive_correct_words.update(|count| {
+            *count += 1;
+            let current = *count;
+
+            if current > highest_combo.get() {
+                set_highest_combo.set(current);
+            }
+
+            let last_milestone = last_combo_milestone.get();
+
+            let should_trigger = match current {
+                5 => last_milestone < 5,
+                10 => last_milestone < 10,
+                15 => last_milestone < 15,
+                20 => last_milestone < 20,
+                40 => last_milestone < 40,
+                80 => last_milestone < 80,
+                160 => last_milestone < 160,
+                320 => last_milestone < 320,
+                640 => last_milestone < 640,
+                1000 => last_milestone < 1000,
+                _ => false,
+            };
+
+            if should_trigger {
+                set_last_combo_milestone.set(current);
+
+                match current {
+                    5 => set_combo_trigger.set(Some(ComboType::Streak5)),
+                    10 => set_combo_trigger.set(Some(ComboType::Streak10)),
+                    15 => set_combo_trigger.set(Some(ComboType::Streak15)),
+                    20 => set_combo_trigger.set(Some(ComboType::Streak20)),
+                    40 => set_combo_trigger.set(Some(ComboType::Streak40)),
+                    80 => set_combo_trigger.set(Some(ComboType::Streak80)),
+                    160 => set_combo_trigger.set(Some(ComboType::Streak160)),
+                    320 => set_combo_trigger.set(Some(ComboType::Streak320)),
+                    640 => set_combo_trigger.set(Some(ComboType::Streak640)),
+                    1000 => set_combo_trigger.set(Some(ComboType::Streak1000)),
+                    _ => {}
+                }
+            }
+        });
+    });
+
+    let on_word_deleted = Callback::new(move |_: ()| {
+        set_total_words_typed.update(|w| {
+            if *w > 0 {
+                *w -= 1;
+            }
+        });
+        let current_combo = consecutive_correct_words.get();
+        if current_combo >= 5 {
+            set_combo_trigger.set(Some(ComboType::ComboBroken));
+        }
+        set_consecutive_correct_words.set(0);
+        set_phrase_has_errors.set(true);
+        set_last_combo_milestone.set(0);
+    });
+
+    let on_complete = Callback::new(move |(wpm, accuracy, errors): (f64, f64, usize)| {
+        set_last_wpm.set(wpm);
+        set_last_accuracy.set(accuracy);
+        set_accuracy_sum.update(|sum| *sum += accuracy);
+        set_wpm_sum.update(|sum| *sum += wpm);
+        set_phrases_completed.update(|count| *count += 1);
+
+        set_best_phrase_wpm.update(|best| *best = f64::max(*best, wpm));
+        set_phrase_wpms.update(|wpms| wpms.push(wpm));
+
+        let keystrokes = phrase_keystrokes.get_untracked().max(1);
+        let keystroke_accuracy =
+            100.0 * (1.0 - phrase_mistakes.get_untracked() as f64 / keystrokes as f64);
+        let lost = lives_lost(errors, keystroke_accuracy);
+
+        if !phrase_has_errors.get() {
+            set_combo_trigger.set(Some(ComboType::PerfectPhrase));
+            set_perfect_streak.update(|streak| *streak += 1);
+            set_best_perfect_streak.update(|best| *best = (*best).max(perfect_streak.get()));
+
+            if regains_life(lives.get(), perfect_streak.get()) {
+                set_lives.update(|l| *l += 1);
+                set_combo_trigger.set(Some(ComboType::LifeGained));
+            }
+        } else {
+            set_perfect_streak.set(0);
+        }
+
+        if lost > 0 {
+            set_lives.update(|l| *l = l.saturating_sub(lost));
+            set_combo_trigger.set(Some(ComboType::LifeLost));
+        }
+
+        set_phrase_keystrokes.set(0);
+        set_phrase_mistakes.set(0);
+        set_phrase_has_errors.set(false);
+
+        if lives.get_untracked() == 0 {
+            finish_game();
+        } else {
+            set_phrase_index.update(|i| *i += 1);
+        }
+    });
+
+    let restart_game = move |_| {
+        set_game_state.set(GameState::Pending);
+        set_lives.set(INITIAL_LIVES);
+        set_phrase_index.set(0);
+        set_total_words_typed.set(0);
+        set_total_chars_typed.set(0);
+        set_last_wpm.set(0.0);
+        set_last_accuracy.set(100.0);
+        set_accuracy_sum.set(0.0);
+        set_wpm_sum.set(0.0);
+        set_phrases_completed.set(0);
+        set_phrase_keystrokes.set(0);
+        set_phrase_mistakes.set(0);
+        set_consecutive_correct_words.set(0);
+        set_phrase_has_errors.set(false);
+        set_last_combo_milestone.set(0);
+        set_highest_combo.set(0);
+        set_best_phrase_wpm.set(0.0);
+        set_perfect_streak.set(0);
+        set_best_perfect_streak.set(0);
+        set_phrase_wpms.set(Vec::new());
+        recorder.reset();
+
+        let phrases = base_phrases.get();
+        let next_seed = random_seed();
+        set_seed.set(next_seed);
+        set_shuffled_phrases.set(shuffle_phrases(&phrases, next_seed, augmentations.get()));
+    };
+
+    view! {
+        <div class="survival-mode">
+            <ComboPopup trigger=Signal::derive(move || combo_trigger.get()) />
+
+            <div class="survival-header">
+                <span class="survival-icon">
+                    <svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round" class="lucide lucide-heart-icon lucide-heart"><path d="M19 14c1.49-1.46 3-3.21 3-5.5A5.5 5.5 0 0 0 16.5 3c-1.76 0-3 .5-4.5 2-1.5-1.5-2.74-2-4.5-2A5.5 5.5 0 0 0 2 8.5c0 2.3 1.5 4.05 3 5.5l7 7Z"/></svg>
+                </span>
+                <h2 class="survival-title">"Survival"</h2>
+                <p class="survival-description">
+                    "Nessun limite di tempo, ma ogni errore lasciato costa una vita. Tre frasi perfette di fila te ne restituiscono una."
+                </p>
+            </div>
+
+            <div class="survival-lives" aria-label="Vite rimaste">
+                {move || {
+                    let remaining = lives.get();
+                    (0..MAX_LIVES)
+                        .map(|i| view! {
+                            <span
+                                class="survival-lives__heart"
+                                class:survival-lives__heart--lost={i >= remaining}
+                            >
+                                "♥"
+                            </span>
+                        })
+                        .collect_view()
+                }}
+            </div>
+
+            <MetricsBar
+                wpm=Signal::derive(move || last_wpm.get())
+                accuracy=Signal::derive(move || last_accuracy.get())
+                chars_typed=Signal::derive(move || total_chars_typed.get() as usize)
+                words_typed=Signal::derive(move || total_words_typed.get() as usize)
+            />
+
+            <div class="survival-typing-area">
+                <Show
+                    when=move || game_state.get() == GameState::Finished
+                    fallback=|| ()
+                >
+                    <div class="survival-game-over">
+                        <h3 class="survival-game-over-title">"Vite Esaurite!"</h3>
+                        <div class="survival-final-stats">
+                            <div class="survival-stat-item survival-stat-item--score">
+                                <span class="survival-stat-label">"Punteggio"</span>
+                                <span class="survival-stat-value survival-stat-value--score">
+                                    {move || final_score.get()}
+                                </span>
+                            </div>
+                            <div class="survival-stat-item">
+                                <span class="survival-stat-label">"Frasi Completate"</span>
+                                <span class="survival-stat-value">{move || phrases_completed.get()}</span>
+                            </div>
+                            <div class="survival-stat-item">
+                                <span class="survival-stat-label">"Parole Totali"</span>
+                                <span class="survival-stat-value">{move || total_words_typed.get()}</span>
+                            </div>
+                            <div class="survival-stat-item">
+                                <span class="survival-stat-label">"Combo Massima"</span>
+                                <span class="survival-stat-value">
+                                    {move || {
+                                        let combo = highest_combo.get();
+                                        let (emoji, _label) = combo_badge(combo);
+                                        format!("{} {}", emoji, combo)
+                                    }}
+                                </span>
+                            </div>
+                            <div class="survival-stat-item">
+                                <span class="survival-stat-label">"Frasi Perfette di Fila"</span>
+                                <span class="survival-stat-value">{move || best_perfect_streak.get()}</span>
+                            </div>
+                            <div class="survival-stat-item">
+                                <span class="survival-stat-label">"WPM Medio"</span>
+                                <span class="survival-stat-value">
+                                    {move || {
+                                        let avg = if phrases_completed.get() > 0 {
+                                            wpm_sum.get() / phrases_completed.get() as f64
+                                        } else {
+                                            0.0
+                                        };
+                                        format!("{:.0}", avg)
+                                    }}
+                                </span>
+                            </div>
+                            <div class="survival-stat-item">
+                                <span class="survival-stat-label">"Record Personale"</span>
+                                <span class="survival-stat-value">
+                                    {move || stats_ctx.get_stats().survival_best_score}
+                                </span>
+                            </div>
+                        </div>
+                        <ShareButton summary=last_summary />
+                        <ResultCardDownload card=last_card />
+                        // Nel torneo si passa al giocatore successivo, non si rigioca
+                        {on_finish.is_none().then(|| view! {
+                            <button class="survival-play-again-button" on:click=restart_game>
+                                "Gioca Ancora"
+                            </button>
+                        })}
+                    </div>
+                </Show>
+
+                <Show
+                    when=move || game_state.get() != GameState::Finished
+                    fallback=|| ()
+                >
+                    {move || {
+                        let phrases_list = shuffled_phrases.get();
+                        if phrases_list.is_empty() {
+                            view! {
+                                <div class="typing-display" style="min-height: 200px;">
+                                    <p>"Caricamento frasi..."</p>
+                                </div>
+                            }.into_any()
+                        } else {
+                            let current_text = phrases_list[phrase_index.get() % phrases_list.len()].clone();
+                            view! {
+                                <TypingEngine
+                                    text=current_text
+                                    on_complete=on_complete
+                                    on_char_typed=on_char_typed
+                                    on_char_error=on_char_error
+                                    on_word_typed=on_word_typed
+                                    on_word_deleted=on_word_deleted
+                                    on_keystroke=on_keystroke
+                                    error_mode=settings_ctx.get_error_mode()
+                                    match_rules=settings_ctx.get_match_rules()
+                                    input_model=settings_ctx.get_input_model()
+                                    caret_style=settings_ctx.get_caret_style()
+                                    on_fail=on_fail
+                                />
+                            }.into_any()
+                        }
+                    }}
+                </Show>
+            </div>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_error_left_costs_a_life() {
+        assert_eq!(lives_lost(0, 100.0), 0);
+        assert_eq!(lives_lost(2, 95.0), 2);
+    }
+
+    #[test]
+    fn a_sloppy_phrase_costs_one_more_life() {
+        // Errori tutti corretti, ma troppi tasti sbagliati lungo la frase
+        assert_eq!(lives_lost(0, 80.0), 1);
+        assert_eq!(lives_lost(1, 80.0), 2);
+        assert_eq!(lives_lost(0, ACCURACY_THRESHOLD), 0);
+    }
+
+    #[test]
+    fn perfect_streaks_give_back_a_life() {
+        assert!(!regains_life(2, 0));
+        assert!(!regains_life(2, 2));
+        assert!(regains_life(2, PERFECT_STREAK_FOR_LIFE));
+        assert!(regains_life(2, PERFECT_STREAK_FOR_LIFE * 2));
+    }
+
+    #[test]
+    fn lives_are_capped() {
+        assert!(regains_life(MAX_LIVES - 1, PERFECT_STREAK_FOR_LIFE));
+        assert!(!regains_life(MAX_LIVES, PERFECT_STREAK_FOR_LIFE));
+    }
+}
@@ -0,0 +1,417 @@
+// src/components/typing/typing_text.rs
+//
+use crate::components::typing::engine::{CharStatus, TypingState, is_separator};
+use leptos::ev;
+use leptos::html::Div;
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+// Pausa dopo l'ultimo tasto oltre la quale il cursore torna a lampeggiare
+const CARET_IDLE_MS: u64 = 600;
+
+// Parole per blocco di testo: i testi lunghi mostrano solo i blocchi vicini al cursore
+const CHUNK_WORDS: usize = 100;
+
+// Aspetto del cursore di battitura
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum CaretStyle {
+    #[default]
+    Line,
+    Block,
+    Underline,
+    Outline,
+}
+
+impl CaretStyle {
+    pub const ALL: [CaretStyle; 4] = [Self::Line, Self::Block, Self::Underline, Self::Outline];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Line => "Linea",
+            Self::Block => "Blocco",
+            Self::Underline => "Sottolineato",
+            Self::Outline => "Contorno",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Line => "Una barra sottile prima del carattere da digitare.",
+            Self::Block => "Un blocco pieno sopra il carattere da digitare.",
+            Self::Underline => "Una linea sotto il carattere da digitare.",
+            Self::Outline => "Un riquadro attorno al carattere da digitare.",
+        }
+    }
+
+    fn modifier(&self) -> &'static str {
+        match self {
+            Self::Line => "line",
+            Self::Block => "block",
+            Self::Underline => "underline",
+            Self::Outline => "outline",
+        }
+    }
+}
+
+// Un grafema della frase con i propri segnali: a ogni tasto si aggiornano
+// solo gli span dei grafemi cambiati
+#[derive(Clone)]
+struct CharCell {
+    grapheme: String,
+    status: RwSignal<CharStatus>,
+    // Lettere in più digitate prima di questo grafema
+    overflow: RwSignal<Vec<String>>,
+}
+
+impl CharCell {
+    fn class(&self) -> String {
+        let class = match self.status.get() {
+            CharStatus::Pending => "typing-char typing-char--pending",
+            CharStatus::Correct => "typing-char typing-char--correct",
+            CharStatus::Incorrect => "typing-char typing-char--incorrect",
+            CharStatus::Missed => "typing-char typing-char--missed",
+        };
+        if self.grapheme == "\n" {
+            format!("{} typing-char--newline", class)
+        } else {
+            class.to_string()
+        }
+    }
+}
+
+// Grafemi di una frase, separati una sola volta quando la frase viene mostrata
+#[derive(Clone, Copy)]
+pub struct TextCells {
+    cells: StoredValue<Vec<CharCell>>,
+    caret: RwSignal<usize>,
+    // Notificato quando le lettere in più spostano il testo
+    layout: Trigger,
+}
+
+impl TextCells {
+    pub fn new(state: &TypingState) -> Self {
+        let cells = state
+            .graphemes
+            .iter()
+            .enumerate()
+            .map(|(i, grapheme)| CharCell {
+                grapheme: grapheme.clone(),
+                status: RwSignal::new(state.char_statuses[i]),
+                overflow: RwSignal::new(state.overflow[i].clone()),
+            })
+            .collect();
+
+        Self {
+            cells: StoredValue::new(cells),
+            caret: RwSignal::new(state.current_index),
+            layout: Trigger::new(),
+        }
+    }
+
+    // Riallinea i grafemi tra `from` e il cursore: un tasto cambia solo quelli
+    pub fn sync(&self, state: &TypingState, from: usize) {
+        let start = from.min(state.current_index);
+        let end = from.max(state.current_index) + 1;
+        self.sync_range(state, start, end);
+    }
+
+    // Riallinea tutta la frase (es. salto a un punto qualsiasi di un replay)
+    pub fn sync_all(&self, state: &TypingState) {
+        self.sync_range(state, 0, state.graphemes.len());
+    }
+
+    fn sync_range(&self, state: &TypingState, start: usize, end: usize) {
+        self.cells.with_value(|cells| {
+            let end = end.min(cells.len()).min(state.char_statuses.len());
+            for (i, cell) in cells.iter().enumerate().take(end).skip(start) {
+                if cell.status.get_untracked() != state.char_statuses[i] {
+                    cell.status.set(state.char_statuses[i]);
+                }
+                if cell.overflow.with_untracked(|o| *o != state.overflow[i]) {
+                    cell.overflow.set(state.overflow[i].clone());
+                    self.layout.notify();
+                }
+            }
+        });
+
+        if self.caret.get_untracked() != state.current_index {
+            self.caret.set(state.current_index);
+        }
+    }
+}
+
+// Span del grafema `index`; oltre l'ultimo grafema si usa l'ultimo, con `true`
+fn find_char(container: &HtmlElement, index: usize) -> Option<(HtmlElement, bool)> {
+    let find = |i: usize| {
+        container
+            .query_selector(&format!("[data-index=\"{}\"]", i))
+            .ok()
+            .flatten()
+            .and_then(|el| el.dyn_into::<HtmlElement>().ok())
+    };
+    find(index)
+        .map(|el| (el, false))
+        .or_else(|| find(index.checked_sub(1)?).map(|el| (el, true)))
+}
+
+// Posizione e dimensioni del grafema `index` nel testo, in pixel.
+// Oltre l'ultimo grafema il cursore si mette subito dopo di esso
+fn measure_char(container: &HtmlElement, index: usize) -> Option<(f64, f64, f64, f64)> {
+    let (el, past_end) = find_char(container, index)?;
+    let width = el.offset_width() as f64;
+    let left = el.offset_left() as f64 + if past_end { width } else { 0.0 };
+    Some((
+        left,
+        el.offset_top() as f64,
+        width,
+        el.offset_height() as f64,
+    ))
+}
+
+// Riga del grafema `index`: altezza e distanza dall'inizio del testo
+fn measure_line(container: &HtmlElement, index: usize) -> Option<(f64, f64)> {
+    let (el, _) = find_char(container, index)?;
+    let word = el
+        .closest(".typing-word")
+        .ok()
+        .flatten()?
+        .dyn_into::<HtmlElement>()
+        .ok()?;
+    Some((word.offset_top() as f64, word.offset_height() as f64))
+}
+
+// Cursore posizionato sopra il testo: scivola da un grafema all'altro
+// e smette di lampeggiare mentre si digita
+#[component]
+fn Caret(
+    container: NodeRef<Div>,
+    layout: Trigger,
+    #[prop(into)] index: Signal<Option<usize>>,
+    class: String,
+) -> impl IntoView {
+    let (rect, set_rect) = signal::<Option<(f64, f64, f64, f64)>>(None);
+    let (typing, set_typing) = signal(false);
+    let moves = StoredValue::new(0u32);
+
+    // Eseguita al frame successivo: il cursore potrebbe non esistere più
+    let measure = move || {
+        let rect = container
+            .try_get_untracked()
+            .flatten()
+            .zip(index.try_get_untracked().flatten())
+            .and_then(|(container, index)| measure_char(&container, index));
+        set_rect.try_set(rect);
+    };
+
+    Effect::new(move |previous: Option<Option<usize>>| {
+        let current = index.get();
+        container.track();
+        layout.track();
+        // Si misura a layout aggiornato
+        request_animation_frame(measure);
+
+        // Dal primo spostamento il lampeggio si ferma fino alla pausa successiva
+        if previous.is_some_and(|p| p != current) {
+            set_typing.set(true);
+            moves.update_value(|m| *m += 1);
+            let this_move = moves.get_value();
+            set_timeout(
+                move || {
+                    if moves.try_get_value() == Some(this_move) {
+                        set_typing.set(false);
+                    }
+                },
+                Duration::from_millis(CARET_IDLE_MS),
+            );
+        }
+        current
+    });
+
+    view! {
+        <Show when=move || rect.get().is_some()>
+            <span
+                class=class.clone()
+                class:typing-caret--typing=move || typing.get()
+                style=move || {
+                    let (x, y, w, h) = rect.get().unwrap_or_default();
+                    format!(
+                        "--caret-x: {}px; --caret-y: {}px; --caret-w: {}px; --caret-h: {}px;",
+                        x, y, w, h
+                    )
+                }
+            />
+        </Show>
+    }
+}
+
+// Testo della frase con lo stato di ogni carattere, in sola lettura.
+// Usato dall'engine durante la partita e dal visualizzatore dei replay.
+// Mostra al massimo tre righe e scorre di una riga alla volta seguendo il cursore
+#[component]
+pub fn TypingText(
+    cells: TextCells,
+    #[prop(into, default = Signal::stored(None))] ghost_index: Signal<Option<usize>>,
+    #[prop(optional)] caret_style: CaretStyle,
+    // Testo su più righe (codice): allineato a sinistra, con gli a capo visibili
+    #[prop(optional)] multiline: bool,
+) -> impl IntoView {
+    let container = NodeRef::<Div>::new();
+    let caret = cells.caret;
+    let layout = cells.layout;
+
+    // Un grafema per span, così accenti combinanti ed emoji restano interi.
+    // La struttura si costruisce una volta: poi cambiano solo le classi
+    let words = cells.cells.with_value(|cells| {
+        let mut words: Vec<Vec<(usize, CharCell)>> = Vec::new();
+        let mut current_word = Vec::new();
+
+        for (index, cell) in cells.iter().enumerate() {
+            if is_separator(&cell.grapheme) {
+                if !current_word.is_empty() {
+                    words.push(std::mem::take(&mut current_word));
+                }
+                words.push(vec![(index, cell.clone())]);
+            } else {
+                current_word.push((index, cell.clone()));
+            }
+        }
+
+        if !current_word.is_empty() {
+            words.push(current_word);
+        }
+
+        // Lettere in più digitate in coda alla parola
+        words
+            .into_iter()
+            .map(|word| {
+                let overflow = word
+                    .last()
+                    .and_then(|(i, _)| cells.get(i + 1))
+                    .map(|cell| cell.overflow);
+                (word, overflow)
+            })
+            .collect::<Vec<_>>()
+    });
+
+    // Blocchi di parole che iniziano sempre su una riga nuova: nasconderne
+    // uno non cambia gli a capo degli altri
+    let mut chunks: Vec<Vec<_>> = vec![Vec::new()];
+    for (word, overflow) in words {
+        let ends_word = word
+            .first()
+            .is_some_and(|(_, cell)| is_separator(&cell.grapheme));
+        chunks.last_mut().unwrap().push((word, overflow));
+        if ends_word && chunks.last().unwrap().len() >= CHUNK_WORDS * 2 {
+            chunks.push(Vec::new());
+        }
+    }
+    let chunk_starts: Vec<usize> = chunks
+        .iter()
+        .map(|chunk| {
+            chunk
+                .first()
+                .and_then(|(word, _)| word.first())
+                .map_or(usize::MAX, |(index, _)| *index)
+        })
+        .collect();
+
+    // Si mostrano solo il blocco del cursore e i due vicini
+    let visible = Memo::new(move |_| {
+        let index = caret.get();
+        let chunk = chunk_starts
+            .partition_point(|start| *start <= index)
+            .saturating_sub(1);
+        chunk.saturating_sub(1)..=chunk + 1
+    });
+
+    // Scorrimento: la riga del cursore resta la seconda del riquadro
+    let (scroll, set_scroll) = signal(0.0);
+    Effect::new(move |_| {
+        let index = caret.get();
+        container.track();
+        layout.track();
+        request_animation_frame(move || {
+            let line = container
+                .try_get_untracked()
+                .flatten()
+                .and_then(|container| measure_line(&container, index));
+            if let Some((top, height)) = line {
+                set_scroll.try_set((top - height).max(0.0));
+            }
+        });
+    });
+
+    // Blocchi mostrati o nascosti e a capo diversi spostano il testo
+    Effect::new(move |previous: Option<()>| {
+        visible.track();
+        if previous.is_some() {
+            layout.notify();
+        }
+    });
+    let resize = window_event_listener(ev::resize, move |_| layout.notify());
+    on_cleanup(move || resize.remove());
+
+    view! {
+        <div class="typing-viewport">
+            <div
+                class="typing-text"
+                class:typing-text--multiline=multiline
+                node_ref=container
+                style:transform=move || format!("translateY(-{}px)", scroll.get())
+            >
+                {chunks.into_iter().enumerate().map(|(chunk_index, chunk)| {
+                    let chunk = StoredValue::new(chunk);
+                    view! {
+                        <Show when=move || visible.get().contains(&chunk_index)>
+                            <div class="typing-chunk">
+                                {chunk.get_value().into_iter().map(|(word, overflow)| {
+                                    // Dopo un a capo il testo riprende su una riga nuova
+                                    let breaks_line = word
+                                        .first()
+                                        .is_some_and(|(_, cell)| cell.grapheme == "\n");
+                                    view! {
+                                    <span class="typing-word">
+                                        {word.into_iter().map(|(index, cell)| {
+                                            let ch_display = match cell.grapheme.as_str() {
+                                                " " => "\u{00A0}".to_string(),
+                                                "\n" => "↵".to_string(),
+                                                _ => cell.grapheme.clone(),
+                                            };
+
+                                            view! {
+                                                <span class=move || cell.class() data-index=index>
+                                                    {ch_display}
+                                                </span>
+                                            }
+                                        }).collect_view()}
+                                        {move || overflow.map(|overflow| overflow.get().into_iter().map(|extra| view! {
+                                            <span class="typing-char typing-char--extra">{extra}</span>
+                                        }).collect_view())}
+                                    </span>
+                                    {breaks_line.then(|| view! { <span class="typing-break"></span> })}
+                                    }
+                                }).collect_view()}
+                            </div>
+                        </Show>
+                    }
+                }).collect_view()}
+                // Cursore fantasma: la posizione del record personale allo stesso istante
+                <Caret
+                    container=container
+                    layout=layout
+                    index=ghost_index
+                    class="typing-caret typing-caret--ghost".to_string()
+                />
+                <Caret
+                    container=container
+                    layout=layout
+                    index=Signal::derive(move || Some(caret.get()))
+                    class=format!("typing-caret typing-caret--{}", caret_style.modifier())
+                />
+            </div>
+        </div>
+    }
+}
@@ -1,8 +1,12 @@
 // src/components/typing/zen_mode.rs (AGGIORNATO con tracking stats)
 //
+use crate::augment::Augmentations;
+use crate::components::typing::engine::KeyInput;
 use crate::components::typing::{ComboPopup, MetricsBar, TypingEngine, combo_popup::ComboType};
+use crate::profile_store::use_profiles;
+use crate::recording::{Replay, RunRecorder};
 use crate::settings_store::use_settings;
-use crate::stats_store::{GameMode as StatsGameMode, use_stats};
+use crate::stats_store::{GameMode as StatsGameMode, SessionRecord, use_stats};
 use leptos::prelude::*;
 use rand::rngs::OsRng;
 use rand::seq::SliceRandom;
@@ -20,16 +24,17 @@ fn load_phrases_from_content(json_content: &str) -> Vec<String> {
     data.phrases
 }
 
-fn shuffle_phrases(phrases: &[String]) -> Vec<String> {
+fn shuffle_phrases(phrases: &[String], augmentations: Augmentations) -> Vec<String> {
     let mut shuffled = phrases.to_vec();
     shuffled.shuffle(&mut OsRng);
-    shuffled
+    augmentations.apply(&shuffled, &mut OsRng)
 }
 
 #[component]
 pub fn ZenMode() -> impl IntoView {
     let settings_ctx = use_settings();
     let stats_ctx = use_stats();
+    let profile_ctx = use_profiles();
 
     let base_phrases = Memo::new(move |_| {
         let difficulty = settings_ctx.get_difficulty();
@@ -37,6 +42,9 @@ pub fn ZenMode() -> impl IntoView {
         load_phrases_from_content(json_content)
     });
 
+    // Numeri, maiuscole e punteggiatura aggiunti alle frasi del dataset
+    let augmentations = Memo::new(move |_| settings_ctx.get_augmentations());
+
     let (shuffled_phrases, set_shuffled_phrases) = signal(Vec::<String>::new());
     let (phrase_index, set_phrase_index) = signal(0_usize);
     let (last_wpm, set_last_wpm) = signal(0.0);
@@ -52,6 +60,11 @@ pub fn ZenMode() -> impl IntoView {
     let (last_combo_milestone, set_last_combo_milestone) = signal(0_usize);
     let (highest_combo, set_highest_combo) = signal(0_usize);
 
+    // Record della partita per gli obiettivi
+    let (best_phrase_wpm, set_best_phrase_wpm) = signal(0.0);
+    let (perfect_streak, set_perfect_streak) = signal(0_u32);
+    let (best_perfect_streak, set_best_perfect_streak) = signal(0_u32);
+
     // Tracking sessione per stats
     let (session_started, set_session_started) = signal(false);
     let (session_start_time, set_session_start_time) = signal(0.0);
@@ -61,9 +74,19 @@ pub fn ZenMode() -> impl IntoView {
     let (wpm_sum, set_wpm_sum) = signal(0.0);
     let (phrases_completed, set_phrases_completed) = signal(0_u32);
 
+    // Registrazione dei tasti per il replay: le frasi sono numerate nella sessione
+    let recorder = RunRecorder::new();
+    Effect::new(move |_| {
+        recorder.set_rules(
+            settings_ctx.get_error_mode(),
+            settings_ctx.get_match_rules(),
+            settings_ctx.get_input_model(),
+        )
+    });
+
     Effect::new(move |_| {
         let phrases = base_phrases.get();
-        set_shuffled_phrases.set(shuffle_phrases(&phrases));
+        set_shuffled_phrases.set(shuffle_phrases(&phrases, augmentations.get()));
         set_phrase_index.set(0);
         set_chars_typed.set(0);
         set_words_typed.set(0);
@@ -71,7 +94,11 @@ pub fn ZenMode() -> impl IntoView {
         set_phrase_has_errors.set(false);
         set_last_combo_milestone.set(0);
         set_highest_combo.set(0);
+        set_best_phrase_wpm.set(0.0);
+        set_perfect_streak.set(0);
+        set_best_perfect_streak.set(0);
         set_session_started.set(false);
+        recorder.reset();
         set_total_session_words.set(0);
         set_total_session_chars.set(0);
         set_accuracy_sum.set(0.0);
@@ -99,16 +126,22 @@ pub fn ZenMode() -> impl IntoView {
         set_last_combo_milestone.set(0);
     });
 
-    let on_complete = Callback::new(move |(wpm, accuracy): (f64, f64)| {
+    let on_complete = Callback::new(move |(wpm, accuracy, _): (f64, f64, usize)| {
         set_last_wpm.set(wpm);
         set_last_accuracy.set(accuracy);
         set_accuracy_sum.update(|sum| *sum += accuracy);
         set_wpm_sum.update(|sum| *sum += wpm);
         set_phrases_completed.update(|count| *count += 1);
 
+        set_best_phrase_wpm.update(|best| *best = f64::max(*best, wpm));
+
         // Se la frase è stata completata senza errori
         if !phrase_has_errors.get() {
             set_combo_trigger.set(Some(ComboType::PerfectPhrase));
+            set_perfect_streak.update(|streak| *streak += 1);
+            set_best_perfect_streak.update(|best| *best = (*best).max(perfect_streak.get()));
+        } else {
+            set_perfect_streak.set(0);
         }
 
         set_is_transitioning.set(true);
@@ -119,7 +152,7 @@ pub fn ZenMode() -> impl IntoView {
                     *i += 1;
                     if *i >= shuffled_phrases.get().len() {
                         let phrases = base_phrases.get();
-                        set_shuffled_phrases.set(shuffle_phrases(&phrases));
+                        set_shuffled_phrases.set(shuffle_phrases(&phrases, augmentations.get()));
                         *i = 0;
                     }
                 });
@@ -132,14 +165,22 @@ pub fn ZenMode() -> impl IntoView {
         );
     });
 
+    let on_keystroke = Callback::new(move |(input, _): (KeyInput, usize)| {
+        recorder.record(
+            phrases_completed.get_untracked() as usize,
+            &current_phrase.get_untracked(),
+            input,
+        );
+    });
+
     let on_char_typed = Callback::new(move |_: ()| {
         // Inizia la sessione al primo carattere
         if !session_started.get() {
             set_session_started.set(true);
-            if let Some(win) = window() {
-                if let Some(perf) = win.performance() {
-                    set_session_start_time.set(perf.now());
-                }
+            if let Some(win) = window()
+                && let Some(perf) = win.performance()
+            {
+                set_session_start_time.set(perf.now());
             }
         }
 
@@ -219,61 +260,94 @@ pub fn ZenMode() -> impl IntoView {
     });
 
     // Funzione per terminare la sessione e salvare le stats
-    let end_session = move |_| {
-        if !session_started.get() || phrases_completed.get() == 0 {
-            return;
-        }
+    let end_session = move || {
+        // Una sessione senza frasi completate viene scartata senza registrarla
+        if session_started.get() && phrases_completed.get() > 0 {
+            let session_time = if let Some(win) = window() {
+                if let Some(perf) = win.performance() {
+                    (perf.now() - session_start_time.get()) / 1000.0 // converti in secondi
+                } else {
+                    0.0
+                }
+            } else {
+                0.0
+            };
 
-        let session_time = if let Some(win) = window() {
-            if let Some(perf) = win.performance() {
-                (perf.now() - session_start_time.get()) / 1000.0 // converti in secondi
+            let avg_wpm = if phrases_completed.get() > 0 {
+                wpm_sum.get() / phrases_completed.get() as f64
             } else {
                 0.0
-            }
-        } else {
-            0.0
-        };
+            };
 
-        let avg_wpm = if phrases_completed.get() > 0 {
-            wpm_sum.get() / phrases_completed.get() as f64
-        } else {
-            0.0
-        };
+            let avg_accuracy = if phrases_completed.get() > 0 {
+                accuracy_sum.get() / phrases_completed.get() as f64
+            } else {
+                100.0
+            };
 
-        let avg_accuracy = if phrases_completed.get() > 0 {
-            accuracy_sum.get() / phrases_completed.get() as f64
-        } else {
-            100.0
-        };
-
-        stats_ctx.record_game(
-            StatsGameMode::Zen,
-            total_session_words.get(),
-            total_session_chars.get(),
-            session_time,
-            avg_wpm,
-            avg_accuracy,
-            highest_combo.get(),
-            None,
-        );
+            let record = SessionRecord {
+                timestamp: js_sys::Date::now(),
+                mode: StatsGameMode::Zen,
+                difficulty: settings_ctx.get_difficulty(),
+                words: total_session_words.get(),
+                chars: total_session_chars.get(),
+                time: session_time,
+                wpm: avg_wpm,
+                accuracy: avg_accuracy,
+                combo: highest_combo.get(),
+                score: None,
+                best_phrase_wpm: best_phrase_wpm.get(),
+                perfect_streak: best_perfect_streak.get(),
+                match_rules: settings_ctx.get_match_rules(),
+                augmentations: augmentations.get(),
+                symbols: Vec::new(),
+            };
+            let _ = stats_ctx.save_replay(Replay::new(
+                &record,
+                &profile_ctx.active().name,
+                None,
+                recorder.recording(),
+            ));
+            stats_ctx.record_game(record);
+        }
 
         // Reset sessione
         set_session_started.set(false);
+        recorder.reset();
         set_total_session_words.set(0);
         set_total_session_chars.set(0);
         set_accuracy_sum.set(0.0);
         set_wpm_sum.set(0.0);
         set_phrases_completed.set(0);
         set_highest_combo.set(0);
+        set_best_phrase_wpm.set(0.0);
+        set_perfect_streak.set(0);
+        set_best_perfect_streak.set(0);
         set_consecutive_correct_words.set(0);
         set_last_combo_milestone.set(0);
 
         // Ricarica le frasi
         let phrases = base_phrases.get();
-        set_shuffled_phrases.set(shuffle_phrases(&phrases));
+        set_shuffled_phrases.set(shuffle_phrases(&phrases, augmentations.get()));
         set_phrase_index.set(0);
     };
 
+    // Morte improvvisa: la sessione si chiude e si riparte da una frase nuova
+    let on_fail = Callback::new(move |_: ()| {
+        end_session();
+        set_is_transitioning.set(true);
+        set_timeout(
+            move || {
+                set_chars_typed.set(0);
+                set_words_typed.set(0);
+                set_consecutive_correct_words.set(0);
+                set_phrase_has_errors.set(false);
+                set_is_transitioning.set(false);
+            },
+            std::time::Duration::from_millis(400),
+        );
+    });
+
     view! {
         <div class="zen-mode">
             <ComboPopup trigger=Signal::derive(move || combo_trigger.get()) />
@@ -322,6 +396,12 @@ pub fn ZenMode() -> impl IntoView {
                                     on_char_error=on_char_error
                                     on_word_typed=on_word_typed
                                     on_word_deleted=on_word_deleted
+                                    on_keystroke=on_keystroke
+                                    error_mode=settings_ctx.get_error_mode()
+                                    match_rules=settings_ctx.get_match_rules()
+                                    input_model=settings_ctx.get_input_model()
+                                    caret_style=settings_ctx.get_caret_style()
+                                    on_fail=on_fail
                                 />
                             </div>
                         }.into_any()
@@ -333,7 +413,7 @@ pub fn ZenMode() -> impl IntoView {
             // Pulsante per terminare la sessione
             <Show when=move || session_started.get()>
                 <div class="zen-session-controls">
-                    <button class="zen-end-session-button" on:click=end_session>
+                    <button class="zen-end-session-button" on:click=move |_| end_session()>
                         <svg xmlns="http://www.w3.org/2000/svg" width="18" height="18" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
                             <path d="M9 9h6v6H9z"/>
                             <path d="M3 12a9 9 0 1 0 18 0 9 9 0 1 0-18 0"/>
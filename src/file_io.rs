@@ -0,0 +1,53 @@
+// src/file_io.rs
+//
+// Download e lettura di file locali dal browser
+
+use leptos::prelude::set_timeout;
+use std::time::Duration;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Blob, BlobPropertyBag, File, HtmlAnchorElement, Url, window};
+
+// Avvia il download di un file generato in memoria
+pub fn download_file(filename: &str, mime: &str, content: &str) -> Result<(), JsValue> {
+    let parts = js_sys::Array::of1(&JsValue::from_str(content));
+    let options = BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob = Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    download_blob(filename, &blob)
+}
+
+// Attesa prima di revocare l'object URL: revocato subito, alcuni browser annullano il download
+const REVOKE_DELAY: Duration = Duration::from_secs(1);
+
+pub fn download_blob(filename: &str, blob: &Blob) -> Result<(), JsValue> {
+    let url = Url::create_object_url_with_blob(blob)?;
+    let result = download_url(filename, &url);
+    set_timeout(
+        move || {
+            let _ = Url::revoke_object_url(&url);
+        },
+        REVOKE_DELAY,
+    );
+    result
+}
+
+// Scarica una risorsa già disponibile come URL (object URL o data URL)
+pub fn download_url(filename: &str, url: &str) -> Result<(), JsValue> {
+    let document = window()
+        .and_then(|w| w.document())
+        .ok_or_else(|| JsValue::from_str("document non disponibile"))?;
+    let anchor = document
+        .create_element("a")?
+        .dyn_into::<HtmlAnchorElement>()?;
+    anchor.set_href(url);
+    anchor.set_download(filename);
+    anchor.click();
+    Ok(())
+}
+
+pub async fn read_file_text(file: File) -> Result<String, JsValue> {
+    let text = JsFuture::from(file.text()).await?;
+    text.as_string()
+        .ok_or_else(|| JsValue::from_str("contenuto del file non leggibile"))
+}
@@ -0,0 +1,377 @@
+// src/leaderboard_store.rs
+//
+// Classifica locale delle migliori partite, condivisa tra tutti i profili
+
+use crate::augment::Augmentations;
+use crate::components::typing::engine::MatchRules;
+use crate::migrations::Schema;
+use crate::settings_store::DatasetDifficulty;
+use crate::stats_store::{GameMode, SessionRecord};
+use crate::storage::{
+    SharedBackend, StorageBackend, StorageError, default_backend, load_versioned, save_versioned,
+};
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const LEADERBOARD_STORAGE_KEY: &str = "litterix_leaderboard";
+
+const LEADERBOARD_SCHEMA: Schema = Schema {
+    current_version: 2,
+    migrations: &[migrate_leaderboard_v0_to_v1, migrate_leaderboard_v1_to_v2],
+};
+
+// La v0 è il blob legacy senza envelope: i campi coincidono con la v1
+fn migrate_leaderboard_v0_to_v1(data: Value) -> Result<Value, String> {
+    Ok(data)
+}
+
+// Nella v1 le regole di confronto erano sulla singola partita: ogni tabella
+// viene divisa in una per ciascuna combinazione di regole presente
+fn migrate_leaderboard_v1_to_v2(mut data: Value) -> Result<Value, String> {
+    let Some(tables) = data.get_mut("tables").and_then(Value::as_array_mut) else {
+        return Ok(data);
+    };
+
+    let mut split: Vec<(MatchRules, Value)> = Vec::new();
+    for table in std::mem::take(tables) {
+        let Value::Object(mut table) = table else {
+            return Err("tabella della classifica non valida".to_string());
+        };
+        let entries = match table.remove("entries") {
+            Some(Value::Array(entries)) => entries,
+            _ => Vec::new(),
+        };
+        let first = split.len();
+        for entry in entries {
+            let rules: MatchRules = entry
+                .get("match_rules")
+                .cloned()
+                .and_then(|rules| serde_json::from_value(rules).ok())
+                .unwrap_or_default();
+            let index = match split[first..].iter().position(|(r, _)| *r == rules) {
+                Some(index) => first + index,
+                None => {
+                    let mut rules_table = table.clone();
+                    rules_table.insert(
+                        "match_rules".to_string(),
+                        serde_json::to_value(rules).map_err(|e| e.to_string())?,
+                    );
+                    rules_table.insert("entries".to_string(), Value::Array(Vec::new()));
+                    split.push((rules, Value::Object(rules_table)));
+                    split.len() - 1
+                }
+            };
+            if let Some(Value::Array(rules_entries)) = split[index].1.get_mut("entries") {
+                rules_entries.push(entry);
+            }
+        }
+    }
+    *tables = split.into_iter().map(|(_, table)| table).collect();
+    Ok(data)
+}
+
+// Partite conservate per ogni combinazione di modalità, difficoltà, durata,
+// regole di confronto e arricchimenti
+pub const LEADERBOARD_SIZE: usize = 10;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub timestamp: f64,
+    // Il nome si legge dal registro dei profili, così segue le rinomine;
+    // il vecchio campo `profile_name` dei dati salvati viene ignorato
+    pub profile_id: String,
+    pub wpm: f64,
+    pub accuracy: f64,
+    pub combo: usize,
+    pub score: u32,
+}
+
+impl LeaderboardEntry {
+    // Ordine di classifica: punteggio, poi WPM, poi accuracy
+    fn ranks_above(&self, other: &LeaderboardEntry) -> bool {
+        (self.score, self.wpm, self.accuracy) > (other.score, other.wpm, other.accuracy)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardTable {
+    pub mode: GameMode,
+    pub difficulty: DatasetDifficulty,
+    pub duration: u32, // durata configurata della modalità, in secondi
+    // Regole permissive e testo arricchito cambiano la sfida: classifiche separate
+    #[serde(default)]
+    pub match_rules: MatchRules,
+    #[serde(default)]
+    pub augmentations: Augmentations,
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+impl LeaderboardTable {
+    fn matches(
+        &self,
+        mode: GameMode,
+        difficulty: DatasetDifficulty,
+        duration: u32,
+        match_rules: MatchRules,
+        augmentations: Augmentations,
+    ) -> bool {
+        self.mode == mode
+            && self.difficulty == difficulty
+            && self.duration == duration
+            && self.match_rules == match_rules
+            && self.augmentations == augmentations
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Leaderboard {
+    pub tables: Vec<LeaderboardTable>,
+}
+
+impl Leaderboard {
+    pub fn load(backend: &dyn StorageBackend) -> Self {
+        load_versioned(backend, LEADERBOARD_STORAGE_KEY, &LEADERBOARD_SCHEMA)
+    }
+
+    pub fn save(&self, backend: &dyn StorageBackend) -> Result<(), StorageError> {
+        save_versioned(backend, LEADERBOARD_STORAGE_KEY, &LEADERBOARD_SCHEMA, self)
+    }
+
+    pub fn entries(
+        &self,
+        mode: GameMode,
+        difficulty: DatasetDifficulty,
+        duration: u32,
+        match_rules: MatchRules,
+        augmentations: Augmentations,
+    ) -> Vec<LeaderboardEntry> {
+        self.tables
+            .iter()
+            .find(|t| t.matches(mode, difficulty, duration, match_rules, augmentations))
+            .map(|t| t.entries.clone())
+            .unwrap_or_default()
+    }
+
+    // Inserisce la partita se entra tra le prime N; ritorna la posizione (da 1)
+    pub fn insert(
+        &mut self,
+        mode: GameMode,
+        difficulty: DatasetDifficulty,
+        duration: u32,
+        match_rules: MatchRules,
+        augmentations: Augmentations,
+        entry: LeaderboardEntry,
+    ) -> Option<usize> {
+        let index = match self
+            .tables
+            .iter()
+            .position(|t| t.matches(mode, difficulty, duration, match_rules, augmentations))
+        {
+            Some(index) => index,
+            None => {
+                self.tables.push(LeaderboardTable {
+                    mode,
+                    difficulty,
+                    duration,
+                    match_rules,
+                    augmentations,
+                    entries: Vec::new(),
+                });
+                self.tables.len() - 1
+            }
+        };
+
+        let entries = &mut self.tables[index].entries;
+        let position = entries
+            .iter()
+            .position(|e| entry.ranks_above(e))
+            .unwrap_or(entries.len());
+        if position >= LEADERBOARD_SIZE {
+            return None;
+        }
+        entries.insert(position, entry);
+        entries.truncate(LEADERBOARD_SIZE);
+        Some(position + 1)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct LeaderboardContext {
+    board: RwSignal<Leaderboard>,
+    backend: StoredValue<SharedBackend>,
+}
+
+impl Default for LeaderboardContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LeaderboardContext {
+    pub fn new() -> Self {
+        let backend = default_backend();
+        Self {
+            board: RwSignal::new(Leaderboard::load(backend.as_ref())),
+            backend: StoredValue::new(backend),
+        }
+    }
+
+    pub fn entries(
+        &self,
+        mode: GameMode,
+        difficulty: DatasetDifficulty,
+        duration: u32,
+        match_rules: MatchRules,
+        augmentations: Augmentations,
+    ) -> Vec<LeaderboardEntry> {
+        self.board
+            .get()
+            .entries(mode, difficulty, duration, match_rules, augmentations)
+    }
+
+    // Propone una partita conclusa alla classifica; ritorna la posizione ottenuta
+    pub fn submit(&self, record: &SessionRecord, duration: u32, profile_id: &str) -> Option<usize> {
+        let entry = LeaderboardEntry {
+            timestamp: record.timestamp,
+            profile_id: profile_id.to_string(),
+            wpm: record.wpm,
+            accuracy: record.accuracy,
+            combo: record.combo,
+            score: record.score.unwrap_or_default(),
+        };
+
+        let mut rank = None;
+        self.board.update(|board| {
+            rank = board.insert(
+                record.mode,
+                record.difficulty,
+                duration,
+                record.match_rules,
+                record.augmentations,
+                entry,
+            );
+        });
+        if rank.is_some() {
+            let board = self.board.get_untracked();
+            let _ = self.backend.with_value(|b| board.save(b.as_ref()));
+        }
+        rank
+    }
+}
+
+pub fn use_leaderboard() -> LeaderboardContext {
+    use_context::<LeaderboardContext>()
+        .expect("LeaderboardContext deve essere fornito a livello di App")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryBackend;
+
+    fn entry(score: u32) -> LeaderboardEntry {
+        LeaderboardEntry {
+            timestamp: score as f64,
+            profile_id: "default".to_string(),
+            wpm: 50.0,
+            accuracy: 95.0,
+            combo: 10,
+            score,
+        }
+    }
+
+    fn lenient() -> MatchRules {
+        MatchRules {
+            ignore_case: true,
+            ..MatchRules::default()
+        }
+    }
+
+    #[test]
+    fn lenient_runs_get_their_own_table() {
+        let mut board = Leaderboard::default();
+        let (mode, difficulty) = (GameMode::Rush, DatasetDifficulty::Base);
+        let plain = Augmentations::default();
+        board.insert(
+            mode,
+            difficulty,
+            60,
+            MatchRules::default(),
+            plain,
+            entry(10),
+        );
+        board.insert(mode, difficulty, 60, lenient(), plain, entry(99));
+
+        let strict = board.entries(mode, difficulty, 60, MatchRules::default(), plain);
+        assert_eq!(strict, [entry(10)]);
+        assert_eq!(
+            board.entries(mode, difficulty, 60, lenient(), plain),
+            [entry(99)]
+        );
+    }
+
+    #[test]
+    fn v1_tables_are_split_by_match_rules() {
+        let mut lenient_entry = serde_json::to_value(entry(99)).unwrap();
+        lenient_entry["match_rules"] = serde_json::to_value(lenient()).unwrap();
+        let legacy = serde_json::json!({
+            "version": 1,
+            "data": { "tables": [{
+                "mode": "Rush",
+                "difficulty": "Base",
+                "duration": 60,
+                "entries": [lenient_entry, entry(10)],
+            }] },
+        });
+        let backend = MemoryBackend::default();
+        backend
+            .set(LEADERBOARD_STORAGE_KEY, &legacy.to_string())
+            .unwrap();
+
+        let board = Leaderboard::load(&backend);
+        assert_eq!(board.tables.len(), 2);
+        let (mode, difficulty) = (GameMode::Rush, DatasetDifficulty::Base);
+        let plain = Augmentations::default();
+        assert_eq!(
+            board.entries(mode, difficulty, 60, MatchRules::default(), plain),
+            [entry(10)]
+        );
+        assert_eq!(
+            board.entries(mode, difficulty, 60, lenient(), plain),
+            [entry(99)]
+        );
+    }
+
+    #[test]
+    fn saved_names_are_ignored_in_favour_of_the_profile_id() {
+        let mut stored = serde_json::to_value(entry(42)).unwrap();
+        stored["profile_name"] = "Vecchio nome".into();
+        let saved = serde_json::json!({
+            "version": 2,
+            "data": { "tables": [{
+                "mode": "Rush",
+                "difficulty": "Base",
+                "duration": 60,
+                "entries": [stored],
+            }] },
+        });
+        let backend = MemoryBackend::default();
+        backend
+            .set(LEADERBOARD_STORAGE_KEY, &saved.to_string())
+            .unwrap();
+
+        let board = Leaderboard::load(&backend);
+        let entries = board.entries(
+            GameMode::Rush,
+            DatasetDifficulty::Base,
+            60,
+            MatchRules::default(),
+            Augmentations::default(),
+        );
+        assert_eq!(entries, [entry(42)]);
+        let resaved = serde_json::to_value(&entries[0]).unwrap();
+        assert!(resaved.get("profile_name").is_none());
+    }
+}
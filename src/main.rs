@@ -2,11 +2,26 @@
 //
 use leptos::mount::mount_to_body;
 
+pub mod achievements_store;
 mod app;
+mod augment;
+mod backup;
+mod calendar;
 mod components;
+mod file_io;
+pub mod leaderboard_store;
+mod migrations;
 mod pages;
+pub mod profile_store;
+mod race_client;
+mod recording;
+mod result_card;
 pub mod settings_store;
+mod share;
 pub mod stats_store;
+pub mod storage;
+mod streaks;
+mod tournament;
 
 use app::App;
 
@@ -0,0 +1,220 @@
+// src/migrations.rs
+//
+// Versionamento dei dati salvati e catena di migrazioni tra formati.
+//
+// Ogni blob viene salvato in un envelope `{ "version": N, "data": {...} }`.
+// I blob senza envelope sono considerati la versione 0 (formato legacy).
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+// Una migrazione porta i dati dalla versione N alla N+1
+pub type Migration = fn(Value) -> Result<Value, String>;
+
+pub struct Schema {
+    pub current_version: u32,
+    // `migrations[i]` trasforma i dati dalla versione `i` alla `i + 1`
+    pub migrations: &'static [Migration],
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecodeError {
+    // JSON non valido o struttura irriconoscibile
+    Malformed(String),
+    // Dati salvati da una versione più recente dell'app
+    FutureVersion(u32),
+    // Una migrazione della catena è fallita
+    Migration { from: u32, reason: String },
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Malformed(reason) => write!(f, "dati non validi: {}", reason),
+            Self::FutureVersion(v) => write!(f, "versione {} non supportata", v),
+            Self::Migration { from, reason } => {
+                write!(f, "migrazione dalla versione {} fallita: {}", from, reason)
+            }
+        }
+    }
+}
+
+pub struct Decoded<T> {
+    pub value: T,
+    // true se i dati sono stati migrati e vanno riscritti
+    pub migrated: bool,
+}
+
+impl Schema {
+    pub fn encode<T: Serialize + ?Sized>(&self, data: &T) -> Result<String, String> {
+        let data = serde_json::to_value(data).map_err(|e| e.to_string())?;
+        let mut envelope = Map::new();
+        envelope.insert("version".to_string(), Value::from(self.current_version));
+        envelope.insert("data".to_string(), data);
+        serde_json::to_string(&Value::Object(envelope)).map_err(|e| e.to_string())
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self, raw: &str) -> Result<Decoded<T>, DecodeError> {
+        let parsed: Value =
+            serde_json::from_str(raw).map_err(|e| DecodeError::Malformed(e.to_string()))?;
+        let (mut version, mut data) = split_envelope(parsed)?;
+
+        if version > self.current_version {
+            return Err(DecodeError::FutureVersion(version));
+        }
+
+        let migrated = version < self.current_version;
+        while version < self.current_version {
+            let step =
+                self.migrations
+                    .get(version as usize)
+                    .ok_or_else(|| DecodeError::Migration {
+                        from: version,
+                        reason: "migrazione mancante".to_string(),
+                    })?;
+            data = step(data).map_err(|reason| DecodeError::Migration {
+                from: version,
+                reason,
+            })?;
+            version += 1;
+        }
+
+        let value =
+            serde_json::from_value(data).map_err(|e| DecodeError::Malformed(e.to_string()))?;
+        Ok(Decoded { value, migrated })
+    }
+}
+
+// Separa versione e dati; un oggetto senza envelope è la versione 0
+fn split_envelope(value: Value) -> Result<(u32, Value), DecodeError> {
+    let Value::Object(mut map) = value else {
+        return Err(DecodeError::Malformed("atteso un oggetto JSON".to_string()));
+    };
+
+    let is_envelope = map.len() == 2 && map.contains_key("version") && map.contains_key("data");
+    if !is_envelope {
+        return Ok((0, Value::Object(map)));
+    }
+
+    let version = map
+        .get("version")
+        .and_then(Value::as_u64)
+        .and_then(|v| u32::try_from(v).ok())
+        .ok_or_else(|| DecodeError::Malformed("campo version non valido".to_string()))?;
+    let data = map.remove("data").unwrap_or(Value::Null);
+    Ok((version, data))
+}
+
+// Chiave dell'n-esimo backup di un blob illeggibile (da 1): i backup
+// successivi ricevono un contatore invece di sovrascrivere il primo
+pub fn backup_key(key: &str, n: u32) -> String {
+    if n <= 1 {
+        format!("{}_backup", key)
+    } else {
+        format!("{}_backup_{}", key, n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Sample {
+        name: String,
+        count: u32,
+    }
+
+    // La v0 chiamava il campo `nome`
+    fn rename_name(mut data: Value) -> Result<Value, String> {
+        let map = data.as_object_mut().ok_or("atteso un oggetto")?;
+        let name = map.remove("nome").ok_or("campo nome mancante")?;
+        map.insert("name".to_string(), name);
+        Ok(data)
+    }
+
+    fn fail(_: Value) -> Result<Value, String> {
+        Err("rotta".to_string())
+    }
+
+    const SCHEMA: Schema = Schema {
+        current_version: 1,
+        migrations: &[rename_name],
+    };
+
+    #[test]
+    fn bare_legacy_value_is_migrated_from_v0() {
+        let raw = json!({ "nome": "ada", "count": 3 }).to_string();
+        let decoded = SCHEMA.decode::<Sample>(&raw).unwrap();
+        assert!(decoded.migrated);
+        assert_eq!(
+            decoded.value,
+            Sample {
+                name: "ada".to_string(),
+                count: 3
+            }
+        );
+    }
+
+    #[test]
+    fn current_envelope_is_decoded_without_migration() {
+        let sample = Sample {
+            name: "ada".to_string(),
+            count: 3,
+        };
+        let decoded = SCHEMA
+            .decode::<Sample>(&SCHEMA.encode(&sample).unwrap())
+            .unwrap();
+        assert!(!decoded.migrated);
+        assert_eq!(decoded.value, sample);
+    }
+
+    #[test]
+    fn newer_version_is_rejected() {
+        let raw = json!({ "version": 2, "data": { "name": "ada", "count": 3 } }).to_string();
+        assert_eq!(
+            SCHEMA.decode::<Sample>(&raw).err(),
+            Some(DecodeError::FutureVersion(2))
+        );
+    }
+
+    #[test]
+    fn failing_migration_reports_its_version() {
+        let schema = Schema {
+            current_version: 1,
+            migrations: &[fail],
+        };
+        let raw = json!({ "name": "ada", "count": 3 }).to_string();
+        assert_eq!(
+            schema.decode::<Sample>(&raw).err(),
+            Some(DecodeError::Migration {
+                from: 0,
+                reason: "rotta".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn missing_migration_is_an_error() {
+        let schema = Schema {
+            current_version: 2,
+            migrations: &[rename_name],
+        };
+        let raw = json!({ "version": 1, "data": { "name": "ada", "count": 3 } }).to_string();
+        assert!(matches!(
+            schema.decode::<Sample>(&raw),
+            Err(DecodeError::Migration { from: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn non_object_is_malformed() {
+        assert!(matches!(
+            SCHEMA.decode::<Sample>("[1, 2]"),
+            Err(DecodeError::Malformed(_))
+        ));
+    }
+}
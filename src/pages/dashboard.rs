@@ -1,17 +1,56 @@
 // src/pages/dashboard.rs
 //
-use crate::stats_store::use_stats;
+use crate::achievements_store::{Achievement, use_achievements};
+use crate::backup::{
+    self, BackupBundle, ImportStrategy, export_filename, history_to_csv, parse_backup,
+};
+use crate::calendar::{day_start, format_date, local_day, weekday};
+use crate::components::Leaderboard;
+use crate::components::typing::{marathon_mode::MARATHON_TIME, rush_mode::INITIAL_TIME};
+use crate::file_io::{download_file, read_file_text};
+use crate::settings_store::{DatasetDifficulty, use_settings};
+use crate::stats_store::{GameMode, use_stats};
+use crate::streaks::{activity_by_day, streaks};
+use leptos::html::Input;
 use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+// Settimane mostrate nella heatmap dell'attività
+const HEATMAP_WEEKS: i64 = 18;
 
 #[component]
 pub fn Dashboard() -> impl IntoView {
     let stats_ctx = use_stats();
+    let settings_ctx = use_settings();
+    let achievements_ctx = use_achievements();
     let stats = Signal::derive(move || stats_ctx.get_stats());
 
     let (show_confirm_dialog, set_show_confirm_dialog) = signal(false);
     let (feedback_message, set_feedback_message) = signal(String::new());
     let (show_feedback, set_show_feedback) = signal(false);
 
+    // Backup selezionato per l'importazione, in attesa di conferma
+    let (import_candidate, set_import_candidate) = signal::<Option<BackupBundle>>(None);
+    let file_input_ref = NodeRef::<Input>::new();
+
+    // Attività per giorno locale, base per obiettivo, serie e heatmap
+    let activity = Memo::new(move |_| activity_by_day(&stats_ctx.get_history()));
+    let today = local_day(js_sys::Date::now());
+    let streak =
+        Memo::new(move |_| streaks(&activity.get(), &settings_ctx.get_daily_goal(), today));
+
+    // Difficoltà mostrata in classifica, inizialmente quella impostata
+    let (board_difficulty, set_board_difficulty) = signal(settings_ctx.get_difficulty());
+
+    let notify = move |message: String| {
+        set_feedback_message.set(message);
+        set_show_feedback.set(true);
+        set_timeout(
+            move || set_show_feedback.set(false),
+            std::time::Duration::from_secs(3),
+        );
+    };
+
     let handle_clear = move |_| {
         set_show_confirm_dialog.set(true);
     };
@@ -37,6 +76,105 @@ pub fn Dashboard() -> impl IntoView {
         set_show_confirm_dialog.set(false);
     };
 
+    let export_json = move |_| {
+        let bundle = BackupBundle {
+            exported_at: js_sys::Date::now(),
+            settings: settings_ctx.get_settings(),
+            stats: stats_ctx.get_stats(),
+            history: stats_ctx.get_history(),
+        };
+        let exported = backup::export_json(&bundle).ok().and_then(|json| {
+            download_file(
+                &export_filename("litterix-backup", "json"),
+                "application/json",
+                &json,
+            )
+            .ok()
+        });
+        match exported {
+            Some(_) => notify("✓ Backup esportato.".to_string()),
+            None => notify("✗ Errore durante l'esportazione.".to_string()),
+        }
+    };
+
+    let export_csv = move |_| {
+        let csv = history_to_csv(&stats_ctx.get_history());
+        match download_file(
+            &export_filename("litterix-storico", "csv"),
+            "text/csv",
+            &csv,
+        ) {
+            Ok(_) => notify("✓ Storico esportato in CSV.".to_string()),
+            Err(_) => notify("✗ Errore durante l'esportazione.".to_string()),
+        }
+    };
+
+    let open_import = move |_| {
+        if let Some(input) = file_input_ref.get() {
+            // Azzera il valore per poter reimportare lo stesso file
+            input.set_value("");
+            input.click();
+        }
+    };
+
+    let handle_file_selected = move |_| {
+        let Some(file) = file_input_ref
+            .get()
+            .and_then(|input| input.files())
+            .and_then(|files| files.get(0))
+        else {
+            return;
+        };
+
+        spawn_local(async move {
+            match read_file_text(file).await {
+                Ok(raw) => match parse_backup(&raw) {
+                    Ok(bundle) => set_import_candidate.set(Some(bundle)),
+                    Err(e) => notify(format!("✗ Backup non valido: {}", e)),
+                },
+                Err(_) => notify("✗ Impossibile leggere il file.".to_string()),
+            }
+        });
+    };
+
+    let import_preview = Memo::new(move |_| {
+        import_candidate.get().map(|bundle| {
+            backup::preview(
+                &settings_ctx.get_settings(),
+                &stats_ctx.get_stats(),
+                &stats_ctx.get_history(),
+                &bundle,
+            )
+        })
+    });
+
+    let confirm_import = move |strategy: ImportStrategy| {
+        let Some(bundle) = import_candidate.get() else {
+            return;
+        };
+        let (settings, stats, history) = backup::apply(
+            strategy,
+            &settings_ctx.get_settings(),
+            &stats_ctx.get_stats(),
+            &stats_ctx.get_history(),
+            &bundle,
+        );
+        set_import_candidate.set(None);
+
+        // Prima lo storico: se l'archivio non è pronto le impostazioni restano invariate
+        match stats_ctx
+            .replace_all(stats, history)
+            .and_then(|_| settings_ctx.replace(settings))
+        {
+            Ok(_) => notify("✓ Backup importato con successo.".to_string()),
+            Err(_) => notify("✗ Errore durante l'importazione.".to_string()),
+        }
+    };
+
+    let cancel_import = move |_| {
+        set_import_candidate.set(None);
+    };
+
     view! {
         <main class="container dashboard">
             <h1 class="dashboard__title">
@@ -91,6 +229,10 @@ pub fn Dashboard() -> impl IntoView {
                                 <div class="dashboard__card-label">"Punteggio Marathon"</div>
                                 <div class="dashboard__card-value">{move || stats.get().marathon_best_score.to_string()}</div>
                             </div>
+                            <div class="dashboard__card dashboard__card--error">
+                                <div class="dashboard__card-label">"Punteggio Survival"</div>
+                                <div class="dashboard__card-value">{move || stats.get().survival_best_score.to_string()}</div>
+                            </div>
                         </div>
                     </section>
 
@@ -133,6 +275,114 @@ pub fn Dashboard() -> impl IntoView {
                         </div>
                     </section>
 
+                    // Sezione Varianti: record e medie per regole e arricchimenti
+                    <Show when=move || !stats.get().variants.is_empty()>
+                        <section class="dashboard__section">
+                            <h2 class="dashboard__section-title">
+                                <span class="dashboard__section-icon">"🎛️"</span>
+                                "Regole e Arricchimenti"
+                            </h2>
+                            <p class="dashboard__variants-text">
+                                "Partite con regole di confronto permissive o testo arricchito: restano fuori da record e medie qui sopra."
+                            </p>
+                            <div class="dashboard__variants">
+                                {move || {
+                                    stats
+                                        .get()
+                                        .variants
+                                        .into_iter()
+                                        .map(|bucket| view! {
+                                            <div class="dashboard__variant">
+                                                <div class="dashboard__variant-name">{bucket.describe()}</div>
+                                                <div class="dashboard__variant-values">
+                                                    <span>{format!("{} partite", bucket.games)}</span>
+                                                    <span>{format!("max {:.0} WPM", bucket.best_wpm)}</span>
+                                                    <span>{format!("media {:.0} WPM", bucket.average_wpm)}</span>
+                                                    <span>{format!("{:.1}% accuracy", bucket.average_accuracy)}</span>
+                                                </div>
+                                            </div>
+                                        })
+                                        .collect_view()
+                                }}
+                            </div>
+                        </section>
+                    </Show>
+
+                    // Sezione Obiettivo Giornaliero
+                    <section class="dashboard__section">
+                        <h2 class="dashboard__section-title">
+                            <span class="dashboard__section-icon">"📅"</span>
+                            "Obiettivo Giornaliero"
+                        </h2>
+                        <div class="dashboard__goal">
+                            <div class="dashboard__goal-progress">
+                                <div class="dashboard__goal-label">
+                                    {move || {
+                                        let goal = settings_ctx.get_daily_goal();
+                                        let done = activity
+                                            .get()
+                                            .get(&today)
+                                            .map(|a| a.progress(&goal))
+                                            .unwrap_or_default();
+                                        format!("Oggi: {} / {}", done, goal.describe())
+                                    }}
+                                </div>
+                                <div class="dashboard__goal-bar">
+                                    <div
+                                        class="dashboard__goal-fill"
+                                        style=move || {
+                                            let goal = settings_ctx.get_daily_goal();
+                                            let done = activity
+                                                .get()
+                                                .get(&today)
+                                                .map(|a| a.progress(&goal))
+                                                .unwrap_or_default();
+                                            let percent = (done as f64 / goal.target.max(1) as f64 * 100.0).min(100.0);
+                                            format!("width: {:.0}%", percent)
+                                        }
+                                    ></div>
+                                </div>
+                            </div>
+                            <div class="dashboard__stat">
+                                <div class="dashboard__stat-label">"Serie Attuale"</div>
+                                <div class="dashboard__stat-value">{move || format!("🔥 {} giorni", streak.get().current)}</div>
+                            </div>
+                            <div class="dashboard__stat">
+                                <div class="dashboard__stat-label">"Serie Migliore"</div>
+                                <div class="dashboard__stat-value">{move || format!("{} giorni", streak.get().best)}</div>
+                            </div>
+                        </div>
+
+                        // Heatmap delle ultime settimane, una colonna per settimana
+                        <div class="dashboard__heatmap">
+                            {move || {
+                                let goal = settings_ctx.get_daily_goal();
+                                let activity = activity.get();
+                                let first_day = today - weekday(today) - (HEATMAP_WEEKS - 1) * 7;
+                                (first_day..=today)
+                                    .map(|day| {
+                                        let day_activity = activity.get(&day).copied().unwrap_or_default();
+                                        let title = format!(
+                                            "{}: {} {}",
+                                            format_date(day_start(day)),
+                                            day_activity.progress(&goal),
+                                            goal.kind.unit()
+                                        );
+                                        view! {
+                                            <div
+                                                class=format!(
+                                                    "dashboard__heatmap-cell dashboard__heatmap-cell--level-{}",
+                                                    day_activity.level(&goal)
+                                                )
+                                                title=title
+                                            ></div>
+                                        }
+                                    })
+                                    .collect_view()
+                            }}
+                        </div>
+                    </section>
+
                     // Sezione Per Modalità
                     <section class="dashboard__section">
                         <h2 class="dashboard__section-title">
@@ -155,6 +405,84 @@ pub fn Dashboard() -> impl IntoView {
                                 <div class="dashboard__mode-name">"Marathon"</div>
                                 <div class="dashboard__mode-count">{move || format!("{} partite", stats.get().marathon_games)}</div>
                             </div>
+                            <div class="dashboard__mode">
+                                <div class="dashboard__mode-icon">"❤️"</div>
+                                <div class="dashboard__mode-name">"Survival"</div>
+                                <div class="dashboard__mode-count">{move || format!("{} partite", stats.get().survival_games)}</div>
+                            </div>
+                            <div class="dashboard__mode">
+                                <div class="dashboard__mode-icon">"💻"</div>
+                                <div class="dashboard__mode-name">"Codice"</div>
+                                <div class="dashboard__mode-count">{move || format!("{} partite", stats.get().code_games)}</div>
+                            </div>
+                        </div>
+                    </section>
+
+                    // Sezione Simboli del Codice, dal meno preciso
+                    <Show when=move || !stats.get().code_symbols.is_empty()>
+                        <section class="dashboard__section">
+                            <h2 class="dashboard__section-title">
+                                <span class="dashboard__section-icon">"🔣"</span>
+                                "Simboli nel Codice"
+                            </h2>
+                            <div class="dashboard__symbols">
+                                {move || {
+                                    let mut symbols: Vec<_> = stats.get().code_symbols.into_values().collect();
+                                    symbols.sort_by(|a, b| a.accuracy().total_cmp(&b.accuracy()));
+                                    symbols
+                                        .into_iter()
+                                        .map(|symbol| view! {
+                                            <div
+                                                class="dashboard__symbol"
+                                                title=format!("{} tasti, {} errori", symbol.attempts, symbol.errors)
+                                            >
+                                                <span class="dashboard__symbol-char">{symbol.symbol.clone()}</span>
+                                                <span class="dashboard__symbol-value">{format!("{:.1}%", symbol.accuracy())}</span>
+                                            </div>
+                                        })
+                                        .collect_view()
+                                }}
+                            </div>
+                        </section>
+                    </Show>
+
+                    // Sezione Obiettivi
+                    <section class="dashboard__section">
+                        <h2 class="dashboard__section-title">
+                            <span class="dashboard__section-icon">"🎖️"</span>
+                            "Obiettivi"
+                            <span class="dashboard__section-count">
+                                {move || {
+                                    let unlocked = achievements_ctx.get_log().unlocked.len();
+                                    format!("{}/{}", unlocked, Achievement::ALL.len())
+                                }}
+                            </span>
+                        </h2>
+                        <div class="dashboard__achievements">
+                            {Achievement::ALL
+                                .into_iter()
+                                .map(|achievement| {
+                                    let unlocked_at = move || achievements_ctx.get_log().unlocked_at(achievement);
+                                    view! {
+                                        <div
+                                            class="dashboard__achievement"
+                                            class:dashboard__achievement--locked=move || unlocked_at().is_none()
+                                        >
+                                            <div class="dashboard__achievement-icon">{achievement.icon()}</div>
+                                            <div class="dashboard__achievement-body">
+                                                <div class="dashboard__achievement-title">{achievement.title()}</div>
+                                                <div class="dashboard__achievement-text">{achievement.description()}</div>
+                                                <div class="dashboard__achievement-date">
+                                                    {move || match unlocked_at() {
+                                                        Some(ts) => format!("Sbloccato il {}", format_date(ts)),
+                                                        None => "🔒 Bloccato".to_string(),
+                                                    }}
+                                                </div>
+                                            </div>
+                                        </div>
+                                    }
+                                })
+                                .collect_view()}
                         </div>
                     </section>
 
@@ -170,6 +498,87 @@ pub fn Dashboard() -> impl IntoView {
                 </div>
             </Show>
 
+            // Classifica locale: condivisa tra i profili, quindi sempre visibile
+            <section class="dashboard__section dashboard__leaderboard">
+                <h2 class="dashboard__section-title">
+                    <span class="dashboard__section-icon">"🏅"</span>
+                    "Classifica Locale"
+                </h2>
+                <div class="dashboard__leaderboard-filters">
+                    {[
+                        (DatasetDifficulty::Base, "Base"),
+                        (DatasetDifficulty::Intermediate, "Intermedio"),
+                        (DatasetDifficulty::Advanced, "Avanzato"),
+                    ]
+                        .into_iter()
+                        .map(|(difficulty, label)| view! {
+                            <button
+                                class="dashboard__leaderboard-filter"
+                                class:dashboard__leaderboard-filter--active=move || board_difficulty.get() == difficulty
+                                on:click=move |_| set_board_difficulty.set(difficulty)
+                            >
+                                {label}
+                            </button>
+                        })
+                        .collect_view()}
+                </div>
+                <div class="dashboard__leaderboard-tables">
+                    <Leaderboard
+                        mode=GameMode::Rush
+                        difficulty=board_difficulty
+                        match_rules=Signal::derive(move || settings_ctx.get_match_rules())
+                        augmentations=Signal::derive(move || settings_ctx.get_augmentations())
+                        duration=INITIAL_TIME as u32
+                    />
+                    <Leaderboard
+                        mode=GameMode::Marathon
+                        difficulty=board_difficulty
+                        match_rules=Signal::derive(move || settings_ctx.get_match_rules())
+                        augmentations=Signal::derive(move || settings_ctx.get_augmentations())
+                        duration=MARATHON_TIME as u32
+                    />
+                </div>
+            </section>
+
+            // Sezione Backup: sempre visibile, anche per importare su un browser nuovo
+            <section class="dashboard__section dashboard__backup">
+                <h2 class="dashboard__section-title">
+                    <span class="dashboard__section-icon">"💾"</span>
+                    "Backup Dati"
+                </h2>
+                <p class="dashboard__backup-text">
+                    "Esporta statistiche, storico e impostazioni per conservarli o spostarli su un altro browser."
+                </p>
+                <div class="dashboard__backup-actions">
+                    <button class="dashboard__backup-button" on:click=export_json>
+                        "Esporta JSON"
+                    </button>
+                    <button
+                        class="dashboard__backup-button"
+                        on:click=export_csv
+                        disabled=move || stats_ctx.get_history().is_empty()
+                    >
+                        "Esporta Storico CSV"
+                    </button>
+                    // Finché l'archivio dello storico non è aperto l'import non è sicuro
+                    <button
+                        class="dashboard__backup-button"
+                        on:click=open_import
+                        disabled=move || stats_ctx.archive().is_none()
+                        title=move || stats_ctx.archive().is_none().then_some("Archivio dello storico in caricamento…")
+                    >
+                        "Importa Backup"
+                    </button>
+                    <input
+                        node_ref=file_input_ref
+                        type="file"
+                        accept=".json,application/json"
+                        class="dashboard__file-input"
+                        on:change=handle_file_selected
+                    />
+                </div>
+            </section>
+
             // Dialog di conferma
             <Show when=move || show_confirm_dialog.get()>
                 <div class="dashboard__overlay" on:click=cancel_clear>
@@ -189,6 +598,56 @@ pub fn Dashboard() -> impl IntoView {
                     </div>
                 </div>
             </Show>
+
+            // Dialog di anteprima importazione
+            <Show when=move || import_preview.get().is_some()>
+                <div class="dashboard__overlay" on:click=cancel_import>
+                    <div class="dashboard__dialog" on:click=|e| e.stop_propagation()>
+                        <h3 class="dashboard__dialog-title">"Importa Backup"</h3>
+                        {move || import_preview.get().map(|p| view! {
+                            <ul class="dashboard__preview-list">
+                                <li>"Partite nel backup: " <strong>{p.backup_sessions}</strong></li>
+                                <li>
+                                    "Nuove: " <strong>{p.new_sessions}</strong>
+                                    " — già presenti: " <strong>{p.duplicate_sessions}</strong>
+                                </li>
+                                <li>
+                                    "Unisci: " {p.current_games} " → "
+                                    <strong>{p.games_after_merge}</strong> " partite"
+                                </li>
+                                <li>
+                                    "Sostituisci: " {p.current_games} " → "
+                                    <strong>{p.games_after_replace}</strong> " partite"
+                                </li>
+                                {p.settings_changes.into_iter().map(|change| view! {
+                                    <li>"Sostituisci cambia " {change}</li>
+                                }).collect_view()}
+                            </ul>
+                        })}
+                        <p class="dashboard__dialog-text">
+                            "Unisci aggiunge le partite nuove e mantiene le impostazioni attuali. "
+                            "Sostituisci rimpiazza tutti i dati con quelli del backup."
+                        </p>
+                        <div class="dashboard__dialog-actions">
+                            <button class="dashboard__dialog-button dashboard__dialog-button--cancel" on:click=cancel_import>
+                                "Annulla"
+                            </button>
+                            <button
+                                class="dashboard__dialog-button dashboard__dialog-button--primary"
+                                on:click=move |_| confirm_import(ImportStrategy::Merge)
+                            >
+                                "Unisci"
+                            </button>
+                            <button
+                                class="dashboard__dialog-button dashboard__dialog-button--confirm"
+                                on:click=move |_| confirm_import(ImportStrategy::Replace)
+                            >
+                                "Sostituisci"
+                            </button>
+                        </div>
+                    </div>
+                </div>
+            </Show>
         </main>
     }
 }
@@ -3,9 +3,17 @@
 pub mod dashboard;
 pub mod homepage;
 pub mod project;
+pub mod race;
+pub mod replays;
 pub mod settings;
+pub mod shared_result;
+pub mod tournament;
 
 pub use dashboard::Dashboard;
 pub use homepage::Home;
 pub use project::Project;
+pub use race::Race;
+pub use replays::Replays;
 pub use settings::Settings;
+pub use shared_result::SharedResult;
+pub use tournament::TournamentPage;
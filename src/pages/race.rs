@@ -0,0 +1,374 @@
+// src/pages/race.rs
+//
+use crate::components::RaceTrack;
+use crate::components::typing::TypingEngine;
+use crate::components::typing::engine::{KeyInput, grapheme_count, performance_now};
+use crate::profile_store::use_profiles;
+use crate::race_client::{RaceConnection, default_server_url};
+use crate::recording::phrase_offsets;
+use crate::settings_store::{DatasetDifficulty, use_settings};
+use leptos::prelude::*;
+use race_protocol::{ClientMessage, PlayerId, PlayerInfo, ServerMessage};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+use std::time::Duration;
+
+#[derive(Deserialize)]
+struct PhrasesData {
+    phrases: Vec<String>,
+}
+
+// Stesso dataset e stesso seed danno a tutti i giocatori le stesse frasi
+fn race_phrases(difficulty: &str, seed: u64, count: usize) -> Vec<String> {
+    let json_content = DatasetDifficulty::from_str(difficulty).get_dataset_content();
+    let data: PhrasesData =
+        serde_json::from_str(json_content).expect("Errore nel parsing del dataset JSON");
+    let mut phrases = data.phrases;
+    phrases.shuffle(&mut StdRng::seed_from_u64(seed));
+    phrases.truncate(count);
+    phrases
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum RacePhase {
+    Lobby,
+    Countdown,
+    Racing,
+    // Arrivato, in attesa degli altri
+    Finished,
+}
+
+#[component]
+pub fn Race() -> impl IntoView {
+    let settings_ctx = use_settings();
+    let profile_ctx = use_profiles();
+
+    let (server_url, set_server_url) = signal(default_server_url());
+    let (player_name, set_player_name) = signal(profile_ctx.active().name);
+    let (room_name, set_room_name) = signal("litterix".to_string());
+
+    let connection = StoredValue::new_local(None::<RaceConnection>);
+    let (connected, set_connected) = signal(false);
+    let (player_id, set_player_id) = signal::<Option<PlayerId>>(None);
+    let (room, set_room) = signal::<Option<(String, String)>>(None);
+    let (players, set_players) = signal(Vec::<PlayerInfo>::new());
+    let (standings, set_standings) = signal::<Option<Vec<PlayerInfo>>>(None);
+    let (error_message, set_error_message) = signal::<Option<String>>(None);
+
+    // Gara in corso
+    let (phase, set_phase) = signal(RacePhase::Lobby);
+    let (countdown, set_countdown) = signal(0_u32);
+    let (phrases, set_phrases) = signal(Vec::<String>::new());
+    let (phrase_index, set_phrase_index) = signal(0_usize);
+    let (race_start, set_race_start) = signal(0.0);
+    let (wpm_sum, set_wpm_sum) = signal(0.0);
+    let (accuracy_sum, set_accuracy_sum) = signal(0.0);
+
+    let offsets = Memo::new(move |_| phrase_offsets(&phrases.get()));
+    let total_chars =
+        Memo::new(move |_| phrases.with(|p| p.iter().map(|p| grapheme_count(p)).sum()));
+
+    let send = move |message: ClientMessage| {
+        connection.with_value(|c| {
+            if let Some(c) = c {
+                let _ = c.send(&message);
+            }
+        });
+    };
+
+    let update_player = move |id: PlayerId, update: &dyn Fn(&mut PlayerInfo)| {
+        set_players.update(|players| {
+            if let Some(player) = players.iter_mut().find(|p| p.id == id) {
+                update(player);
+            }
+        });
+    };
+
+    let handle_message = move |message: ServerMessage| match message {
+        ServerMessage::Welcome { player_id } => {
+            set_player_id.set(Some(player_id));
+            set_error_message.set(None);
+        }
+        ServerMessage::Lobby {
+            room,
+            difficulty,
+            racing: _,
+            players,
+        } => {
+            set_room.set(Some((room, difficulty)));
+            set_players.set(players);
+        }
+        ServerMessage::RaceStart {
+            seed,
+            difficulty,
+            phrases,
+            countdown,
+        } => {
+            set_phrases.set(race_phrases(&difficulty, seed, phrases));
+            set_phrase_index.set(0);
+            set_wpm_sum.set(0.0);
+            set_accuracy_sum.set(0.0);
+            set_standings.set(None);
+            set_players.update(|players| {
+                for player in players.iter_mut() {
+                    player.chars = 0;
+                    player.wpm = 0.0;
+                    player.accuracy = None;
+                    player.place = None;
+                }
+            });
+            set_countdown.set(countdown);
+            set_phase.set(RacePhase::Countdown);
+        }
+        ServerMessage::Progress {
+            player_id,
+            chars,
+            wpm,
+        } => update_player(player_id, &|p| {
+            p.chars = chars;
+            p.wpm = wpm;
+        }),
+        ServerMessage::Finished {
+            player_id,
+            place,
+            wpm,
+            accuracy,
+        } => update_player(player_id, &|p| {
+            p.place = Some(place);
+            p.wpm = wpm;
+            p.accuracy = Some(accuracy);
+        }),
+        ServerMessage::RaceOver { standings } => {
+            set_standings.set(Some(standings));
+            set_phase.set(RacePhase::Lobby);
+        }
+        ServerMessage::Error { message } => set_error_message.set(Some(message)),
+    };
+
+    let disconnect = move || {
+        connection.update_value(|c| {
+            if let Some(c) = c.take() {
+                c.close();
+            }
+        });
+        set_connected.set(false);
+        set_player_id.set(None);
+        set_room.set(None);
+        set_players.set(Vec::new());
+        set_standings.set(None);
+        set_phase.set(RacePhase::Lobby);
+    };
+
+    let connect = move |_| {
+        let join = ClientMessage::Join {
+            room: room_name.get(),
+            name: player_name.get(),
+            difficulty: settings_ctx.get_difficulty().to_str().to_string(),
+        };
+        let on_close = move || {
+            disconnect();
+            set_error_message.set(Some("Connessione al race server chiusa.".to_string()));
+        };
+        match RaceConnection::connect(&server_url.get(), join, handle_message, on_close) {
+            Ok(c) => {
+                connection.set_value(Some(c));
+                set_connected.set(true);
+                set_error_message.set(None);
+            }
+            Err(_) => set_error_message.set(Some("Indirizzo del server non valido.".to_string())),
+        }
+    };
+
+    // Countdown prima del via
+    Effect::new(move |_| {
+        if phase.get() != RacePhase::Countdown {
+            return;
+        }
+        let remaining = countdown.get();
+        let handle = set_timeout_with_handle(
+            move || {
+                if remaining <= 1 {
+                    set_race_start.set(performance_now().unwrap_or_default());
+                    set_phase.set(RacePhase::Racing);
+                } else {
+                    set_countdown.set(remaining - 1);
+                }
+            },
+            Duration::from_secs(1),
+        )
+        .unwrap();
+        on_cleanup(move || handle.clear());
+    });
+
+    let on_keystroke = Callback::new(move |(_, index): (KeyInput, usize)| {
+        let offset = offsets.with_untracked(|o| o.get(phrase_index.get_untracked()).copied());
+        let chars = offset.unwrap_or_default() + index;
+        let minutes =
+            (performance_now().unwrap_or_default() - race_start.get_untracked()) / 60_000.0;
+        let wpm = if minutes > 0.0 {
+            (chars as f64 / 5.0) / minutes
+        } else {
+            0.0
+        };
+        if let Some(id) = player_id.get_untracked() {
+            update_player(id, &|p| {
+                p.chars = chars;
+                p.wpm = wpm;
+            });
+        }
+        send(ClientMessage::Progress { chars, wpm });
+    });
+
+    let on_complete = Callback::new(move |(wpm, accuracy, _): (f64, f64, usize)| {
+        set_wpm_sum.update(|sum| *sum += wpm);
+        set_accuracy_sum.update(|sum| *sum += accuracy);
+        let completed = phrase_index.get_untracked() + 1;
+        if completed >= phrases.with_untracked(|p| p.len()) {
+            set_phase.set(RacePhase::Finished);
+            send(ClientMessage::Finish {
+                wpm: wpm_sum.get_untracked() / completed as f64,
+                accuracy: accuracy_sum.get_untracked() / completed as f64,
+            });
+        } else {
+            set_phrase_index.set(completed);
+        }
+    });
+
+    let is_ready = move || {
+        let id = player_id.get();
+        players.with(|p| p.iter().any(|p| Some(p.id) == id && p.ready))
+    };
+
+    on_cleanup(move || {
+        connection.update_value(|c| {
+            if let Some(c) = c.take() {
+                c.close();
+            }
+        });
+    });
+
+    view! {
+        <main class="container race">
+            <h1 class="race__title">
+                "Gara "
+                <span class="race__highlight">"multiplayer"</span>
+            </h1>
+
+            {move || error_message.get().map(|message| view! {
+                <div class="race__feedback">{message}</div>
+            })}
+
+            <Show
+                when=move || connected.get()
+                fallback=move || view! {
+                    <section class="race__join">
+                        <p class="race__hint">
+                            "Avvia il race server con "
+                            <code>"cargo run -p race-server"</code>
+                            " e condividi la stessa stanza con chi è sulla tua rete."
+                        </p>
+                        <label class="race__field">
+                            <span>"Server"</span>
+                            <input
+                                type="text"
+                                prop:value=move || server_url.get()
+                                on:input=move |ev| set_server_url.set(event_target_value(&ev))
+                            />
+                        </label>
+                        <label class="race__field">
+                            <span>"Nome"</span>
+                            <input
+                                type="text"
+                                maxlength="24"
+                                prop:value=move || player_name.get()
+                                on:input=move |ev| set_player_name.set(event_target_value(&ev))
+                            />
+                        </label>
+                        <label class="race__field">
+                            <span>"Stanza"</span>
+                            <input
+                                type="text"
+                                maxlength="32"
+                                prop:value=move || room_name.get()
+                                on:input=move |ev| set_room_name.set(event_target_value(&ev))
+                            />
+                        </label>
+                        <button class="race__button race__button--primary" on:click=connect>
+                            "Entra"
+                        </button>
+                    </section>
+                }
+            >
+                <section class="race__room">
+                    <div class="race__room-header">
+                        <span class="race__room-name">
+                            {move || room.get().map(|(name, difficulty)| format!("Stanza {} · {}", name, difficulty))}
+                        </span>
+                        <button class="race__button" on:click=move |_| disconnect()>"Esci"</button>
+                    </div>
+
+                    <RaceTrack players=players total_chars=total_chars me=player_id />
+
+                    {move || match phase.get() {
+                        RacePhase::Lobby => view! {
+                            <div class="race__lobby">
+                                {move || standings.get().map(|standings| view! {
+                                    <ol class="race__standings">
+                                        {standings
+                                            .into_iter()
+                                            .map(|p| view! {
+                                                <li>
+                                                    {format!(
+                                                        "{} · {:.0} wpm · {}",
+                                                        p.name,
+                                                        p.wpm,
+                                                        p.accuracy.map_or("-".to_string(), |a| format!("{:.1}%", a))
+                                                    )}
+                                                </li>
+                                            })
+                                            .collect_view()}
+                                    </ol>
+                                })}
+                                <p class="race__hint">"La gara parte quando tutti i giocatori sono pronti."</p>
+                                <button
+                                    class="race__button race__button--primary"
+                                    class:race__button--active=is_ready
+                                    on:click=move |_| send(ClientMessage::SetReady { ready: !is_ready() })
+                                >
+                                    {move || if is_ready() { "Non sono pronto" } else { "Sono pronto" }}
+                                </button>
+                            </div>
+                        }
+                        .into_any(),
+                        RacePhase::Countdown => view! {
+                            <div class="race__countdown">{move || countdown.get()}</div>
+                        }
+                        .into_any(),
+                        RacePhase::Racing => view! {
+                            <div class="race__typing">
+                                {move || {
+                                    let text = phrases.with(|p| p.get(phrase_index.get()).cloned().unwrap_or_default());
+                                    view! {
+                                        <TypingEngine
+                                            text=text
+                                            on_complete=on_complete
+                                            on_keystroke=on_keystroke
+                                            caret_style=settings_ctx.get_caret_style()
+                                        />
+                                    }
+                                }}
+                            </div>
+                        }
+                        .into_any(),
+                        RacePhase::Finished => view! {
+                            <p class="race__hint">"Traguardo! In attesa degli altri giocatori..."</p>
+                        }
+                        .into_any(),
+                    }}
+                </section>
+            </Show>
+        </main>
+    }
+}
@@ -0,0 +1,302 @@
+// src/pages/replays.rs
+//
+use crate::backup::export_filename;
+use crate::calendar::format_datetime;
+use crate::components::typing::engine::TypingState;
+use crate::components::typing::{TextCells, TypingText};
+use crate::file_io::{download_file, read_file_text};
+use crate::recording::{Replay, export_replay, parse_replay};
+use crate::settings_store::use_settings;
+use crate::stats_store::use_stats;
+use leptos::html::Input;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use std::time::Duration;
+
+const PLAYBACK_SPEEDS: [f64; 4] = [0.5, 1.0, 2.0, 4.0];
+const PLAYBACK_TICK_MS: f64 = 50.0;
+
+fn format_clock(ms: f64) -> String {
+    let seconds = ms / 1000.0;
+    format!("{}:{:04.1}", (seconds / 60.0).floor(), seconds % 60.0)
+}
+
+#[component]
+pub fn Replays() -> impl IntoView {
+    let stats_ctx = use_stats();
+    let settings_ctx = use_settings();
+
+    let (selected, set_selected) = signal::<Option<Replay>>(None);
+    let (position, set_position) = signal(0.0);
+    let (playing, set_playing) = signal(false);
+    let (speed, set_speed) = signal(1.0);
+    let (feedback_message, set_feedback_message) = signal::<Option<String>>(None);
+    let file_input_ref = NodeRef::<Input>::new();
+
+    let duration =
+        Memo::new(move |_| selected.with(|r| r.as_ref().map_or(0.0, |r| r.recording.duration())));
+
+    // Stato della frase in riproduzione, ricostruito dai tasti fino a `position`
+    let playback = Memo::new(move |_| {
+        let at = position.get();
+        selected.with(|r| r.as_ref().and_then(|r| r.recording.state_at(at)))
+    });
+    let typing_state = Signal::derive(move || {
+        playback
+            .get()
+            .map(|(_, state)| state)
+            .unwrap_or_else(|| TypingState::new(String::new()))
+    });
+    // Le celle si ricostruiscono solo al cambio di frase
+    let phrase_key = Memo::new(move |_| {
+        playback.with(|p| {
+            p.as_ref()
+                .map(|(index, state)| (*index, state.text.clone()))
+        })
+    });
+
+    Effect::new(move |_| {
+        if playing.get() {
+            let handle = set_interval_with_handle(
+                move || {
+                    let next = position.get_untracked() + PLAYBACK_TICK_MS * speed.get_untracked();
+                    if next >= duration.get_untracked() {
+                        set_position.set(duration.get_untracked());
+                        set_playing.set(false);
+                    } else {
+                        set_position.set(next);
+                    }
+                },
+                Duration::from_millis(PLAYBACK_TICK_MS as u64),
+            )
+            .unwrap();
+
+            on_cleanup(move || handle.clear());
+        }
+    });
+
+    let select_replay = move |replay: Replay| {
+        set_playing.set(false);
+        set_position.set(0.0);
+        set_selected.set(Some(replay));
+    };
+
+    let toggle_playback = move |_| {
+        // Dalla fine si riparte dall'inizio
+        if !playing.get() && position.get() >= duration.get() {
+            set_position.set(0.0);
+        }
+        set_playing.update(|p| *p = !*p);
+    };
+
+    let export_selected = move |_| {
+        let Some(replay) = selected.get() else {
+            return;
+        };
+        let exported = export_replay(&replay).ok().and_then(|json| {
+            download_file(
+                &export_filename("litterix-replay", "json"),
+                "application/json",
+                &json,
+            )
+            .ok()
+        });
+        if exported.is_none() {
+            set_feedback_message.set(Some("✗ Errore durante l'esportazione.".to_string()));
+        }
+    };
+
+    let open_import = move |_| {
+        if let Some(input) = file_input_ref.get() {
+            input.set_value("");
+            input.click();
+        }
+    };
+
+    let handle_file_selected = move |_| {
+        let Some(file) = file_input_ref
+            .get()
+            .and_then(|input| input.files())
+            .and_then(|files| files.get(0))
+        else {
+            return;
+        };
+
+        spawn_local(async move {
+            match read_file_text(file).await {
+                Ok(raw) => match parse_replay(&raw) {
+                    Ok(replay) => {
+                        set_feedback_message.set(None);
+                        select_replay(replay);
+                    }
+                    Err(e) => set_feedback_message.set(Some(format!("✗ Replay non valido: {}", e))),
+                },
+                Err(_) => {
+                    set_feedback_message.set(Some("✗ Impossibile leggere il file.".to_string()))
+                }
+            }
+        });
+    };
+
+    view! {
+        <main class="container replays">
+            <h1 class="replays__title">
+                "Replay "
+                <span class="replays__highlight">"rivedi le tue partite"</span>
+            </h1>
+
+            {move || feedback_message.get().map(|message| view! {
+                <div class="replays__feedback">{message}</div>
+            })}
+
+            <div class="replays__layout">
+                // Elenco dei replay salvati
+                <aside class="replays__list">
+                    <div class="replays__list-header">
+                        <span>"Partite salvate"</span>
+                        <button class="replays__button" on:click=open_import>"Importa"</button>
+                        <input
+                            node_ref=file_input_ref
+                            type="file"
+                            accept=".json,application/json"
+                            class="replays__file-input"
+                            on:change=handle_file_selected
+                        />
+                    </div>
+                    <Show
+                        when=move || !stats_ctx.get_replays().is_empty()
+                        fallback=|| view! {
+                            <p class="replays__empty">"Gioca una partita per registrare il primo replay."</p>
+                        }
+                    >
+                        <ul class="replays__items">
+                            <For
+                                each=move || stats_ctx.get_replays()
+                                key=|r| r.recorded_at.to_bits()
+                                children=move |replay| {
+                                    let recorded_at = replay.recorded_at;
+                                    let is_selected = move || {
+                                        selected.with(|s| s.as_ref().is_some_and(|s| s.recorded_at == recorded_at))
+                                    };
+                                    let label = format!(
+                                        "{} · {} · {:.0} WPM",
+                                        replay.mode.label(),
+                                        replay.difficulty.to_str(),
+                                        replay.wpm
+                                    );
+                                    view! {
+                                        <li>
+                                            <button
+                                                class="replays__item"
+                                                class:replays__item--active=is_selected
+                                                on:click=move |_| select_replay(replay.clone())
+                                            >
+                                                <span class="replays__item-date">{format_datetime(recorded_at)}</span>
+                                                <span class="replays__item-label">{label}</span>
+                                            </button>
+                                        </li>
+                                    }
+                                }
+                            />
+                        </ul>
+                    </Show>
+                </aside>
+
+                // Riproduzione
+                <section class="replays__viewer">
+                    <Show
+                        when=move || selected.with(|s| s.is_some())
+                        fallback=|| view! {
+                            <p class="replays__empty">"Seleziona un replay da rivedere."</p>
+                        }
+                    >
+                        <div class="replays__summary">
+                            {move || selected.get().map(|r| {
+                                let limit = r
+                                    .time_limit
+                                    .map(|t| format!(" · {:.0}s", t))
+                                    .unwrap_or_default();
+                                let score = r
+                                    .score
+                                    .map(|s| format!(" · {} punti", s))
+                                    .unwrap_or_default();
+                                format!(
+                                    "{} · {}{} — {} · {:.0} WPM · {:.1}%{}",
+                                    r.mode.label(),
+                                    r.difficulty.to_str(),
+                                    limit,
+                                    r.profile_name,
+                                    r.wpm,
+                                    r.accuracy,
+                                    score
+                                )
+                            })}
+                        </div>
+
+                        <div class="typing-display replays__display">
+                            {move || {
+                                phrase_key.track();
+                                let cells = TextCells::new(&typing_state.get_untracked());
+                                Effect::new(move |_| typing_state.with(|s| cells.sync_all(s)));
+                                view! {
+                                    <TypingText
+                                        cells=cells
+                                        caret_style=settings_ctx.get_caret_style()
+                                    />
+                                }
+                            }}
+                        </div>
+
+                        <div class="replays__progress">
+                            <span>
+                                {move || {
+                                    let phrase = playback.get().map_or(0, |(index, _)| index + 1);
+                                    let total = selected.with(|r| r.as_ref().map_or(0, |r| r.recording.phrases.len()));
+                                    format!("Frase {}/{}", phrase, total)
+                                }}
+                            </span>
+                            <span>
+                                {move || format!("{} / {}", format_clock(position.get()), format_clock(duration.get()))}
+                            </span>
+                        </div>
+
+                        <input
+                            type="range"
+                            class="replays__scrubber"
+                            min="0"
+                            step="10"
+                            prop:max=move || duration.get().to_string()
+                            prop:value=move || position.get().to_string()
+                            on:input=move |ev| {
+                                if let Ok(value) = event_target_value(&ev).parse::<f64>() {
+                                    set_position.set(value);
+                                }
+                            }
+                        />
+
+                        <div class="replays__controls">
+                            <button class="replays__button replays__button--primary" on:click=toggle_playback>
+                                {move || if playing.get() { "Pausa" } else { "Riproduci" }}
+                            </button>
+                            <div class="replays__speeds">
+                                {PLAYBACK_SPEEDS
+                                    .into_iter()
+                                    .map(|s| view! {
+                                        <button
+                                            class="replays__speed"
+                                            class:replays__speed--active=move || speed.get() == s
+                                            on:click=move |_| set_speed.set(s)
+                                        >
+                                            {format!("{}x", s)}
+                                        </button>
+                                    })
+                                    .collect_view()}
+                            </div>
+                            <button class="replays__button" on:click=export_selected>"Esporta"</button>
+                        </div>
+                    </Show>
+                </section>
+            </div>
+        </main>
+    }
+}
@@ -1,6 +1,9 @@
 // src/pages/settings.rs
 //
-use crate::settings_store::{DatasetDifficulty, use_settings};
+use crate::augment::{Augmentation, Augmentations};
+use crate::components::typing::engine::{ErrorMode, InputModel, MatchRule, MatchRules};
+use crate::components::typing::typing_text::CaretStyle;
+use crate::settings_store::{DailyGoal, DatasetDifficulty, GoalKind, use_settings};
 use leptos::prelude::*;
 
 #[component]
@@ -9,6 +12,22 @@ pub fn Settings() -> impl IntoView {
 
     // State locale per la selezione corrente (non salvata finché non si preme Salva)
     let (selected_difficulty, set_selected_difficulty) = signal(settings_ctx.get_difficulty());
+    let (selected_goal, set_selected_goal) = signal(settings_ctx.get_daily_goal());
+    let (selected_error_mode, set_selected_error_mode) = signal(settings_ctx.get_error_mode());
+    let (selected_match_rules, set_selected_match_rules) = signal(settings_ctx.get_match_rules());
+    let (selected_input_model, set_selected_input_model) = signal(settings_ctx.get_input_model());
+    let (selected_caret_style, set_selected_caret_style) = signal(settings_ctx.get_caret_style());
+    let (selected_augmentations, set_selected_augmentations) =
+        signal(settings_ctx.get_augmentations());
+
+    // Riallinea la selezione quando cambiano le impostazioni (es. cambio profilo)
+    Effect::new(move |_| set_selected_difficulty.set(settings_ctx.get_difficulty()));
+    Effect::new(move |_| set_selected_goal.set(settings_ctx.get_daily_goal()));
+    Effect::new(move |_| set_selected_error_mode.set(settings_ctx.get_error_mode()));
+    Effect::new(move |_| set_selected_match_rules.set(settings_ctx.get_match_rules()));
+    Effect::new(move |_| set_selected_input_model.set(settings_ctx.get_input_model()));
+    Effect::new(move |_| set_selected_caret_style.set(settings_ctx.get_caret_style()));
+    Effect::new(move |_| set_selected_augmentations.set(settings_ctx.get_augmentations()));
 
     // State per il messaggio di feedback
     let (feedback_message, set_feedback_message) = signal(String::new());
@@ -21,6 +40,12 @@ pub fn Settings() -> impl IntoView {
     let handle_save = move |_| {
         // Aggiorna il context con la selezione corrente
         settings_ctx.set_difficulty(selected_difficulty.get());
+        settings_ctx.set_daily_goal(selected_goal.get());
+        settings_ctx.set_error_mode(selected_error_mode.get());
+        settings_ctx.set_match_rules(selected_match_rules.get());
+        settings_ctx.set_input_model(selected_input_model.get());
+        settings_ctx.set_caret_style(selected_caret_style.get());
+        settings_ctx.set_augmentations(selected_augmentations.get());
 
         // Salva in localStorage
         match settings_ctx.save() {
@@ -47,6 +72,12 @@ pub fn Settings() -> impl IntoView {
         match settings_ctx.reset() {
             Ok(_) => {
                 set_selected_difficulty.set(DatasetDifficulty::Base);
+                set_selected_goal.set(DailyGoal::default());
+                set_selected_error_mode.set(ErrorMode::default());
+                set_selected_match_rules.set(MatchRules::default());
+                set_selected_input_model.set(InputModel::default());
+                set_selected_caret_style.set(CaretStyle::default());
+                set_selected_augmentations.set(Augmentations::default());
                 set_feedback_message
                     .set("✓ Impostazioni ripristinate ai valori predefiniti.".to_string());
                 set_show_feedback.set(true);
@@ -178,6 +209,210 @@ pub fn Settings() -> impl IntoView {
                 </div>
             </div>
 
+            <div class="settings__section">
+                <h2 class="settings__section-title">"Arricchimento del Testo"</h2>
+                <p style="color: var(--color-text-muted); margin-bottom: 1rem;">
+                    "Le frasi dei dataset sono tutte minuscole: aggiungi numeri, maiuscole e punteggiatura per allenare shift e simboli. Le classifiche sono separate per ogni combinazione."
+                </p>
+
+                <div class="settings__options">
+                    {Augmentation::ALL
+                        .into_iter()
+                        .map(|augmentation| view! {
+                            <label
+                                class="settings__option"
+                                class:settings__option--active=move || selected_augmentations.get().is_enabled(augmentation)
+                            >
+                                <input
+                                    type="checkbox"
+                                    class="settings__option-radio settings__option-radio--square"
+                                    checked=move || selected_augmentations.get().is_enabled(augmentation)
+                                    on:change=move |ev| {
+                                        let enabled = event_target_checked(&ev);
+                                        set_selected_augmentations.update(|a| a.set(augmentation, enabled));
+                                    }
+                                />
+                                <div class="settings__option-content">
+                                    <div class="settings__option-title">{augmentation.label()}</div>
+                                    <div class="settings__option-description">{augmentation.description()}</div>
+                                </div>
+                            </label>
+                        })
+                        .collect_view()}
+                </div>
+            </div>
+
+            <div class="settings__section">
+                <h2 class="settings__section-title">"Obiettivo Giornaliero"</h2>
+                <p style="color: var(--color-text-muted); margin-bottom: 1rem;">
+                    "Scegli quanto vuoi esercitarti ogni giorno: la Dashboard conterà i giorni consecutivi in cui lo raggiungi."
+                </p>
+
+                <div class="settings__goal">
+                    <div class="settings__goal-kinds">
+                        {GoalKind::ALL
+                            .into_iter()
+                            .map(|kind| view! {
+                                <button
+                                    class="settings__goal-kind"
+                                    class:settings__goal-kind--active=move || selected_goal.get().kind == kind
+                                    on:click=move |_| set_selected_goal.update(|g| g.kind = kind)
+                                >
+                                    {kind.label()}
+                                </button>
+                            })
+                            .collect_view()}
+                    </div>
+                    <input
+                        type="number"
+                        min="1"
+                        class="settings__goal-input"
+                        prop:value=move || selected_goal.get().target.to_string()
+                        on:input=move |ev| {
+                            if let Ok(target) = event_target_value(&ev).parse::<u32>() {
+                                set_selected_goal.update(|g| g.target = target.max(1));
+                            }
+                        }
+                    />
+                    <span class="settings__goal-unit">{move || format!("{} al giorno", selected_goal.get().kind.unit())}</span>
+                </div>
+            </div>
+
+            <div class="settings__section">
+                <h2 class="settings__section-title">"Gestione Errori"</h2>
+                <p style="color: var(--color-text-muted); margin-bottom: 1rem;">
+                    "Scegli cosa succede quando premi un tasto sbagliato, in tutte le modalità di gioco."
+                </p>
+
+                <div class="settings__options">
+                    {ErrorMode::ALL
+                        .into_iter()
+                        .map(|mode| view! {
+                            <label
+                                class="settings__option"
+                                class:settings__option--active=move || selected_error_mode.get() == mode
+                            >
+                                <input
+                                    type="radio"
+                                    name="error-mode"
+                                    class="settings__option-radio"
+                                    checked=move || selected_error_mode.get() == mode
+                                    on:change=move |_| set_selected_error_mode.set(mode)
+                                />
+                                <div class="settings__option-content">
+                                    <div class="settings__option-title">{mode.label()}</div>
+                                    <div class="settings__option-description">{mode.description()}</div>
+                                </div>
+                            </label>
+                        })
+                        .collect_view()}
+                </div>
+            </div>
+
+            <div class="settings__section">
+                <h2 class="settings__section-title">"Modello di Input"</h2>
+                <p style="color: var(--color-text-muted); margin-bottom: 1rem;">
+                    "Scegli come i tasti vengono allineati al testo quando digiti lettere in più o salti una parola."
+                </p>
+
+                <div class="settings__options">
+                    {InputModel::ALL
+                        .into_iter()
+                        .map(|model| {
+                            // Il modello scelto resta salvato, ma con gli altri modi di gestione errori vale `Linear`
+                            let is_active = move || {
+                                selected_input_model.get().effective(selected_error_mode.get()) == model
+                            };
+                            let is_disabled = move || !model.is_available(selected_error_mode.get());
+                            view! {
+                                <label
+                                    class="settings__option"
+                                    class:settings__option--active=is_active
+                                    class:settings__option--disabled=is_disabled
+                                >
+                                    <input
+                                        type="radio"
+                                        name="input-model"
+                                        class="settings__option-radio"
+                                        checked=is_active
+                                        disabled=is_disabled
+                                        on:change=move |_| set_selected_input_model.set(model)
+                                    />
+                                    <div class="settings__option-content">
+                                        <div class="settings__option-title">{model.label()}</div>
+                                        <div class="settings__option-description">{model.description()}</div>
+                                    </div>
+                                </label>
+                            }
+                        })
+                        .collect_view()}
+                </div>
+            </div>
+
+            <div class="settings__section">
+                <h2 class="settings__section-title">"Regole di Confronto"</h2>
+                <p style="color: var(--color-text-muted); margin-bottom: 1rem;">
+                    "Rendi il confronto più permissivo. I risultati ottenuti con regole attive vengono contrassegnati in classifica e nello storico."
+                </p>
+
+                <div class="settings__options">
+                    {MatchRule::ALL
+                        .into_iter()
+                        .map(|rule| view! {
+                            <label
+                                class="settings__option"
+                                class:settings__option--active=move || selected_match_rules.get().is_enabled(rule)
+                            >
+                                <input
+                                    type="checkbox"
+                                    class="settings__option-radio settings__option-radio--square"
+                                    checked=move || selected_match_rules.get().is_enabled(rule)
+                                    on:change=move |ev| {
+                                        let enabled = event_target_checked(&ev);
+                                        set_selected_match_rules.update(|rules| rules.set(rule, enabled));
+                                    }
+                                />
+                                <div class="settings__option-content">
+                                    <div class="settings__option-title">{rule.label()}</div>
+                                    <div class="settings__option-description">{rule.description()}</div>
+                                </div>
+                            </label>
+                        })
+                        .collect_view()}
+                </div>
+            </div>
+
+            <div class="settings__section">
+                <h2 class="settings__section-title">"Stile del Cursore"</h2>
+                <p style="color: var(--color-text-muted); margin-bottom: 1rem;">
+                    "Scegli l'aspetto del cursore di battitura. Mentre digiti smette di lampeggiare."
+                </p>
+
+                <div class="settings__options">
+                    {CaretStyle::ALL
+                        .into_iter()
+                        .map(|style| view! {
+                            <label
+                                class="settings__option"
+                                class:settings__option--active=move || selected_caret_style.get() == style
+                            >
+                                <input
+                                    type="radio"
+                                    name="caret-style"
+                                    class="settings__option-radio"
+                                    checked=move || selected_caret_style.get() == style
+                                    on:change=move |_| set_selected_caret_style.set(style)
+                                />
+                                <div class="settings__option-content">
+                                    <div class="settings__option-title">{style.label()}</div>
+                                    <div class="settings__option-description">{style.description()}</div>
+                                </div>
+                            </label>
+                        })
+                        .collect_view()}
+                </div>
+            </div>
+
             // Pulsanti di azione
             <div class="settings__actions">
                 <button class="settings__button settings__button--primary" on:click=handle_save>
@@ -0,0 +1,104 @@
+// src/pages/shared_result.rs
+//
+use crate::calendar::format_date;
+use crate::components::typing::combo_badge;
+use crate::settings_store::use_settings;
+use crate::share::ResultSummary;
+use leptos::prelude::*;
+use leptos_router::hooks::{use_location, use_navigate};
+
+// Scheda in sola lettura di un risultato ricevuto tramite link
+#[component]
+pub fn SharedResult() -> impl IntoView {
+    let settings_ctx = use_settings();
+    let location = use_location();
+    let navigate = use_navigate();
+
+    let summary = Memo::new(move |_| {
+        let hash = location.hash.get();
+        ResultSummary::decode(hash.trim_start_matches('#'))
+    });
+
+    // Stessa difficoltà e arricchimenti del risultato, altrimenti il seed darebbe frasi diverse
+    let play_same_seed = move |result: &ResultSummary| {
+        if let Some(path) = result.play_path() {
+            settings_ctx.set_difficulty(result.difficulty);
+            settings_ctx.set_augmentations(result.augmentations);
+            navigate(&path, Default::default());
+        }
+    };
+
+    view! {
+        <main class="container shared-result">
+            <h1 class="shared-result__title">
+                "Risultato "
+                <span class="shared-result__highlight">"condiviso"</span>
+            </h1>
+
+            {move || match summary.get() {
+                Ok(result) => {
+                    let (emoji, label) = combo_badge(result.combo);
+                    let play_same_seed = play_same_seed.clone();
+                    let seeded = result.clone();
+                    view! {
+                        <article class="shared-result__card">
+                            <header class="shared-result__header">
+                                <span class="shared-result__mode">{result.mode.label()}</span>
+                                <span class="shared-result__meta">
+                                    {if result.augmentations.is_plain() {
+                                        format!("{} · {}", result.difficulty.to_str(), format_date(result.timestamp))
+                                    } else {
+                                        format!(
+                                            "{} · {} · {}",
+                                            result.difficulty.to_str(),
+                                            result.augmentations.describe(),
+                                            format_date(result.timestamp),
+                                        )
+                                    }}
+                                </span>
+                            </header>
+                            <div class="shared-result__stats">
+                                <div class="shared-result__stat">
+                                    <span class="shared-result__label">"Punteggio"</span>
+                                    <span class="shared-result__value shared-result__value--primary">{result.score}</span>
+                                </div>
+                                <div class="shared-result__stat">
+                                    <span class="shared-result__label">"WPM Medio"</span>
+                                    <span class="shared-result__value">{format!("{:.0}", result.wpm)}</span>
+                                </div>
+                                <div class="shared-result__stat">
+                                    <span class="shared-result__label">"Accuracy Media"</span>
+                                    <span class="shared-result__value">{format!("{:.1}%", result.accuracy)}</span>
+                                </div>
+                                <div class="shared-result__stat">
+                                    <span class="shared-result__label">"Combo Massima"</span>
+                                    <span class="shared-result__value">{format!("{} {}", emoji, result.combo)}</span>
+                                    <span class="shared-result__badge">{label}</span>
+                                </div>
+                            </div>
+                            {result.seed.map(|seed| view! {
+                                <footer class="shared-result__footer">
+                                    <span class="shared-result__seed">{format!("Seed {}", seed)}</span>
+                                    <button
+                                        class="shared-result__play"
+                                        on:click=move |_| play_same_seed(&seeded)
+                                    >
+                                        "Gioca lo stesso seed"
+                                    </button>
+                                </footer>
+                            })}
+                        </article>
+                    }
+                    .into_any()
+                }
+                Err(e) => view! {
+                    <div class="shared-result__error">
+                        <p>{format!("✗ Link al risultato non valido: {}.", e)}</p>
+                        <a class="shared-result__play" href="/">"Torna al gioco"</a>
+                    </div>
+                }
+                .into_any(),
+            }}
+        </main>
+    }
+}
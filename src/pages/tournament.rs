@@ -0,0 +1,309 @@
+// src/pages/tournament.rs
+//
+use crate::components::{MarathonMode, RushMode, SurvivalMode, TournamentResults};
+use crate::profile_store::use_profiles;
+use crate::settings_store::use_settings;
+use crate::stats_store::{GameMode, SessionRecord};
+use crate::tournament::{
+    HotSeatPlayer, MAX_PLAYERS, MIN_PLAYERS, Tournament, TournamentFormat, TournamentRules, Turn,
+    TurnResult,
+};
+use leptos::prelude::*;
+
+#[component]
+pub fn TournamentPage() -> impl IntoView {
+    let settings_ctx = use_settings();
+    let profile_ctx = use_profiles();
+
+    // Configurazione
+    let (mode, set_mode) = signal(GameMode::Rush);
+    let (format, set_format) = signal(TournamentFormat::Table);
+    let (names, set_names) = signal(vec![String::new(); MIN_PLAYERS]);
+    let (error_message, set_error_message) = signal::<Option<String>>(None);
+
+    let (tournament, set_tournament) = signal::<Option<Tournament>>(None);
+    // Turno in corso: la modalità resta montata fino a "Continua"
+    let (active_turn, set_active_turn) = signal::<Option<Turn>>(None);
+    let (turn_done, set_turn_done) = signal(false);
+    // Profilo attivo prima del torneo, ripristinato alla fine
+    let original_profile = StoredValue::new(None::<String>);
+
+    let restore_profile = move || {
+        // `try_`: viene chiamata anche allo smontaggio della pagina
+        if let Some(id) = original_profile.try_get_value().flatten() {
+            let _ = profile_ctx.switch(&id);
+            // Il profilo potrebbe essere già attivo: le regole del torneo vanno scartate comunque
+            settings_ctx.reload();
+            original_profile.try_set_value(None);
+        }
+    };
+
+    // Ogni giocatore gioca sul proprio profilo, creato se non esiste
+    let start_tournament = move |_| {
+        let entries: Vec<String> = names.get().iter().map(|n| n.trim().to_string()).collect();
+        if entries.iter().any(|n| n.is_empty()) {
+            set_error_message.set(Some("Ogni giocatore deve avere un nome.".to_string()));
+            return;
+        }
+        let duplicated = entries
+            .iter()
+            .enumerate()
+            .any(|(i, a)| entries[..i].iter().any(|b| b.eq_ignore_ascii_case(a)));
+        if duplicated {
+            set_error_message.set(Some(
+                "I nomi dei giocatori devono essere diversi.".to_string(),
+            ));
+            return;
+        }
+
+        let profiles = profile_ctx.profiles();
+        let mut players = Vec::new();
+        for name in entries {
+            let existing = profiles.iter().find(|p| p.name.eq_ignore_ascii_case(&name));
+            let profile = match existing {
+                Some(profile) => profile.clone(),
+                None => match profile_ctx.create(&name) {
+                    Ok(profile) => profile,
+                    Err(e) => {
+                        set_error_message.set(Some(e));
+                        return;
+                    }
+                },
+            };
+            players.push(HotSeatPlayer {
+                name: profile.name,
+                profile_id: profile.id,
+            });
+        }
+
+        original_profile.set_value(Some(profile_ctx.active_id()));
+        set_error_message.set(None);
+        set_tournament.set(Some(Tournament::new(
+            mode.get(),
+            TournamentRules::from_settings(&settings_ctx.get_settings()),
+            format.get(),
+            players,
+        )));
+    };
+
+    let start_turn = move |_| {
+        let Some(current) = tournament.get_untracked() else {
+            return;
+        };
+        let Some(turn) = current.current_turn() else {
+            return;
+        };
+        let player = &current.players[turn.player];
+        if let Err(e) = profile_ctx.switch(&player.profile_id) {
+            set_error_message.set(Some(e));
+            return;
+        }
+        // Tutti sulla stessa difficoltà e con gli stessi arricchimenti, altrimenti
+        // il seed darebbe frasi diverse, e con le stesse regole di gioco. Restano
+        // in memoria: le impostazioni salvate del giocatore non cambiano
+        let rules = current.rules;
+        settings_ctx.set_difficulty(rules.difficulty);
+        settings_ctx.set_augmentations(rules.augmentations);
+        settings_ctx.set_error_mode(rules.error_mode);
+        settings_ctx.set_match_rules(rules.match_rules);
+        settings_ctx.set_input_model(rules.input_model);
+        set_turn_done.set(false);
+        set_active_turn.set(Some(turn));
+    };
+
+    let on_finish = Callback::new(move |record: SessionRecord| {
+        let Some(turn) = active_turn.get_untracked() else {
+            return;
+        };
+        set_tournament.update(|t| {
+            if let Some(t) = t {
+                t.record(TurnResult::from_record(turn.player, &record));
+            }
+        });
+        set_turn_done.set(true);
+    });
+
+    let end_turn = move |_| {
+        set_active_turn.set(None);
+        // Il giocatore ritrova le proprie impostazioni
+        settings_ctx.reload();
+        if tournament.with_untracked(|t| t.as_ref().is_some_and(|t| t.is_finished())) {
+            restore_profile();
+        }
+    };
+
+    let close_tournament = move |_| {
+        restore_profile();
+        set_active_turn.set(None);
+        set_tournament.set(None);
+    };
+
+    on_cleanup(restore_profile);
+
+    let next_player_name = move || {
+        tournament.with(|t| {
+            t.as_ref().and_then(|t| {
+                t.current_turn()
+                    .map(|turn| t.players[turn.player].name.clone())
+            })
+        })
+    };
+    let champion_name = move || {
+        tournament.with(|t| {
+            t.as_ref()
+                .and_then(|t| t.champion().map(|c| t.players[c].name.clone()))
+        })
+    };
+
+    view! {
+        <main class="container tournament">
+            <h1 class="tournament__title">
+                "Torneo "
+                <span class="tournament__highlight">"in locale"</span>
+            </h1>
+
+            {move || error_message.get().map(|message| view! {
+                <div class="tournament__feedback">{message}</div>
+            })}
+
+            <Show
+                when=move || tournament.with(|t| t.is_some())
+                fallback=move || view! {
+                    <section class="tournament__setup">
+                        <p class="tournament__hint">
+                            "Da 2 a 8 giocatori si alternano sullo stesso computer sulla stessa sequenza di frasi. Ogni partita viene registrata sul profilo del giocatore."
+                        </p>
+
+                        <div class="tournament__options">
+                            {[GameMode::Rush, GameMode::Marathon, GameMode::Survival]
+                                .into_iter()
+                                .map(|m| view! {
+                                    <button
+                                        class="tournament__option"
+                                        class:tournament__option--active=move || mode.get() == m
+                                        on:click=move |_| set_mode.set(m)
+                                    >
+                                        {m.label()}
+                                    </button>
+                                })
+                                .collect_view()}
+                        </div>
+
+                        <div class="tournament__options">
+                            {TournamentFormat::ALL
+                                .into_iter()
+                                .map(|f| view! {
+                                    <button
+                                        class="tournament__option"
+                                        class:tournament__option--active=move || format.get() == f
+                                        on:click=move |_| set_format.set(f)
+                                    >
+                                        {f.label()}
+                                    </button>
+                                })
+                                .collect_view()}
+                        </div>
+
+                        <div class="tournament__players">
+                            {move || {
+                                let count = names.with(|n| n.len());
+                                (0..count)
+                                    .map(|index| view! {
+                                        <div class="tournament__player">
+                                            <input
+                                                type="text"
+                                                maxlength="24"
+                                                placeholder=format!("Giocatore {}", index + 1)
+                                                prop:value=move || names.with(|n| n.get(index).cloned().unwrap_or_default())
+                                                on:input=move |ev| {
+                                                    let value = event_target_value(&ev);
+                                                    set_names.update(|n| n[index] = value);
+                                                }
+                                            />
+                                            <button
+                                                class="tournament__remove"
+                                                disabled=count <= MIN_PLAYERS
+                                                on:click=move |_| set_names.update(|n| { n.remove(index); })
+                                            >
+                                                "✕"
+                                            </button>
+                                        </div>
+                                    })
+                                    .collect_view()
+                            }}
+                            <Show when=move || names.with(|n| n.len() < MAX_PLAYERS)>
+                                <button
+                                    class="tournament__button"
+                                    on:click=move |_| set_names.update(|n| n.push(String::new()))
+                                >
+                                    "+ Aggiungi giocatore"
+                                </button>
+                            </Show>
+                        </div>
+
+                        <button class="tournament__button tournament__button--primary" on:click=start_tournament>
+                            "Inizia torneo"
+                        </button>
+                    </section>
+                }
+            >
+                {move || match active_turn.get() {
+                    // Partita del giocatore di turno, con le modalità di sempre
+                    Some(turn) => {
+                        let mode = tournament.with_untracked(|t| t.as_ref().map(|t| t.mode));
+                        view! {
+                            <section class="tournament__turn">
+                                <Show when=move || turn_done.get()>
+                                    <button class="tournament__button tournament__button--primary" on:click=end_turn>
+                                        "Continua il torneo"
+                                    </button>
+                                </Show>
+                                {match mode {
+                                    Some(GameMode::Marathon) => view! {
+                                        <MarathonMode seed=turn.seed on_finish=on_finish />
+                                    }
+                                    .into_any(),
+                                    Some(GameMode::Survival) => view! {
+                                        <SurvivalMode seed=turn.seed on_finish=on_finish />
+                                    }
+                                    .into_any(),
+                                    _ => view! { <RushMode seed=turn.seed on_finish=on_finish /> }.into_any(),
+                                }}
+                            </section>
+                        }
+                        .into_any()
+                    }
+                    None => view! {
+                        <section class="tournament__overview">
+                            {move || match (next_player_name(), champion_name()) {
+                                (Some(name), _) => view! {
+                                    <div class="tournament__next">
+                                        <span class="tournament__next-label">"Tocca a"</span>
+                                        <span class="tournament__next-name">{name}</span>
+                                        <button class="tournament__button tournament__button--primary" on:click=start_turn>
+                                            "Inizia il turno"
+                                        </button>
+                                    </div>
+                                }
+                                .into_any(),
+                                (None, Some(name)) => view! {
+                                    <div class="tournament__next">
+                                        <span class="tournament__next-label">"🏆 Vince il torneo"</span>
+                                        <span class="tournament__next-name">{name}</span>
+                                    </div>
+                                }
+                                .into_any(),
+                                (None, None) => ().into_any(),
+                            }}
+                            <TournamentResults tournament=tournament />
+                            <button class="tournament__button" on:click=close_tournament>
+                                {move || if champion_name().is_some() { "Nuovo torneo" } else { "Annulla torneo" }}
+                            </button>
+                        </section>
+                    }
+                    .into_any(),
+                }}
+            </Show>
+        </main>
+    }
+}
@@ -0,0 +1,337 @@
+// src/profile_store.rs
+//
+// Profili giocatore locali: ogni profilo ha impostazioni e statistiche
+// salvate in un proprio namespace di chiavi
+
+use crate::migrations::Schema;
+use crate::settings_store::SettingsContext;
+use crate::stats_store::StatsContext;
+use crate::storage::{
+    SharedBackend, StorageBackend, StorageError, default_backend, load_versioned, save_versioned,
+};
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+const PROFILES_STORAGE_KEY: &str = "litterix_profiles";
+
+const PROFILES_SCHEMA: Schema = Schema {
+    current_version: 1,
+    migrations: &[],
+};
+
+// Il profilo predefinito usa le chiavi storiche, così i dati esistenti restano suoi
+pub const DEFAULT_PROFILE_ID: &str = "default";
+const DEFAULT_PROFILE_NAME: &str = "Giocatore";
+const MAX_NAME_LENGTH: usize = 24;
+
+// Chiave di storage di un dato nel namespace del profilo
+pub fn profile_key(base: &str, profile_id: &str) -> String {
+    if profile_id == DEFAULT_PROFILE_ID {
+        base.to_string()
+    } else {
+        format!("{}@{}", base, profile_id)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileRegistry {
+    pub profiles: Vec<Profile>,
+    pub active: String,
+    // Contatore per generare id univoci anche dopo le eliminazioni
+    pub next_id: u32,
+}
+
+impl Default for ProfileRegistry {
+    fn default() -> Self {
+        Self {
+            profiles: vec![Profile {
+                id: DEFAULT_PROFILE_ID.to_string(),
+                name: DEFAULT_PROFILE_NAME.to_string(),
+            }],
+            active: DEFAULT_PROFILE_ID.to_string(),
+            next_id: 1,
+        }
+    }
+}
+
+impl ProfileRegistry {
+    pub fn load(backend: &dyn StorageBackend) -> Self {
+        let mut registry: Self = load_versioned(backend, PROFILES_STORAGE_KEY, &PROFILES_SCHEMA);
+        if registry.profiles.is_empty() {
+            registry = Self::default();
+        }
+        if registry.find(&registry.active).is_none() {
+            registry.active = registry.profiles[0].id.clone();
+        }
+        registry
+    }
+
+    pub fn save(&self, backend: &dyn StorageBackend) -> Result<(), StorageError> {
+        save_versioned(backend, PROFILES_STORAGE_KEY, &PROFILES_SCHEMA, self)
+    }
+
+    pub fn find(&self, id: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|p| p.id == id)
+    }
+
+    pub fn create(&mut self, name: &str) -> Result<Profile, String> {
+        let name = self.validate_name(name, None)?;
+        let profile = Profile {
+            id: format!("p{}", self.next_id),
+            name,
+        };
+        self.next_id += 1;
+        self.profiles.push(profile.clone());
+        Ok(profile)
+    }
+
+    pub fn rename(&mut self, id: &str, name: &str) -> Result<(), String> {
+        let name = self.validate_name(name, Some(id))?;
+        let profile = self
+            .profiles
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| "Profilo non trovato.".to_string())?;
+        profile.name = name;
+        Ok(())
+    }
+
+    // Toglie il profilo dal registro; ritorna true se era quello attivo
+    pub fn remove(&mut self, id: &str) -> Result<bool, String> {
+        if self.find(id).is_none() {
+            return Err("Profilo non trovato.".to_string());
+        }
+        if self.profiles.len() <= 1 {
+            return Err("Deve rimanere almeno un profilo.".to_string());
+        }
+        self.profiles.retain(|p| p.id != id);
+
+        let was_active = self.active == id;
+        if was_active {
+            self.active = self.profiles[0].id.clone();
+        }
+        Ok(was_active)
+    }
+
+    // Ritorna true se il profilo attivo è cambiato
+    pub fn set_active(&mut self, id: &str) -> Result<bool, String> {
+        if self.find(id).is_none() {
+            return Err("Profilo non trovato.".to_string());
+        }
+        if self.active == id {
+            return Ok(false);
+        }
+        self.active = id.to_string();
+        Ok(true)
+    }
+
+    fn validate_name(&self, name: &str, ignore_id: Option<&str>) -> Result<String, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Il nome non può essere vuoto.".to_string());
+        }
+        if name.chars().count() > MAX_NAME_LENGTH {
+            return Err(format!(
+                "Il nome può avere al massimo {} caratteri.",
+                MAX_NAME_LENGTH
+            ));
+        }
+        let taken = self
+            .profiles
+            .iter()
+            .any(|p| Some(p.id.as_str()) != ignore_id && p.name.eq_ignore_ascii_case(name));
+        if taken {
+            return Err("Esiste già un profilo con questo nome.".to_string());
+        }
+        Ok(name.to_string())
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct ProfileContext {
+    registry: RwSignal<ProfileRegistry>,
+    backend: StoredValue<SharedBackend>,
+    settings: SettingsContext,
+    stats: StatsContext,
+}
+
+impl ProfileContext {
+    // Il registro va caricato prima dei context, che partono dal profilo attivo
+    pub fn new(registry: ProfileRegistry, settings: SettingsContext, stats: StatsContext) -> Self {
+        Self {
+            registry: RwSignal::new(registry),
+            backend: StoredValue::new(default_backend()),
+            settings,
+            stats,
+        }
+    }
+
+    pub fn profiles(&self) -> Vec<Profile> {
+        self.registry.get().profiles
+    }
+
+    pub fn active(&self) -> Profile {
+        let registry = self.registry.get();
+        registry
+            .find(&registry.active)
+            .cloned()
+            .unwrap_or_else(|| registry.profiles[0].clone())
+    }
+
+    pub fn active_id(&self) -> String {
+        self.registry.get().active
+    }
+
+    // Nome del profilo, se esiste ancora
+    pub fn name_of(&self, id: &str) -> Option<String> {
+        self.registry.with(|r| r.find(id).map(|p| p.name.clone()))
+    }
+
+    pub fn create(&self, name: &str) -> Result<Profile, String> {
+        let mut registry = self.registry.get_untracked();
+        let profile = registry.create(name)?;
+        self.commit(registry)?;
+        Ok(profile)
+    }
+
+    pub fn rename(&self, id: &str, name: &str) -> Result<(), String> {
+        let mut registry = self.registry.get_untracked();
+        registry.rename(id, name)?;
+        self.commit(registry)
+    }
+
+    // Elimina il profilo e i suoi dati; se era attivo si passa al primo rimasto
+    pub fn delete(&self, id: &str) -> Result<(), String> {
+        let mut registry = self.registry.get_untracked();
+        let was_active = registry.remove(id)?;
+        self.commit(registry)?;
+
+        if was_active {
+            self.load_active();
+        }
+        self.settings
+            .delete_profile_data(id)
+            .and_then(|_| self.stats.delete_profile_data(id))
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn switch(&self, id: &str) -> Result<(), String> {
+        let mut registry = self.registry.get_untracked();
+        if registry.set_active(id)? {
+            self.commit(registry)?;
+            self.load_active();
+        }
+        Ok(())
+    }
+
+    fn load_active(&self) {
+        let id = self.registry.get_untracked().active;
+        self.settings.switch_profile(&id);
+        self.stats.switch_profile(&id);
+    }
+
+    fn commit(&self, registry: ProfileRegistry) -> Result<(), String> {
+        self.backend
+            .with_value(|b| registry.save(b.as_ref()))
+            .map_err(|e| e.to_string())?;
+        self.registry.set(registry);
+        Ok(())
+    }
+}
+
+pub fn use_profiles() -> ProfileContext {
+    use_context::<ProfileContext>().expect("ProfileContext deve essere fornito a livello di App")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryBackend;
+
+    fn registry_with(names: &[&str]) -> ProfileRegistry {
+        let mut registry = ProfileRegistry::default();
+        for name in names {
+            registry.create(name).unwrap();
+        }
+        registry
+    }
+
+    #[test]
+    fn create_assigns_fresh_ids_and_trims_names() {
+        let mut registry = registry_with(&["Anna"]);
+        let profile = registry.create("  Bruno ").unwrap();
+        assert_eq!(profile.id, "p2");
+        assert_eq!(profile.name, "Bruno");
+
+        // Gli id non vengono riusati dopo un'eliminazione
+        registry.remove("p2").unwrap();
+        assert_eq!(registry.create("Carla").unwrap().id, "p3");
+    }
+
+    #[test]
+    fn create_rejects_invalid_and_duplicate_names() {
+        let mut registry = registry_with(&["Anna"]);
+        assert!(registry.create("   ").is_err());
+        assert!(registry.create(&"x".repeat(MAX_NAME_LENGTH + 1)).is_err());
+        assert!(registry.create("anna").is_err());
+        assert!(registry.create("giocatore").is_err());
+        assert_eq!(registry.profiles.len(), 2);
+    }
+
+    #[test]
+    fn rename_checks_duplicates_but_not_itself() {
+        let mut registry = registry_with(&["Anna", "Bruno"]);
+        assert!(registry.rename("p1", "bruno").is_err());
+        registry.rename("p1", "ANNA").unwrap();
+        assert_eq!(registry.find("p1").unwrap().name, "ANNA");
+        assert!(registry.rename("p9", "Carla").is_err());
+    }
+
+    #[test]
+    fn removing_the_active_profile_activates_the_first_left() {
+        let mut registry = registry_with(&["Anna", "Bruno"]);
+        registry.set_active("p2").unwrap();
+        assert_eq!(registry.remove("p1"), Ok(false));
+        assert_eq!(registry.active, "p2");
+        assert_eq!(registry.remove("p2"), Ok(true));
+        assert_eq!(registry.active, DEFAULT_PROFILE_ID);
+    }
+
+    #[test]
+    fn the_default_profile_can_go_but_not_the_last_one() {
+        let mut registry = registry_with(&["Anna"]);
+        assert_eq!(registry.remove(DEFAULT_PROFILE_ID), Ok(true));
+        assert_eq!(registry.active, "p1");
+        assert!(registry.remove("p1").is_err());
+        assert!(registry.remove(DEFAULT_PROFILE_ID).is_err());
+        assert_eq!(registry.profiles.len(), 1);
+    }
+
+    #[test]
+    fn set_active_reports_changes() {
+        let mut registry = registry_with(&["Anna"]);
+        assert_eq!(registry.set_active(DEFAULT_PROFILE_ID), Ok(false));
+        assert_eq!(registry.set_active("p1"), Ok(true));
+        assert!(registry.set_active("p9").is_err());
+        assert_eq!(registry.active, "p1");
+    }
+
+    #[test]
+    fn load_repairs_a_dangling_active_profile() {
+        let backend = MemoryBackend::default();
+        let mut registry = registry_with(&["Anna"]);
+        registry.active = "p9".to_string();
+        registry.save(&backend).unwrap();
+
+        let loaded = ProfileRegistry::load(&backend);
+        assert_eq!(loaded.active, DEFAULT_PROFILE_ID);
+        assert_eq!(loaded.profiles, registry.profiles);
+    }
+}
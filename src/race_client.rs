@@ -0,0 +1,77 @@
+// src/race_client.rs
+//
+// Connessione WebSocket al race server per le gare multiplayer
+
+use race_protocol::{ClientMessage, DEFAULT_PORT, ServerMessage, WS_PATH};
+use wasm_bindgen::JsCast;
+use wasm_bindgen::JsValue;
+use wasm_bindgen::closure::Closure;
+use web_sys::{Event, MessageEvent, WebSocket, window};
+
+// Server sullo stesso host della pagina: in LAN basta aprire l'app dall'IP di chi ospita
+pub fn default_server_url() -> String {
+    let host = window()
+        .and_then(|w| w.location().hostname().ok())
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "localhost".to_string());
+    format!("ws://{}:{}{}", host, DEFAULT_PORT, WS_PATH)
+}
+
+pub struct RaceConnection {
+    socket: WebSocket,
+    // Le callback vivono quanto la connessione
+    _on_open: Closure<dyn FnMut(Event)>,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_close: Closure<dyn FnMut(Event)>,
+}
+
+impl RaceConnection {
+    // `join` viene inviato appena il socket è aperto
+    pub fn connect(
+        url: &str,
+        join: ClientMessage,
+        on_message: impl Fn(ServerMessage) + 'static,
+        on_close: impl Fn() + 'static,
+    ) -> Result<Self, JsValue> {
+        let socket = WebSocket::new(url)?;
+
+        let join_socket = socket.clone();
+        let on_open = Closure::<dyn FnMut(Event)>::new(move |_: Event| {
+            let _ = send_on(&join_socket, &join);
+        });
+        let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            if let Some(text) = event.data().as_string()
+                && let Ok(message) = serde_json::from_str::<ServerMessage>(&text)
+            {
+                on_message(message);
+            }
+        });
+        let on_close = Closure::<dyn FnMut(Event)>::new(move |_: Event| on_close());
+
+        socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        // Un errore di connessione è sempre seguito dalla chiusura
+        socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+        Ok(Self {
+            socket,
+            _on_open: on_open,
+            _on_message: on_message,
+            _on_close: on_close,
+        })
+    }
+
+    pub fn send(&self, message: &ClientMessage) -> Result<(), JsValue> {
+        send_on(&self.socket, message)
+    }
+
+    pub fn close(&self) {
+        self.socket.set_onclose(None);
+        let _ = self.socket.close();
+    }
+}
+
+fn send_on(socket: &WebSocket, message: &ClientMessage) -> Result<(), JsValue> {
+    let text = serde_json::to_string(message).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    socket.send_with_str(&text)
+}
@@ -0,0 +1,559 @@
+// src/recording.rs
+//
+// Registrazione dei tasti di una partita: replay, e gara contro il fantasma del record personale
+
+use crate::augment::Augmentations;
+use crate::components::typing::engine::{
+    ErrorMode, InputModel, KeyInput, MatchRules, TypingState, grapheme_count, performance_now,
+};
+use crate::migrations::Schema;
+use crate::profile_store::profile_key;
+use crate::settings_store::DatasetDifficulty;
+use crate::stats_store::{GameMode, SessionRecord};
+use crate::storage::{StorageBackend, StorageError, load_versioned, save_versioned};
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const GHOST_STORAGE_KEY: &str = "litterix_ghost";
+const REPLAYS_STORAGE_KEY: &str = "litterix_replays";
+
+const GHOST_SCHEMA: Schema = Schema {
+    current_version: 2,
+    migrations: &[migrate_ghost_v0_to_v1, migrate_ghost_v1_to_v2],
+};
+
+// Nessun fantasma salvato prima dell'envelope versionato
+fn migrate_ghost_v0_to_v1(data: Value) -> Result<Value, String> {
+    Ok(data)
+}
+
+// La v1 conservava un solo fantasma per chiave, la v2 uno per combinazione
+// di regole di confronto e arricchimenti
+fn migrate_ghost_v1_to_v2(data: Value) -> Result<Value, String> {
+    match data {
+        Value::Null => Ok(Value::Array(Vec::new())),
+        ghost @ Value::Object(_) => Ok(Value::Array(vec![ghost])),
+        _ => Err("fantasma non valido".to_string()),
+    }
+}
+
+// Lo stesso envelope è usato per l'archivio locale e per i file esportati
+const REPLAY_SCHEMA: Schema = Schema {
+    current_version: 1,
+    migrations: &[],
+};
+
+// Replay conservati per profilo, dal più recente
+pub const MAX_SAVED_REPLAYS: usize = 20;
+
+const GHOST_DIFFICULTIES: [DatasetDifficulty; 3] = [
+    DatasetDifficulty::Base,
+    DatasetDifficulty::Intermediate,
+    DatasetDifficulty::Advanced,
+];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Keystroke {
+    pub time: f64,     // millisecondi dall'inizio della partita
+    pub phrase: usize, // indice della frase nella sequenza della partita
+    pub input: KeyInput,
+}
+
+// Sequenza di frasi e tasti di una partita, sufficiente a riprodurla
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunRecording {
+    pub phrases: Vec<String>,
+    pub keystrokes: Vec<Keystroke>,
+    // Regole dell'engine durante la partita, per riprodurla fedelmente
+    pub error_mode: ErrorMode,
+    pub match_rules: MatchRules,
+    pub input_model: InputModel,
+}
+
+impl RunRecording {
+    // `phrase` è la posizione nella sequenza della partita, `text` la frase mostrata
+    pub fn record(&mut self, time: f64, phrase: usize, text: &str, input: KeyInput) {
+        while self.phrases.len() <= phrase {
+            self.phrases.push(text.to_string());
+        }
+        self.keystrokes.push(Keystroke {
+            time,
+            phrase,
+            input,
+        });
+    }
+
+    pub fn duration(&self) -> f64 {
+        self.keystrokes.last().map_or(0.0, |k| k.time)
+    }
+
+    // Frase corrente e suo stato dopo i tasti premuti entro l'istante indicato
+    pub fn state_at(&self, time: f64) -> Option<(usize, TypingState)> {
+        let first = self.phrases.first()?;
+        let mut current = (0, self.new_state(first));
+        for keystroke in self.keystrokes.iter().take_while(|k| k.time <= time) {
+            if keystroke.phrase != current.0 {
+                let text = self.phrases.get(keystroke.phrase)?;
+                current = (keystroke.phrase, self.new_state(text));
+            }
+            apply_input(&mut current.1, keystroke);
+        }
+        // A frase completata si mostra già la successiva, come durante la partita
+        if current.1.is_complete
+            && let Some(next) = self.phrases.get(current.0 + 1)
+        {
+            current = (current.0 + 1, self.new_state(next));
+        }
+        Some(current)
+    }
+
+    fn new_state(&self, text: &str) -> TypingState {
+        TypingState::with_rules(
+            text.to_string(),
+            self.error_mode,
+            self.match_rules,
+            self.input_model,
+        )
+    }
+}
+
+fn apply_input(state: &mut TypingState, keystroke: &Keystroke) {
+    match keystroke.input {
+        KeyInput::Char(c) => {
+            state.handle_key_at(&c.to_string(), Some(keystroke.time));
+        }
+        KeyInput::Grapheme(ref grapheme) => {
+            state.handle_key_at(grapheme, Some(keystroke.time));
+        }
+        KeyInput::Backspace => {
+            state.handle_backspace();
+        }
+        KeyInput::DeleteWord => {
+            state.handle_word_backspace();
+        }
+    }
+}
+
+// Registratore usato dalle modalità: il tempo parte dal primo tasto
+#[derive(Clone, Copy)]
+pub struct RunRecorder {
+    recording: StoredValue<RunRecording>,
+    start: RwSignal<Option<f64>>,
+}
+
+impl Default for RunRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RunRecorder {
+    pub fn new() -> Self {
+        Self {
+            recording: StoredValue::new(RunRecording::default()),
+            start: RwSignal::new(None),
+        }
+    }
+
+    pub fn record(&self, phrase: usize, text: &str, input: KeyInput) {
+        let now = performance_now().unwrap_or_default();
+        let start = self.start.get_untracked().unwrap_or_else(|| {
+            self.start.set(Some(now));
+            now
+        });
+        self.recording
+            .update_value(|r| r.record(now - start, phrase, text, input));
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.start.get().is_some()
+    }
+
+    // Millisecondi trascorsi dal primo tasto
+    pub fn elapsed(&self) -> Option<f64> {
+        let start = self.start.get_untracked()?;
+        performance_now().map(|now| now - start)
+    }
+
+    pub fn recording(&self) -> RunRecording {
+        self.recording.get_value()
+    }
+
+    // Le regole dell'engine restano valide anche dopo un reset
+    pub fn set_rules(
+        &self,
+        error_mode: ErrorMode,
+        match_rules: MatchRules,
+        input_model: InputModel,
+    ) {
+        self.recording.update_value(|r| {
+            r.error_mode = error_mode;
+            r.match_rules = match_rules;
+            r.input_model = input_model;
+        });
+    }
+
+    pub fn reset(&self) {
+        self.recording.update_value(|r| {
+            *r = RunRecording {
+                error_mode: r.error_mode,
+                match_rules: r.match_rules,
+                input_model: r.input_model,
+                ..RunRecording::default()
+            }
+        });
+        self.start.set(None);
+    }
+}
+
+// Posizione del fantasma dopo un tasto
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GhostSample {
+    pub time: f64,
+    pub phrase: usize,
+    pub index: usize,
+    // Caratteri percorsi dall'inizio della partita
+    pub progress: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GhostTrack {
+    samples: Vec<GhostSample>,
+}
+
+impl GhostTrack {
+    // Riproduce i tasti registrati con le stesse regole dell'engine
+    pub fn from_recording(recording: &RunRecording) -> Self {
+        let offsets = phrase_offsets(&recording.phrases);
+        let mut states: Vec<TypingState> = recording
+            .phrases
+            .iter()
+            .map(|p| recording.new_state(p))
+            .collect();
+
+        let samples = recording
+            .keystrokes
+            .iter()
+            .filter_map(|k| {
+                let state = states.get_mut(k.phrase)?;
+                apply_input(state, k);
+                Some(GhostSample {
+                    time: k.time,
+                    phrase: k.phrase,
+                    index: state.current_index,
+                    progress: offsets[k.phrase] + state.current_index,
+                })
+            })
+            .collect();
+
+        Self { samples }
+    }
+
+    // Ultima posizione raggiunta entro l'istante indicato
+    pub fn position_at(&self, time: f64) -> Option<GhostSample> {
+        let count = self.samples.partition_point(|s| s.time <= time);
+        count.checked_sub(1).map(|i| self.samples[i])
+    }
+
+    // Primo istante in cui il fantasma ha percorso almeno `progress` caratteri
+    pub fn time_to_reach(&self, progress: usize) -> Option<f64> {
+        self.samples
+            .iter()
+            .find(|s| s.progress >= progress)
+            .map(|s| s.time)
+    }
+}
+
+// Caratteri che precedono ogni frase della sequenza
+pub fn phrase_offsets(phrases: &[String]) -> Vec<usize> {
+    phrases
+        .iter()
+        .scan(0, |total, phrase| {
+            let offset = *total;
+            *total += grapheme_count(phrase);
+            Some(offset)
+        })
+        .collect()
+}
+
+// Vantaggio sul fantasma: positivo se si è avanti
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GhostDelta {
+    pub chars: i64,
+    pub seconds: Option<f64>,
+}
+
+impl GhostDelta {
+    pub fn compute(track: &GhostTrack, progress: usize, elapsed: f64) -> Self {
+        let ghost_progress = track.position_at(elapsed).map_or(0, |s| s.progress);
+        Self {
+            chars: progress as i64 - ghost_progress as i64,
+            // Quanto prima (o dopo) il fantasma era arrivato allo stesso punto
+            seconds: track
+                .time_to_reach(progress)
+                .map(|ghost_time| (ghost_time - elapsed) / 1000.0),
+        }
+    }
+}
+
+// Miglior partita salvata come fantasma per una modalità e difficoltà
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GhostRun {
+    pub score: u32,
+    pub recorded_at: f64,
+    pub recording: RunRecording,
+    // Testo arricchito durante la partita
+    #[serde(default)]
+    pub augmentations: Augmentations,
+}
+
+impl GhostRun {
+    // Si gareggia solo contro partite con le stesse regole e lo stesso testo
+    pub fn is_comparable(&self, match_rules: MatchRules, augmentations: Augmentations) -> bool {
+        self.recording.match_rules == match_rules && self.augmentations == augmentations
+    }
+}
+
+fn ghost_key(profile: &str, mode: GameMode, difficulty: DatasetDifficulty) -> String {
+    let base = format!(
+        "{}_{}_{}",
+        GHOST_STORAGE_KEY,
+        mode.label().to_lowercase(),
+        difficulty.to_str()
+    );
+    profile_key(&base, profile)
+}
+
+pub fn load_ghost(
+    backend: &dyn StorageBackend,
+    profile: &str,
+    mode: GameMode,
+    difficulty: DatasetDifficulty,
+    match_rules: MatchRules,
+    augmentations: Augmentations,
+) -> Option<GhostRun> {
+    load_ghosts(backend, &ghost_key(profile, mode, difficulty))
+        .into_iter()
+        .find(|ghost| ghost.is_comparable(match_rules, augmentations))
+}
+
+fn load_ghosts(backend: &dyn StorageBackend, key: &str) -> Vec<GhostRun> {
+    load_versioned(backend, key, &GHOST_SCHEMA)
+}
+
+pub fn save_ghost(
+    backend: &dyn StorageBackend,
+    profile: &str,
+    mode: GameMode,
+    difficulty: DatasetDifficulty,
+    ghost: &GhostRun,
+) -> Result<(), StorageError> {
+    // Sostituisce solo il fantasma con le stesse regole e arricchimenti
+    let key = ghost_key(profile, mode, difficulty);
+    let mut ghosts = load_ghosts(backend, &key);
+    ghosts.retain(|g| !g.is_comparable(ghost.recording.match_rules, ghost.augmentations));
+    ghosts.push(ghost.clone());
+    save_versioned(backend, &key, &GHOST_SCHEMA, &ghosts)
+}
+
+// Partita registrata con tutto il necessario per riprodurla altrove
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub recorded_at: f64,
+    pub profile_name: String,
+    pub mode: GameMode,
+    // Dataset da cui provengono le frasi
+    pub difficulty: DatasetDifficulty,
+    // Durata configurata della modalità, in secondi (assente per Zen)
+    pub time_limit: Option<f64>,
+    pub wpm: f64,
+    pub accuracy: f64,
+    pub score: Option<u32>,
+    pub recording: RunRecording,
+}
+
+impl Replay {
+    pub fn new(
+        record: &SessionRecord,
+        profile_name: &str,
+        time_limit: Option<f64>,
+        recording: RunRecording,
+    ) -> Self {
+        Self {
+            recorded_at: record.timestamp,
+            profile_name: profile_name.to_string(),
+            mode: record.mode,
+            difficulty: record.difficulty,
+            time_limit,
+            wpm: record.wpm,
+            accuracy: record.accuracy,
+            score: record.score,
+            recording,
+        }
+    }
+}
+
+pub fn load_replays(backend: &dyn StorageBackend, profile: &str) -> Vec<Replay> {
+    load_versioned(
+        backend,
+        &profile_key(REPLAYS_STORAGE_KEY, profile),
+        &REPLAY_SCHEMA,
+    )
+}
+
+// Aggiunge un replay in testa, scartando i più vecchi oltre il limite
+pub fn save_replay(
+    backend: &dyn StorageBackend,
+    profile: &str,
+    replay: Replay,
+) -> Result<(), StorageError> {
+    let mut replays = load_replays(backend, profile);
+    replays.insert(0, replay);
+    replays.truncate(MAX_SAVED_REPLAYS);
+    save_versioned(
+        backend,
+        &profile_key(REPLAYS_STORAGE_KEY, profile),
+        &REPLAY_SCHEMA,
+        &replays,
+    )
+}
+
+pub fn export_replay(replay: &Replay) -> Result<String, String> {
+    REPLAY_SCHEMA.encode(replay)
+}
+
+pub fn parse_replay(raw: &str) -> Result<Replay, String> {
+    let replay = REPLAY_SCHEMA
+        .decode::<Replay>(raw)
+        .map_err(|e| e.to_string())?
+        .value;
+    if replay.recording.phrases.is_empty() {
+        return Err("il replay non contiene frasi".to_string());
+    }
+    let phrases = replay.recording.phrases.len();
+    if replay
+        .recording
+        .keystrokes
+        .iter()
+        .any(|k| k.phrase >= phrases)
+    {
+        return Err("tasti riferiti a frasi inesistenti".to_string());
+    }
+    Ok(replay)
+}
+
+// Elimina fantasmi e replay del profilo
+pub fn clear_recordings(backend: &dyn StorageBackend, profile: &str) -> Result<(), StorageError> {
+    for mode in [
+        GameMode::Zen,
+        GameMode::Rush,
+        GameMode::Marathon,
+        GameMode::Survival,
+        GameMode::Code,
+    ] {
+        for difficulty in GHOST_DIFFICULTIES {
+            backend.remove(&ghost_key(profile, mode, difficulty))?;
+        }
+    }
+    backend.remove(&profile_key(REPLAYS_STORAGE_KEY, profile))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryBackend;
+
+    const PROFILE: &str = "default";
+
+    fn ghost(score: u32, match_rules: MatchRules, augmentations: Augmentations) -> GhostRun {
+        GhostRun {
+            score,
+            recorded_at: 0.0,
+            recording: RunRecording {
+                match_rules,
+                ..RunRecording::default()
+            },
+            augmentations,
+        }
+    }
+
+    fn load(
+        backend: &MemoryBackend,
+        match_rules: MatchRules,
+        augmentations: Augmentations,
+    ) -> Option<u32> {
+        load_ghost(
+            backend,
+            PROFILE,
+            GameMode::Marathon,
+            DatasetDifficulty::Base,
+            match_rules,
+            augmentations,
+        )
+        .map(|g| g.score)
+    }
+
+    fn save(backend: &MemoryBackend, ghost: &GhostRun) {
+        save_ghost(
+            backend,
+            PROFILE,
+            GameMode::Marathon,
+            DatasetDifficulty::Base,
+            ghost,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn ghosts_are_kept_per_rules_and_augmentations() {
+        let backend = MemoryBackend::default();
+        let plain = Augmentations::default();
+        let punctuation = Augmentations {
+            punctuation: true,
+            ..Augmentations::default()
+        };
+        let lenient = MatchRules {
+            ignore_case: true,
+            ..MatchRules::default()
+        };
+
+        save(&backend, &ghost(10, MatchRules::default(), plain));
+        save(&backend, &ghost(20, MatchRules::default(), punctuation));
+        save(&backend, &ghost(30, lenient, plain));
+
+        assert_eq!(load(&backend, MatchRules::default(), plain), Some(10));
+        assert_eq!(load(&backend, MatchRules::default(), punctuation), Some(20));
+        assert_eq!(load(&backend, lenient, plain), Some(30));
+        assert_eq!(load(&backend, lenient, punctuation), None);
+
+        // Un nuovo record sostituisce solo il fantasma comparabile
+        save(&backend, &ghost(15, MatchRules::default(), plain));
+        assert_eq!(load(&backend, MatchRules::default(), plain), Some(15));
+        assert_eq!(load(&backend, MatchRules::default(), punctuation), Some(20));
+    }
+
+    #[test]
+    fn single_v1_ghost_is_migrated_as_plain() {
+        let backend = MemoryBackend::default();
+        let key = ghost_key(PROFILE, GameMode::Marathon, DatasetDifficulty::Base);
+        let v1 = Schema {
+            current_version: 1,
+            migrations: &[migrate_ghost_v0_to_v1],
+        };
+        let legacy = serde_json::json!({
+            "score": 42,
+            "recorded_at": 0.0,
+            "recording": RunRecording::default(),
+        });
+        backend
+            .set(&key, &v1.encode(&Some(legacy)).unwrap())
+            .unwrap();
+
+        assert_eq!(
+            load(&backend, MatchRules::default(), Augmentations::default()),
+            Some(42)
+        );
+    }
+}
@@ -0,0 +1,318 @@
+// src/result_card.rs
+//
+// Scheda del risultato come immagine: SVG generato in Rust, PNG tramite canvas
+
+use crate::augment::Augmentations;
+use crate::calendar::format_date;
+use crate::components::typing::combo_badge;
+use crate::settings_store::DatasetDifficulty;
+use crate::stats_store::{GameMode, SessionRecord};
+use std::fmt::Write;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, HtmlImageElement, window};
+
+// Formato delle anteprime social
+pub const CARD_WIDTH: u32 = 1200;
+pub const CARD_HEIGHT: u32 = 630;
+
+// Stessi colori del tema "Rust Dark" in main.css
+const COLOR_BG: &str = "#1a1a1a";
+const COLOR_BG_SECONDARY: &str = "#141414";
+const COLOR_SURFACE: &str = "#252525";
+const COLOR_TEXT: &str = "#e0e0e0";
+const COLOR_TEXT_MUTED: &str = "#666666";
+const COLOR_PRIMARY: &str = "#f74c00";
+const FONT_MONO: &str = "'Roboto Mono', 'JetBrains Mono', monospace";
+
+// Area del grafico WPM nella scheda
+const CHART_X: f64 = 80.0;
+const CHART_Y: f64 = 400.0;
+const CHART_WIDTH: f64 = 1040.0;
+const CHART_HEIGHT: f64 = 150.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResultCard {
+    pub mode: GameMode,
+    pub difficulty: DatasetDifficulty,
+    pub augmentations: Augmentations,
+    pub wpm: f64,
+    pub accuracy: f64,
+    pub combo: usize,
+    pub score: Option<u32>,
+    pub timestamp: f64,
+    // WPM di ogni frase completata, in ordine
+    pub phrase_wpms: Vec<f64>,
+}
+
+impl ResultCard {
+    pub fn from_record(record: &SessionRecord, phrase_wpms: Vec<f64>) -> Self {
+        Self {
+            mode: record.mode,
+            difficulty: record.difficulty,
+            augmentations: record.augmentations,
+            wpm: record.wpm,
+            accuracy: record.accuracy,
+            combo: record.combo,
+            score: record.score,
+            timestamp: record.timestamp,
+            phrase_wpms,
+        }
+    }
+
+    pub fn to_svg(&self) -> String {
+        self.render_svg(&format_date(self.timestamp))
+    }
+
+    // La data arriva già formattata: il resto della scheda non dipende dal browser
+    fn render_svg(&self, date: &str) -> String {
+        let (emoji, label) = combo_badge(self.combo);
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = CARD_WIDTH,
+            h = CARD_HEIGHT
+        );
+
+        // Sfondo e intestazione, come la barra di navigazione dell'app
+        let _ = write!(
+            svg,
+            r#"<rect width="{w}" height="{h}" fill="{bg}"/><rect width="{w}" height="72" fill="{bg2}"/><line x1="0" y1="72" x2="{w}" y2="72" stroke="{surface}"/>"#,
+            w = CARD_WIDTH,
+            h = CARD_HEIGHT,
+            bg = COLOR_BG,
+            bg2 = COLOR_BG_SECONDARY,
+            surface = COLOR_SURFACE
+        );
+        let _ = write!(
+            svg,
+            r#"<g font-family="{font}"><text x="80" y="46" font-size="26" font-weight="700" fill="{text}">🦀 litterix</text><text x="1120" y="46" font-size="20" text-anchor="end" fill="{muted}">{date}</text></g>"#,
+            font = FONT_MONO,
+            text = COLOR_TEXT,
+            muted = COLOR_TEXT_MUTED,
+        );
+
+        // Modalità, difficoltà e arricchimenti, come nel link condiviso
+        let _ = write!(
+            svg,
+            r#"<g font-family="{font}"><text x="80" y="150" font-size="44" fill="{text}">{mode}</text><text x="80" y="186" font-size="20" fill="{muted}">{difficulty}</text></g>"#,
+            font = FONT_MONO,
+            text = COLOR_TEXT,
+            muted = COLOR_TEXT_MUTED,
+            mode = self.mode.label(),
+            difficulty = self.subtitle()
+        );
+
+        // Statistiche principali
+        let score = self
+            .score
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        let stats = [
+            ("wpm", format!("{:.0}", self.wpm), COLOR_PRIMARY),
+            ("accuracy", format!("{:.1}%", self.accuracy), COLOR_TEXT),
+            ("combo", format!("{} {}", emoji, self.combo), COLOR_TEXT),
+            ("punteggio", score, COLOR_TEXT),
+        ];
+        for (index, (name, value, color)) in stats.iter().enumerate() {
+            let x = 80 + index * 270;
+            let _ = write!(
+                svg,
+                r#"<g font-family="{font}"><text x="{x}" y="290" font-size="64" fill="{color}">{value}</text><text x="{x}" y="325" font-size="20" fill="{muted}">{name}</text></g>"#,
+                font = FONT_MONO,
+                muted = COLOR_TEXT_MUTED,
+            );
+        }
+        let _ = write!(
+            svg,
+            r#"<text x="620" y="352" font-family="{font}" font-size="16" fill="{muted}">{label}</text>"#,
+            font = FONT_MONO,
+            muted = COLOR_TEXT_MUTED,
+        );
+
+        svg.push_str(&self.chart_svg());
+        svg.push_str("</svg>");
+        svg
+    }
+
+    fn subtitle(&self) -> String {
+        if self.augmentations.is_plain() {
+            self.difficulty.to_str().to_string()
+        } else {
+            format!(
+                "{} · {}",
+                self.difficulty.to_str(),
+                self.augmentations.describe()
+            )
+        }
+    }
+
+    // Mini grafico dei WPM frase per frase
+    fn chart_svg(&self) -> String {
+        let mut chart = format!(
+            r#"<rect x="{x}" y="{y}" width="{w}" height="{h}" rx="8" fill="{bg2}"/><text x="{x}" y="{label_y}" font-family="{font}" font-size="16" fill="{muted}">wpm per frase</text>"#,
+            x = CHART_X,
+            y = CHART_Y,
+            w = CHART_WIDTH,
+            h = CHART_HEIGHT,
+            bg2 = COLOR_BG_SECONDARY,
+            label_y = CHART_Y + CHART_HEIGHT + 30.0,
+            font = FONT_MONO,
+            muted = COLOR_TEXT_MUTED,
+        );
+
+        if self.phrase_wpms.len() < 2 {
+            let _ = write!(
+                chart,
+                r#"<text x="{x}" y="{y}" font-family="{font}" font-size="18" text-anchor="middle" fill="{muted}">Frasi insufficienti per il grafico</text>"#,
+                x = CHART_X + CHART_WIDTH / 2.0,
+                y = CHART_Y + CHART_HEIGHT / 2.0 + 6.0,
+                font = FONT_MONO,
+                muted = COLOR_TEXT_MUTED,
+            );
+            return chart;
+        }
+
+        let padding = 16.0;
+        let max_wpm = self.phrase_wpms.iter().copied().fold(1.0, f64::max);
+        let step = (CHART_WIDTH - 2.0 * padding) / (self.phrase_wpms.len() - 1) as f64;
+        let bottom = CHART_Y + CHART_HEIGHT - padding;
+        let points: Vec<String> = self
+            .phrase_wpms
+            .iter()
+            .enumerate()
+            .map(|(i, wpm)| {
+                let x = CHART_X + padding + i as f64 * step;
+                let y = bottom - (wpm / max_wpm) * (CHART_HEIGHT - 2.0 * padding);
+                format!("{:.1},{:.1}", x, y)
+            })
+            .collect();
+
+        let first_x = CHART_X + padding;
+        let last_x = CHART_X + CHART_WIDTH - padding;
+        let _ = write!(
+            chart,
+            r#"<polygon points="{first_x:.1},{bottom:.1} {points} {last_x:.1},{bottom:.1}" fill="{primary}" fill-opacity="0.15"/><polyline points="{points}" fill="none" stroke="{primary}" stroke-width="3" stroke-linejoin="round" stroke-linecap="round"/>"#,
+            points = points.join(" "),
+            primary = COLOR_PRIMARY,
+        );
+        chart
+    }
+}
+
+// Disegna l'SVG su un canvas e ritorna il PNG come data URL
+pub async fn svg_to_png(svg: &str) -> Result<String, JsValue> {
+    let document = window()
+        .and_then(|w| w.document())
+        .ok_or_else(|| JsValue::from_str("document non disponibile"))?;
+
+    let image = HtmlImageElement::new()?;
+    let loaded = js_sys::Promise::new(&mut |resolve, reject| {
+        image.set_onload(Some(&resolve));
+        image.set_onerror(Some(&reject));
+    });
+    image.set_src(&format!(
+        "data:image/svg+xml;charset=utf-8,{}",
+        js_sys::encode_uri_component(svg)
+    ));
+    JsFuture::from(loaded).await?;
+
+    let canvas = document
+        .create_element("canvas")?
+        .dyn_into::<HtmlCanvasElement>()?;
+    canvas.set_width(CARD_WIDTH);
+    canvas.set_height(CARD_HEIGHT);
+    let context = canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("canvas 2d non disponibile"))?
+        .dyn_into::<CanvasRenderingContext2d>()?;
+    context.draw_image_with_html_image_element(&image, 0.0, 0.0)?;
+    canvas.to_data_url_with_type("image/png")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(phrase_wpms: Vec<f64>) -> ResultCard {
+        ResultCard {
+            mode: GameMode::Rush,
+            difficulty: DatasetDifficulty::Base,
+            augmentations: Augmentations::default(),
+            wpm: 62.4,
+            accuracy: 97.5,
+            combo: 12,
+            score: Some(840),
+            timestamp: 0.0,
+            phrase_wpms,
+        }
+    }
+
+    // Punti della polilinea del grafico, come coppie (x, y)
+    fn chart_points(chart: &str) -> Vec<(f64, f64)> {
+        let start = chart.find(r#"<polyline points=""#).unwrap() + r#"<polyline points=""#.len();
+        let end = start + chart[start..].find('"').unwrap();
+        chart[start..end]
+            .split(' ')
+            .map(|point| {
+                let (x, y) = point.split_once(',').unwrap();
+                (x.parse().unwrap(), y.parse().unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn chart_needs_at_least_two_phrases() {
+        for phrase_wpms in [vec![], vec![55.0]] {
+            let chart = card(phrase_wpms).chart_svg();
+            assert!(chart.contains("Frasi insufficienti per il grafico"));
+            assert!(!chart.contains("<polyline"));
+            assert!(!chart.contains("<polygon"));
+        }
+    }
+
+    #[test]
+    fn chart_spans_the_area_and_scales_to_the_best_phrase() {
+        let chart = card(vec![30.0, 60.0, 45.0]).chart_svg();
+        assert!(!chart.contains("Frasi insufficienti"));
+
+        let points = chart_points(&chart);
+        assert_eq!(points.len(), 3);
+        let padding = 16.0;
+        assert_eq!(points[0].0, CHART_X + padding);
+        assert_eq!(points[2].0, CHART_X + CHART_WIDTH - padding);
+        assert_eq!(points[1].0, CHART_X + CHART_WIDTH / 2.0);
+        // La frase più veloce tocca il bordo alto, le altre in proporzione
+        let top = CHART_Y + padding;
+        let bottom = CHART_Y + CHART_HEIGHT - padding;
+        assert_eq!(points[1].1, top);
+        assert_eq!(points[0].1, (bottom + top) / 2.0);
+    }
+
+    #[test]
+    fn svg_shows_the_stats_and_the_date() {
+        for phrase_wpms in [vec![], vec![55.0], vec![40.0, 50.0, 60.0, 70.0]] {
+            let svg = card(phrase_wpms).render_svg("19/10/2026");
+            assert!(svg.starts_with("<svg "));
+            assert!(svg.ends_with("</svg>"));
+            assert_eq!(svg.matches("<svg").count(), 1);
+            for text in ["19/10/2026", "Rush", ">base<", ">62<", ">97.5%<", ">840<"] {
+                assert!(svg.contains(text), "manca {}", text);
+            }
+        }
+    }
+
+    #[test]
+    fn svg_labels_augmented_runs() {
+        let plain = card(vec![]).render_svg("19/10/2026");
+        assert!(!plain.contains("Numeri"));
+
+        let mut augmented = card(vec![]);
+        augmented.augmentations = Augmentations {
+            numbers: true,
+            punctuation: true,
+            ..Augmentations::default()
+        };
+        let svg = augmented.render_svg("19/10/2026");
+        assert!(svg.contains(">base · Numeri, Punteggiatura<"));
+    }
+}
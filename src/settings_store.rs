@@ -1,14 +1,32 @@
 // src/settings_store.rs
 //
-// Gestisce le impostazioni dell'applicazione usando localStorage del browser
+// Gestisce le impostazioni dell'applicazione tramite il backend di storage (localStorage)
 
+use crate::augment::Augmentations;
+use crate::components::typing::engine::{ErrorMode, InputModel, MatchRules};
+use crate::components::typing::typing_text::CaretStyle;
+use crate::migrations::Schema;
+use crate::profile_store::{DEFAULT_PROFILE_ID, profile_key};
+use crate::storage::{
+    SharedBackend, StorageBackend, StorageError, default_backend, load_versioned, save_versioned,
+};
 use leptos::prelude::*;
 use serde::{Deserialize, Serialize};
-use wasm_bindgen::JsValue;
-use web_sys::window;
+use serde_json::Value;
 
 const STORAGE_KEY: &str = "litterix_settings";
 
+// Versione corrente del formato salvato e migrazioni dai formati precedenti
+const SETTINGS_SCHEMA: Schema = Schema {
+    current_version: 1,
+    migrations: &[migrate_settings_v0_to_v1],
+};
+
+// La v0 è il blob legacy senza envelope: i campi coincidono con la v1
+fn migrate_settings_v0_to_v1(data: Value) -> Result<Value, String> {
+    Ok(data)
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum DatasetDifficulty {
     Base,
@@ -25,6 +43,7 @@ impl DatasetDifficulty {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Self {
         match s {
             "intermediate" => Self::Intermediate,
@@ -42,54 +61,104 @@ impl DatasetDifficulty {
     }
 }
 
+// Unità di misura dell'obiettivo giornaliero
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum GoalKind {
+    Minutes,
+    Games,
+    Words,
+}
+
+impl GoalKind {
+    pub const ALL: [GoalKind; 3] = [Self::Minutes, Self::Games, Self::Words];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Minutes => "Minuti",
+            Self::Games => "Partite",
+            Self::Words => "Parole",
+        }
+    }
+
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Self::Minutes => "min",
+            Self::Games => "partite",
+            Self::Words => "parole",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DailyGoal {
+    pub kind: GoalKind,
+    pub target: u32,
+}
+
+impl Default for DailyGoal {
+    fn default() -> Self {
+        Self {
+            kind: GoalKind::Minutes,
+            target: 10,
+        }
+    }
+}
+
+impl DailyGoal {
+    pub fn describe(&self) -> String {
+        format!("{} {}", self.target, self.kind.unit())
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppSettings {
     pub difficulty: DatasetDifficulty,
+    pub daily_goal: DailyGoal,
+    pub error_mode: ErrorMode,
+    pub match_rules: MatchRules,
+    pub input_model: InputModel,
+    pub caret_style: CaretStyle,
+    pub augmentations: Augmentations,
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
             difficulty: DatasetDifficulty::Base,
+            daily_goal: DailyGoal::default(),
+            error_mode: ErrorMode::default(),
+            match_rules: MatchRules::default(),
+            input_model: InputModel::default(),
+            caret_style: CaretStyle::default(),
+            augmentations: Augmentations::default(),
         }
     }
 }
 
 impl AppSettings {
-    // Carica le impostazioni da localStorage
-    pub fn load_from_storage() -> Self {
-        if let Some(window) = window() {
-            if let Ok(Some(storage)) = window.local_storage() {
-                if let Ok(Some(json)) = storage.get_item(STORAGE_KEY) {
-                    if let Ok(settings) = serde_json::from_str::<AppSettings>(&json) {
-                        return settings;
-                    }
-                }
-            }
-        }
-        Self::default()
+    // Carica le impostazioni del profilo, migrando i formati precedenti
+    pub fn load(backend: &dyn StorageBackend, profile: &str) -> Self {
+        load_versioned(
+            backend,
+            &profile_key(STORAGE_KEY, profile),
+            &SETTINGS_SCHEMA,
+        )
     }
 
-    // Salva le impostazioni in localStorage
-    pub fn save_to_storage(&self) -> Result<(), JsValue> {
-        if let Some(window) = window() {
-            if let Ok(Some(storage)) = window.local_storage() {
-                if let Ok(json) = serde_json::to_string(self) {
-                    return storage.set_item(STORAGE_KEY, &json);
-                }
-            }
-        }
-        Ok(())
+    // Salva le impostazioni nel backend
+    pub fn save(&self, backend: &dyn StorageBackend, profile: &str) -> Result<(), StorageError> {
+        save_versioned(
+            backend,
+            &profile_key(STORAGE_KEY, profile),
+            &SETTINGS_SCHEMA,
+            self,
+        )
     }
 
-    // Elimina le impostazioni da localStorage
-    pub fn clear_storage() -> Result<(), JsValue> {
-        if let Some(window) = window() {
-            if let Ok(Some(storage)) = window.local_storage() {
-                return storage.remove_item(STORAGE_KEY);
-            }
-        }
-        Ok(())
+    // Elimina le impostazioni salvate
+    pub fn clear(backend: &dyn StorageBackend, profile: &str) -> Result<(), StorageError> {
+        backend.remove(&profile_key(STORAGE_KEY, profile))
     }
 }
 
@@ -97,17 +166,51 @@ impl AppSettings {
 #[derive(Clone, Copy)]
 pub struct SettingsContext {
     settings: RwSignal<AppSettings>,
+    backend: StoredValue<SharedBackend>,
+    // Profilo a cui appartengono le impostazioni caricate
+    profile: RwSignal<String>,
+}
+
+impl Default for SettingsContext {
+    fn default() -> Self {
+        Self::new(DEFAULT_PROFILE_ID)
+    }
 }
 
 impl SettingsContext {
-    pub fn new() -> Self {
+    pub fn new(profile: &str) -> Self {
+        Self::with_backend(default_backend(), profile)
+    }
+
+    pub fn with_backend(backend: SharedBackend, profile: &str) -> Self {
         // Carica le impostazioni salvate all'avvio
-        let saved_settings = AppSettings::load_from_storage();
+        let saved_settings = AppSettings::load(backend.as_ref(), profile);
         Self {
             settings: RwSignal::new(saved_settings),
+            backend: StoredValue::new(backend),
+            profile: RwSignal::new(profile.to_string()),
         }
     }
 
+    // Ricarica le impostazioni dal namespace di un altro profilo
+    pub fn switch_profile(&self, profile: &str) {
+        let settings = self
+            .backend
+            .with_value(|b| AppSettings::load(b.as_ref(), profile));
+        self.profile.set(profile.to_string());
+        self.settings.set(settings);
+    }
+
+    pub fn delete_profile_data(&self, profile: &str) -> Result<(), StorageError> {
+        self.backend
+            .with_value(|b| AppSettings::clear(b.as_ref(), profile))
+    }
+
+    // Scarta le modifiche non salvate, ricaricando le impostazioni del profilo
+    pub fn reload(&self) {
+        self.switch_profile(&self.profile.get_untracked());
+    }
+
     pub fn get_difficulty(&self) -> DatasetDifficulty {
         self.settings.get().difficulty
     }
@@ -116,19 +219,77 @@ impl SettingsContext {
         self.settings.update(|s| s.difficulty = difficulty);
     }
 
+    pub fn get_daily_goal(&self) -> DailyGoal {
+        self.settings.get().daily_goal
+    }
+
+    pub fn set_daily_goal(&self, goal: DailyGoal) {
+        self.settings.update(|s| s.daily_goal = goal);
+    }
+
+    pub fn get_error_mode(&self) -> ErrorMode {
+        self.settings.get().error_mode
+    }
+
+    pub fn set_error_mode(&self, error_mode: ErrorMode) {
+        self.settings.update(|s| s.error_mode = error_mode);
+    }
+
+    pub fn get_match_rules(&self) -> MatchRules {
+        self.settings.get().match_rules
+    }
+
+    pub fn set_match_rules(&self, match_rules: MatchRules) {
+        self.settings.update(|s| s.match_rules = match_rules);
+    }
+
+    pub fn get_input_model(&self) -> InputModel {
+        self.settings.get().input_model
+    }
+
+    pub fn set_input_model(&self, input_model: InputModel) {
+        self.settings.update(|s| s.input_model = input_model);
+    }
+
+    pub fn get_caret_style(&self) -> CaretStyle {
+        self.settings.get().caret_style
+    }
+
+    pub fn set_caret_style(&self, caret_style: CaretStyle) {
+        self.settings.update(|s| s.caret_style = caret_style);
+    }
+
+    pub fn get_augmentations(&self) -> Augmentations {
+        self.settings.get().augmentations
+    }
+
+    pub fn set_augmentations(&self, augmentations: Augmentations) {
+        self.settings.update(|s| s.augmentations = augmentations);
+    }
+
     pub fn get_settings(&self) -> AppSettings {
         self.settings.get()
     }
 
-    // Salva le impostazioni correnti in localStorage
-    pub fn save(&self) -> Result<(), JsValue> {
+    // Sostituisce e salva tutte le impostazioni (es. ripristino da backup)
+    pub fn replace(&self, settings: AppSettings) -> Result<(), StorageError> {
+        self.settings.set(settings);
+        self.save()
+    }
+
+    // Salva le impostazioni correnti nel backend
+    pub fn save(&self) -> Result<(), StorageError> {
         let settings = self.settings.get();
-        settings.save_to_storage()
+        let profile = self.profile.get_untracked();
+        self.backend
+            .with_value(|b| settings.save(b.as_ref(), &profile))
     }
 
-    // Reset alle impostazioni di default e cancella localStorage
-    pub fn reset(&self) -> Result<(), JsValue> {
-        AppSettings::clear_storage()?;
+    // Reset alle impostazioni di default e cancella i dati salvati
+    pub fn reset(&self) -> Result<(), StorageError> {
+        let profile = self.profile.get_untracked();
+        self.backend
+            .with_value(|b| AppSettings::clear(b.as_ref(), &profile))?;
         self.settings.set(AppSettings::default());
         Ok(())
     }
@@ -0,0 +1,338 @@
+// src/share.rs
+//
+// Link condivisibili dei risultati: riepilogo compatto nel frammento dell'URL, con checksum
+
+use crate::augment::Augmentations;
+use crate::settings_store::DatasetDifficulty;
+use crate::stats_store::{GameMode, SessionRecord};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use rand::RngCore;
+use rand::rngs::OsRng;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::window;
+
+const RESULT_ROUTE: &str = "/result";
+
+const LINK_VERSION: u8 = 1;
+const HEADER_LEN: usize = 4; // versione, modalità, difficoltà, flag
+const CHECKSUM_LEN: usize = 4;
+const FLAG_SEED: u8 = 1;
+// Arricchimenti del testo: i link senza questi bit restano frasi originali
+const FLAG_NUMBERS: u8 = 1 << 1;
+const FLAG_CAPITALS: u8 = 1 << 2;
+const FLAG_PUNCTUATION: u8 = 1 << 3;
+
+// Seed casuale per la sequenza di frasi di una partita
+pub fn random_seed() -> u64 {
+    OsRng.next_u64()
+}
+
+// Riepilogo di una partita Rush, Marathon o Survival, come viaggia nel link
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResultSummary {
+    pub mode: GameMode,
+    pub difficulty: DatasetDifficulty,
+    pub wpm: f64,
+    pub accuracy: f64,
+    pub combo: usize,
+    pub score: u32,
+    pub timestamp: f64,
+    // Presente solo se la sequenza di frasi è riproducibile dal seed
+    pub seed: Option<u64>,
+    pub augmentations: Augmentations,
+}
+
+impl ResultSummary {
+    pub fn from_record(record: &SessionRecord, seed: Option<u64>) -> Self {
+        Self {
+            mode: record.mode,
+            difficulty: record.difficulty,
+            wpm: record.wpm,
+            accuracy: record.accuracy,
+            combo: record.combo,
+            score: record.score.unwrap_or_default(),
+            timestamp: record.timestamp,
+            seed,
+            augmentations: record.augmentations,
+        }
+    }
+
+    // WPM e accuracy con un decimale, data al secondo
+    pub fn encode(&self) -> String {
+        let flags = [
+            (self.seed.is_some(), FLAG_SEED),
+            (self.augmentations.numbers, FLAG_NUMBERS),
+            (self.augmentations.capitals, FLAG_CAPITALS),
+            (self.augmentations.punctuation, FLAG_PUNCTUATION),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .fold(0, |flags, (_, flag)| flags | flag);
+        let mut bytes = vec![
+            LINK_VERSION,
+            mode_code(self.mode),
+            difficulty_code(self.difficulty),
+            flags,
+        ];
+        write_varint(&mut bytes, (self.wpm * 10.0).round() as u64);
+        write_varint(&mut bytes, (self.accuracy * 10.0).round() as u64);
+        write_varint(&mut bytes, self.combo as u64);
+        write_varint(&mut bytes, self.score as u64);
+        write_varint(&mut bytes, (self.timestamp / 1000.0).round() as u64);
+        if let Some(seed) = self.seed {
+            write_varint(&mut bytes, seed);
+        }
+        let checksum = fnv1a(&bytes);
+        bytes.extend_from_slice(&checksum.to_be_bytes());
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    pub fn decode(code: &str) -> Result<Self, String> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(code.trim())
+            .map_err(|_| "codifica non valida".to_string())?;
+        if bytes.len() < HEADER_LEN + CHECKSUM_LEN {
+            return Err("link incompleto".to_string());
+        }
+        let (payload, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+        if fnv1a(payload).to_be_bytes() != checksum {
+            return Err("checksum non valido".to_string());
+        }
+        if payload[0] != LINK_VERSION {
+            return Err(format!("versione {} non supportata", payload[0]));
+        }
+
+        let mode = match payload[1] {
+            1 => GameMode::Rush,
+            2 => GameMode::Marathon,
+            3 => GameMode::Survival,
+            _ => return Err("modalità sconosciuta".to_string()),
+        };
+        let difficulty = match payload[2] {
+            0 => DatasetDifficulty::Base,
+            1 => DatasetDifficulty::Intermediate,
+            2 => DatasetDifficulty::Advanced,
+            _ => return Err("difficoltà sconosciuta".to_string()),
+        };
+
+        let mut fields = payload[HEADER_LEN..].iter().copied();
+        let wpm = read_varint(&mut fields)? as f64 / 10.0;
+        let accuracy = read_varint(&mut fields)? as f64 / 10.0;
+        let combo = read_varint(&mut fields)? as usize;
+        let score = u32::try_from(read_varint(&mut fields)?)
+            .map_err(|_| "punteggio non valido".to_string())?;
+        let timestamp = read_varint(&mut fields)? as f64 * 1000.0;
+        let seed = if payload[3] & FLAG_SEED != 0 {
+            Some(read_varint(&mut fields)?)
+        } else {
+            None
+        };
+        if fields.next().is_some() {
+            return Err("dati in eccesso".to_string());
+        }
+        let augmentations = Augmentations {
+            numbers: payload[3] & FLAG_NUMBERS != 0,
+            capitals: payload[3] & FLAG_CAPITALS != 0,
+            punctuation: payload[3] & FLAG_PUNCTUATION != 0,
+        };
+
+        Ok(Self {
+            mode,
+            difficulty,
+            wpm,
+            accuracy,
+            combo,
+            score,
+            timestamp,
+            seed,
+            augmentations,
+        })
+    }
+
+    pub fn url(&self) -> Option<String> {
+        let origin = window()?.location().origin().ok()?;
+        Some(format!("{}{}#{}", origin, RESULT_ROUTE, self.encode()))
+    }
+
+    // Home con la stessa modalità e sequenza di frasi
+    pub fn play_path(&self) -> Option<String> {
+        let seed = self.seed?;
+        Some(format!(
+            "/?seed={}#{}",
+            seed,
+            self.mode.label().to_lowercase()
+        ))
+    }
+}
+
+fn mode_code(mode: GameMode) -> u8 {
+    match mode {
+        GameMode::Zen => 0,
+        GameMode::Rush => 1,
+        GameMode::Marathon => 2,
+        GameMode::Survival => 3,
+        GameMode::Code => 4,
+    }
+}
+
+fn difficulty_code(difficulty: DatasetDifficulty) -> u8 {
+    match difficulty {
+        DatasetDifficulty::Base => 0,
+        DatasetDifficulty::Intermediate => 1,
+        DatasetDifficulty::Advanced => 2,
+    }
+}
+
+// Interi a lunghezza variabile, 7 bit per byte
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn read_varint(bytes: &mut impl Iterator<Item = u8>) -> Result<u64, String> {
+    let mut value = 0_u64;
+    for shift in (0..64).step_by(7) {
+        let byte = bytes.next().ok_or_else(|| "link incompleto".to_string())?;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("numero non valido".to_string())
+}
+
+// FNV-1a a 32 bit: basta a scartare link troncati o modificati a mano
+fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ u32::from(*byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+pub async fn copy_to_clipboard(text: &str) -> Result<(), JsValue> {
+    let window = window().ok_or_else(|| JsValue::from_str("window non disponibile"))?;
+    JsFuture::from(window.navigator().clipboard().write_text(text)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(seed: Option<u64>) -> ResultSummary {
+        ResultSummary {
+            mode: GameMode::Marathon,
+            difficulty: DatasetDifficulty::Intermediate,
+            wpm: 87.4,
+            accuracy: 96.2,
+            combo: 312,
+            score: 1_234,
+            timestamp: 1_735_689_600_000.0,
+            seed,
+            augmentations: Augmentations::default(),
+        }
+    }
+
+    // Payload grezzo con checksum valido, per costruire link arbitrari
+    fn seal(mut bytes: Vec<u8>) -> String {
+        let checksum = fnv1a(&bytes);
+        bytes.extend_from_slice(&checksum.to_be_bytes());
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    fn header(mode: u8, flags: u8) -> Vec<u8> {
+        let mut bytes = vec![LINK_VERSION, mode, 0, flags];
+        for value in [500, 950, 3, 40, 1_700_000_000] {
+            write_varint(&mut bytes, value);
+        }
+        bytes
+    }
+
+    #[test]
+    fn summary_round_trips() {
+        for seed in [None, Some(0), Some(u64::MAX)] {
+            let original = summary(seed);
+            assert_eq!(ResultSummary::decode(&original.encode()), Ok(original));
+        }
+    }
+
+    #[test]
+    fn augmentation_flags_round_trip() {
+        let mut original = summary(Some(7));
+        original.augmentations = Augmentations {
+            numbers: true,
+            capitals: false,
+            punctuation: true,
+        };
+        assert_eq!(ResultSummary::decode(&original.encode()), Ok(original));
+    }
+
+    #[test]
+    fn links_without_augmentation_bits_decode_as_plain() {
+        let decoded = ResultSummary::decode(&seal(header(1, 0))).unwrap();
+        assert_eq!(decoded.mode, GameMode::Rush);
+        assert_eq!(decoded.seed, None);
+        assert!(decoded.augmentations.is_plain());
+    }
+
+    #[test]
+    fn varint_handles_multi_byte_values() {
+        for value in [0, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+            let mut bytes = Vec::new();
+            write_varint(&mut bytes, value);
+            assert_eq!(read_varint(&mut bytes.into_iter()), Ok(value));
+        }
+    }
+
+    #[test]
+    fn tampered_checksum_is_rejected() {
+        let mut bytes = URL_SAFE_NO_PAD.decode(summary(None).encode()).unwrap();
+        bytes[5] ^= 1;
+        assert_eq!(
+            ResultSummary::decode(&URL_SAFE_NO_PAD.encode(bytes)),
+            Err("checksum non valido".to_string())
+        );
+    }
+
+    #[test]
+    fn unknown_mode_is_rejected() {
+        // Zen e Codice non hanno un risultato condivisibile
+        for mode in [0, 4, 9] {
+            assert_eq!(
+                ResultSummary::decode(&seal(header(mode, 0))),
+                Err("modalità sconosciuta".to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn trailing_bytes_are_rejected() {
+        let mut bytes = header(1, 0);
+        bytes.push(0);
+        assert_eq!(
+            ResultSummary::decode(&seal(bytes)),
+            Err("dati in eccesso".to_string())
+        );
+    }
+
+    #[test]
+    fn missing_seed_is_rejected() {
+        assert_eq!(
+            ResultSummary::decode(&seal(header(1, FLAG_SEED))),
+            Err("link incompleto".to_string())
+        );
+    }
+
+    #[test]
+    fn unsupported_version_and_garbage_are_rejected() {
+        let mut bytes = header(1, 0);
+        bytes[0] = LINK_VERSION + 1;
+        assert!(ResultSummary::decode(&seal(bytes)).is_err());
+        assert!(ResultSummary::decode("non è base64!").is_err());
+        assert!(ResultSummary::decode("").is_err());
+    }
+}
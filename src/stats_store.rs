@@ -2,14 +2,61 @@
 //
 // Sistema di tracking delle statistiche dell'utente
 
+use crate::achievements_store::AchievementsContext;
+use crate::augment::Augmentations;
+use crate::components::typing::engine::MatchRules;
+use crate::migrations::Schema;
+use crate::profile_store::{DEFAULT_PROFILE_ID, profile_key};
+use crate::recording::{Replay, clear_recordings, load_replays, save_replay};
+use crate::settings_store::DatasetDifficulty;
+use crate::storage::{
+    IndexedDbBackend, SharedBackend, StorageBackend, StorageError, default_backend, load_versioned,
+    save_versioned,
+};
 use leptos::prelude::*;
 use serde::{Deserialize, Serialize};
-use wasm_bindgen::JsValue;
-use web_sys::window;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
 
 const STATS_STORAGE_KEY: &str = "litterix_stats";
+const HISTORY_STORAGE_KEY: &str = "litterix_history";
+
+// Database IndexedDB per i dati voluminosi (storico, replay)
+pub const ARCHIVE_DB_NAME: &str = "litterix";
+
+// Versione corrente del formato salvato e migrazioni dai formati precedenti
+const STATS_SCHEMA: Schema = Schema {
+    current_version: 2,
+    migrations: &[migrate_stats_v0_to_v1, migrate_stats_v1_to_v2],
+};
+
+// La v0 è il blob legacy senza envelope: i campi coincidono con la v1
+fn migrate_stats_v0_to_v1(data: Value) -> Result<Value, String> {
+    Ok(data)
+}
+
+// La v2 separa le partite con testo arricchito: quelle della v1 contano tutte
+// come partite normali, base di record e medie
+pub fn migrate_stats_v1_to_v2(mut data: Value) -> Result<Value, String> {
+    let Some(stats) = data.as_object_mut() else {
+        return Err("statistiche non in formato oggetto".to_string());
+    };
+    let games = stats
+        .get("total_games_played")
+        .cloned()
+        .unwrap_or(Value::from(0));
+    stats.insert("plain_games".to_string(), games);
+    Ok(data)
+}
+
+const HISTORY_SCHEMA: Schema = Schema {
+    current_version: 1,
+    migrations: &[],
+};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GameStats {
     // Statistiche generali
     pub total_games_played: u32,
@@ -17,20 +64,29 @@ pub struct GameStats {
     pub total_chars_typed: u32,
     pub total_time_played: f64, // in secondi
 
-    // Record personali
+    // Record personali, solo sulle partite con confronto esatto e senza arricchimenti
     pub best_wpm: f64,
     pub best_accuracy: f64,
     pub highest_combo: usize,
 
-    // Medie
+    // Medie, sulle stesse partite dei record
+    pub plain_games: u32,
     pub average_wpm: f64,
     pub average_accuracy: f64,
 
+    // Record e medie per ogni combinazione di regole e arricchimenti usata
+    pub variants: Vec<VariantStats>,
+
     // Per modalità
     pub zen_games: u32,
     pub rush_games: u32,
     pub marathon_games: u32,
     pub marathon_best_score: u32,
+    pub survival_games: u32,
+    pub survival_best_score: u32,
+    pub code_games: u32,
+    // Precisione per simbolo (parentesi, operatori) nella modalità Codice
+    pub code_symbols: BTreeMap<String, SymbolAccuracy>,
 }
 
 impl Default for GameStats {
@@ -43,138 +99,637 @@ impl Default for GameStats {
             best_wpm: 0.0,
             best_accuracy: 0.0,
             highest_combo: 0,
+            plain_games: 0,
             average_wpm: 0.0,
             average_accuracy: 0.0,
+            variants: Vec::new(),
             zen_games: 0,
             rush_games: 0,
             marathon_games: 0,
             marathon_best_score: 0,
+            survival_games: 0,
+            survival_best_score: 0,
+            code_games: 0,
+            code_symbols: BTreeMap::new(),
         }
     }
 }
 
 impl GameStats {
-    pub fn load_from_storage() -> Self {
-        if let Some(window) = window() {
-            if let Ok(Some(storage)) = window.local_storage() {
-                if let Ok(Some(json)) = storage.get_item(STATS_STORAGE_KEY) {
-                    if let Ok(stats) = serde_json::from_str::<GameStats>(&json) {
-                        return stats;
-                    }
+    pub fn load(backend: &dyn StorageBackend, profile: &str) -> Self {
+        load_versioned(
+            backend,
+            &profile_key(STATS_STORAGE_KEY, profile),
+            &STATS_SCHEMA,
+        )
+    }
+
+    pub fn save(&self, backend: &dyn StorageBackend, profile: &str) -> Result<(), StorageError> {
+        save_versioned(
+            backend,
+            &profile_key(STATS_STORAGE_KEY, profile),
+            &STATS_SCHEMA,
+            self,
+        )
+    }
+
+    pub fn clear(backend: &dyn StorageBackend, profile: &str) -> Result<(), StorageError> {
+        backend.remove(&profile_key(STATS_STORAGE_KEY, profile))
+    }
+
+    pub fn has_played(&self) -> bool {
+        self.total_games_played > 0
+    }
+
+    // Aggiunge una partita conclusa ai totali, ai record e alle medie
+    pub fn apply(&mut self, record: &SessionRecord) {
+        self.total_games_played += 1;
+        self.total_words_typed += record.words;
+        self.total_chars_typed += record.chars;
+        self.total_time_played += record.time;
+
+        if record.combo > self.highest_combo {
+            self.highest_combo = record.combo;
+        }
+
+        // Regole permissive e testo arricchito cambiano la difficoltà: record e medie a parte
+        if record.match_rules.is_strict() && record.augmentations.is_plain() {
+            self.plain_games += 1;
+            self.best_wpm = self.best_wpm.max(record.wpm);
+            self.best_accuracy = self.best_accuracy.max(record.accuracy);
+            self.average_wpm = running_average(self.average_wpm, self.plain_games, record.wpm);
+            self.average_accuracy =
+                running_average(self.average_accuracy, self.plain_games, record.accuracy);
+        } else {
+            self.variant_entry(record.match_rules, record.augmentations)
+                .apply(record);
+        }
+
+        // Aggiorna contatori per modalità
+        match record.mode {
+            GameMode::Zen => self.zen_games += 1,
+            GameMode::Rush => self.rush_games += 1,
+            GameMode::Marathon => {
+                self.marathon_games += 1;
+                if let Some(score) = record.score
+                    && score > self.marathon_best_score
+                {
+                    self.marathon_best_score = score;
                 }
             }
+            GameMode::Survival => {
+                self.survival_games += 1;
+                if let Some(score) = record.score
+                    && score > self.survival_best_score
+                {
+                    self.survival_best_score = score;
+                }
+            }
+            GameMode::Code => self.code_games += 1,
+        }
+
+        for symbol in &record.symbols {
+            self.code_symbols
+                .entry(symbol.symbol.clone())
+                .or_default()
+                .merge(symbol);
         }
-        Self::default()
     }
 
-    pub fn save_to_storage(&self) -> Result<(), JsValue> {
-        if let Some(window) = window() {
-            if let Ok(Some(storage)) = window.local_storage() {
-                if let Ok(json) = serde_json::to_string(self) {
-                    return storage.set_item(STATS_STORAGE_KEY, &json);
-                }
-            }
+    // Porta nei record personali i massimi di un altro archivio
+    pub fn merge_bests(&mut self, other: &GameStats) {
+        self.best_wpm = self.best_wpm.max(other.best_wpm);
+        self.best_accuracy = self.best_accuracy.max(other.best_accuracy);
+        self.highest_combo = self.highest_combo.max(other.highest_combo);
+        self.marathon_best_score = self.marathon_best_score.max(other.marathon_best_score);
+        self.survival_best_score = self.survival_best_score.max(other.survival_best_score);
+        for bucket in &other.variants {
+            let entry = self.variant_entry(bucket.match_rules, bucket.augmentations);
+            entry.best_wpm = entry.best_wpm.max(bucket.best_wpm);
+            entry.best_accuracy = entry.best_accuracy.max(bucket.best_accuracy);
         }
-        Ok(())
     }
 
-    pub fn clear_storage() -> Result<(), JsValue> {
-        if let Some(window) = window() {
-            if let Ok(Some(storage)) = window.local_storage() {
-                return storage.remove_item(STATS_STORAGE_KEY);
+    // Statistiche di una combinazione di regole e arricchimenti
+    pub fn variant_stats(
+        &self,
+        match_rules: MatchRules,
+        augmentations: Augmentations,
+    ) -> Option<&VariantStats> {
+        self.variants
+            .iter()
+            .find(|bucket| bucket.matches(match_rules, augmentations))
+    }
+
+    fn variant_entry(
+        &mut self,
+        match_rules: MatchRules,
+        augmentations: Augmentations,
+    ) -> &mut VariantStats {
+        let index = match self
+            .variants
+            .iter()
+            .position(|bucket| bucket.matches(match_rules, augmentations))
+        {
+            Some(index) => index,
+            None => {
+                self.variants.push(VariantStats {
+                    match_rules,
+                    augmentations,
+                    ..VariantStats::default()
+                });
+                self.variants.len() - 1
             }
+        };
+        &mut self.variants[index]
+    }
+}
+
+// Record e medie delle partite giocate con le stesse regole e gli stessi arricchimenti
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VariantStats {
+    pub match_rules: MatchRules,
+    pub augmentations: Augmentations,
+    pub games: u32,
+    pub best_wpm: f64,
+    pub best_accuracy: f64,
+    pub average_wpm: f64,
+    pub average_accuracy: f64,
+}
+
+impl VariantStats {
+    fn matches(&self, match_rules: MatchRules, augmentations: Augmentations) -> bool {
+        self.match_rules == match_rules && self.augmentations == augmentations
+    }
+
+    // Regole e arricchimenti attivi, per etichettare il gruppo
+    pub fn describe(&self) -> String {
+        [self.match_rules.describe(), self.augmentations.describe()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn apply(&mut self, record: &SessionRecord) {
+        self.games += 1;
+        self.best_wpm = self.best_wpm.max(record.wpm);
+        self.best_accuracy = self.best_accuracy.max(record.accuracy);
+        self.average_wpm = running_average(self.average_wpm, self.games, record.wpm);
+        self.average_accuracy = running_average(self.average_accuracy, self.games, record.accuracy);
+    }
+}
+
+// Media aggiornata con un nuovo valore; `games` include già quello nuovo
+fn running_average(average: f64, games: u32, value: f64) -> f64 {
+    let games = games as f64;
+    (average * (games - 1.0) + value) / games
+}
+
+// Una singola partita conclusa, conservata nello storico
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub timestamp: f64, // millisecondi dall'epoch
+    pub mode: GameMode,
+    pub difficulty: DatasetDifficulty,
+    pub words: u32,
+    pub chars: u32,
+    pub time: f64, // in secondi
+    pub wpm: f64,
+    pub accuracy: f64,
+    pub combo: usize,
+    pub score: Option<u32>,
+    // WPM della frase più veloce e frasi perfette consecutive più lunghe
+    #[serde(default)]
+    pub best_phrase_wpm: f64,
+    #[serde(default)]
+    pub perfect_streak: u32,
+    // Regole di confronto permissive attive durante la partita
+    #[serde(default)]
+    pub match_rules: MatchRules,
+    // Numeri, maiuscole e punteggiatura aggiunti alle frasi
+    #[serde(default)]
+    pub augmentations: Augmentations,
+    // Precisione per simbolo, solo nella modalità Codice
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub symbols: Vec<SymbolAccuracy>,
+}
+
+// Tasti premuti su un simbolo del codice e quanti erano sbagliati
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SymbolAccuracy {
+    pub symbol: String,
+    pub attempts: u32,
+    pub errors: u32,
+}
+
+impl SymbolAccuracy {
+    pub fn accuracy(&self) -> f64 {
+        if self.attempts == 0 {
+            return 100.0;
         }
-        Ok(())
+        100.0 * (self.attempts - self.errors) as f64 / self.attempts as f64
     }
 
-    pub fn has_played(&self) -> bool {
-        self.total_games_played > 0
+    pub fn merge(&mut self, other: &SymbolAccuracy) {
+        self.symbol = other.symbol.clone();
+        self.attempts += other.attempts;
+        self.errors += other.errors;
+    }
+}
+
+// Punteggio di una partita a tempo: parole più un bonus per la combo massima
+pub fn run_score(words: u32, combo: usize) -> u32 {
+    words + (combo as u32 / 5)
+}
+
+// Punteggio Survival: ogni frase superata vale più delle singole parole
+pub fn survival_score(phrases: u32, words: u32, combo: usize) -> u32 {
+    phrases * 10 + run_score(words, combo)
+}
+
+impl SessionRecord {
+    // Due record con stesso istante e modalità sono la stessa partita
+    pub fn is_same_session(&self, other: &SessionRecord) -> bool {
+        self.timestamp == other.timestamp && self.mode == other.mode
     }
 }
 
+fn load_history(backend: &dyn StorageBackend, profile: &str) -> Vec<SessionRecord> {
+    load_versioned(
+        backend,
+        &profile_key(HISTORY_STORAGE_KEY, profile),
+        &HISTORY_SCHEMA,
+    )
+}
+
+fn save_history(
+    backend: &dyn StorageBackend,
+    profile: &str,
+    history: &[SessionRecord],
+) -> Result<(), StorageError> {
+    save_versioned(
+        backend,
+        &profile_key(HISTORY_STORAGE_KEY, profile),
+        &HISTORY_SCHEMA,
+        history,
+    )
+}
+
 #[derive(Clone, Copy)]
 pub struct StatsContext {
     stats: RwSignal<GameStats>,
+    history: RwSignal<Vec<SessionRecord>>,
+    backend: StoredValue<SharedBackend>,
+    // Backend per lo storico; `None` finché IndexedDB non è pronto
+    archive: RwSignal<Option<SharedBackend>>,
+    // Profilo a cui appartengono statistiche e storico caricati
+    profile: RwSignal<String>,
+    // Obiettivi del profilo, valutati a ogni partita registrata
+    achievements: AchievementsContext,
+    // Incrementato a ogni replay salvato, per aggiornare le viste
+    replays_version: RwSignal<u32>,
+}
+
+impl Default for StatsContext {
+    fn default() -> Self {
+        Self::new(DEFAULT_PROFILE_ID)
+    }
 }
 
 impl StatsContext {
-    pub fn new() -> Self {
-        let saved_stats = GameStats::load_from_storage();
+    pub fn new(profile: &str) -> Self {
+        let ctx = Self::with_backend(default_backend(), profile);
+        IndexedDbBackend::open(ARCHIVE_DB_NAME, move |archive| {
+            ctx.attach_archive(Arc::new(archive.clone()));
+        });
+        ctx
+    }
+
+    pub fn with_backend(backend: SharedBackend, profile: &str) -> Self {
+        let saved_stats = GameStats::load(backend.as_ref(), profile);
         Self {
+            achievements: AchievementsContext::with_backend(backend.clone(), profile),
+            replays_version: RwSignal::new(0),
             stats: RwSignal::new(saved_stats),
+            history: RwSignal::new(Vec::new()),
+            backend: StoredValue::new(backend),
+            archive: RwSignal::new(None),
+            profile: RwSignal::new(profile.to_string()),
+        }
+    }
+
+    // Collega il backend dello storico: le partite registrate prima che
+    // fosse pronto vengono unite a quelle già salvate
+    pub fn attach_archive(&self, archive: SharedBackend) {
+        let profile = self.profile.get_untracked();
+        let mut history = load_history(archive.as_ref(), &profile);
+        let pending = self.history.get_untracked();
+        if !pending.is_empty() {
+            history.extend(pending);
+            let _ = save_history(archive.as_ref(), &profile, &history);
+        }
+        self.history.set(history);
+        self.archive.set(Some(archive));
+    }
+
+    // Ricarica statistiche e storico dal namespace di un altro profilo
+    pub fn switch_profile(&self, profile: &str) {
+        let stats = self
+            .backend
+            .with_value(|b| GameStats::load(b.as_ref(), profile));
+        let history = self
+            .archive
+            .get_untracked()
+            .map(|archive| load_history(archive.as_ref(), profile))
+            .unwrap_or_default();
+        self.profile.set(profile.to_string());
+        self.stats.set(stats);
+        self.history.set(history);
+        self.achievements.switch_profile(profile);
+    }
+
+    pub fn delete_profile_data(&self, profile: &str) -> Result<(), StorageError> {
+        self.backend
+            .with_value(|b| GameStats::clear(b.as_ref(), profile))?;
+        if let Some(archive) = self.archive.get_untracked() {
+            archive.remove(&profile_key(HISTORY_STORAGE_KEY, profile))?;
+            clear_recordings(archive.as_ref(), profile)?;
+        }
+        self.achievements.delete_profile_data(profile)
+    }
+
+    // Backend IndexedDB per i dati voluminosi, quando pronto
+    pub fn archive(&self) -> Option<SharedBackend> {
+        self.archive.get()
+    }
+
+    pub fn profile(&self) -> String {
+        self.profile.get()
+    }
+
+    // Replay salvati del profilo, dal più recente
+    pub fn get_replays(&self) -> Vec<Replay> {
+        self.replays_version.track();
+        let profile = self.profile.get();
+        self.archive
+            .get()
+            .map(|archive| load_replays(archive.as_ref(), &profile))
+            .unwrap_or_default()
+    }
+
+    // Archivia il replay di una partita; senza IndexedDB non viene conservato
+    pub fn save_replay(&self, replay: Replay) -> Result<(), StorageError> {
+        if replay.recording.keystrokes.is_empty() {
+            return Ok(());
         }
+        let Some(archive) = self.archive.get_untracked() else {
+            return Ok(());
+        };
+        save_replay(archive.as_ref(), &self.profile.get_untracked(), replay)?;
+        self.replays_version.update(|v| *v += 1);
+        Ok(())
+    }
+
+    pub fn achievements(&self) -> AchievementsContext {
+        self.achievements
     }
 
     pub fn get_stats(&self) -> GameStats {
         self.stats.get()
     }
 
+    pub fn get_history(&self) -> Vec<SessionRecord> {
+        self.history.get()
+    }
+
     // Aggiorna le statistiche dopo una partita
-    pub fn record_game(
+    pub fn record_game(&self, record: SessionRecord) {
+        self.stats.update(|s| s.apply(&record));
+        self.history.update(|h| h.push(record.clone()));
+        self.history.with_untracked(|h| {
+            self.achievements
+                .evaluate(&record, &self.stats.get_untracked(), h)
+        });
+
+        let _ = self.persist_stats();
+        let _ = self.persist_history();
+    }
+
+    // Sostituisce statistiche e storico (es. ripristino da backup). Serve
+    // l'archivio già aperto: altrimenti `attach_archive` riunirebbe lo storico
+    // salvato a quello importato, trasformando la sostituzione in un'unione
+    pub fn replace_all(
         &self,
-        mode: GameMode,
-        words: u32,
-        chars: u32,
-        time: f64,
-        wpm: f64,
-        accuracy: f64,
-        combo: usize,
-        marathon_score: Option<u32>,
-    ) {
-        self.stats.update(|s| {
-            s.total_games_played += 1;
-            s.total_words_typed += words;
-            s.total_chars_typed += chars;
-            s.total_time_played += time;
-
-            // Aggiorna record
-            if wpm > s.best_wpm {
-                s.best_wpm = wpm;
-            }
-            if accuracy > s.best_accuracy {
-                s.best_accuracy = accuracy;
-            }
-            if combo > s.highest_combo {
-                s.highest_combo = combo;
-            }
+        stats: GameStats,
+        history: Vec<SessionRecord>,
+    ) -> Result<(), StorageError> {
+        let Some(archive) = self.archive.get_untracked() else {
+            return Err(StorageError(
+                "archivio dello storico non ancora pronto".into(),
+            ));
+        };
+        // Lo storico salvato viene cancellato, non esteso
+        let profile = self.profile.get_untracked();
+        archive.remove(&profile_key(HISTORY_STORAGE_KEY, &profile))?;
 
-            // Aggiorna medie
-            let total_games = s.total_games_played as f64;
-            s.average_wpm = (s.average_wpm * (total_games - 1.0) + wpm) / total_games;
-            s.average_accuracy = (s.average_accuracy * (total_games - 1.0) + accuracy) / total_games;
-
-            // Aggiorna contatori per modalità
-            match mode {
-                GameMode::Zen => s.zen_games += 1,
-                GameMode::Rush => s.rush_games += 1,
-                GameMode::Marathon => {
-                    s.marathon_games += 1;
-                    if let Some(score) = marathon_score {
-                        if score > s.marathon_best_score {
-                            s.marathon_best_score = score;
-                        }
-                    }
-                }
-            }
-        });
+        self.stats.set(stats);
+        self.history.set(history);
+        self.persist_stats()?;
+        self.persist_history()
+    }
+
+    fn persist_stats(&self) -> Result<(), StorageError> {
+        let stats = self.stats.get_untracked();
+        let profile = self.profile.get_untracked();
+        self.backend
+            .with_value(|b| stats.save(b.as_ref(), &profile))
+    }
 
-        let _ = self.stats.get().save_to_storage();
+    fn persist_history(&self) -> Result<(), StorageError> {
+        match self.archive.get_untracked() {
+            Some(archive) => {
+                let profile = self.profile.get_untracked();
+                self.history
+                    .with_untracked(|h| save_history(archive.as_ref(), &profile, h))
+            }
+            // Lo storico resta in memoria finché l'archivio non è pronto
+            None => Ok(()),
+        }
     }
 
-    pub fn clear(&self) -> Result<(), JsValue> {
-        GameStats::clear_storage()?;
+    pub fn clear(&self) -> Result<(), StorageError> {
+        self.delete_profile_data(&self.profile.get_untracked())?;
         self.stats.set(GameStats::default());
-        Ok(())
+        self.history.set(Vec::new());
+        self.achievements.clear()
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum GameMode {
     Zen,
     Rush,
     Marathon,
+    Survival,
+    Code,
+}
+
+impl GameMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Zen => "Zen",
+            Self::Rush => "Rush",
+            Self::Marathon => "Marathon",
+            Self::Survival => "Survival",
+            Self::Code => "Codice",
+        }
+    }
 }
 
 pub fn use_stats() -> StatsContext {
     use_context::<StatsContext>().expect("StatsContext deve essere fornito a livello di App")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryBackend;
+
+    fn record(wpm: f64, accuracy: f64, augmentations: Augmentations) -> SessionRecord {
+        SessionRecord {
+            timestamp: 1.0,
+            mode: GameMode::Rush,
+            difficulty: DatasetDifficulty::Base,
+            words: 10,
+            chars: 50,
+            time: 20.0,
+            wpm,
+            accuracy,
+            combo: 4,
+            score: None,
+            best_phrase_wpm: wpm,
+            perfect_streak: 0,
+            match_rules: MatchRules::default(),
+            augmentations,
+            symbols: Vec::new(),
+        }
+    }
+
+    fn punctuation() -> Augmentations {
+        Augmentations {
+            punctuation: true,
+            ..Augmentations::default()
+        }
+    }
+
+    #[test]
+    fn augmented_runs_stay_out_of_plain_records_and_averages() {
+        let mut stats = GameStats::default();
+        stats.apply(&record(80.0, 98.0, Augmentations::default()));
+        stats.apply(&record(40.0, 90.0, punctuation()));
+        stats.apply(&record(60.0, 94.0, punctuation()));
+
+        assert_eq!(stats.total_games_played, 3);
+        assert_eq!(stats.plain_games, 1);
+        assert_eq!(stats.best_wpm, 80.0);
+        assert_eq!(stats.average_wpm, 80.0);
+        assert_eq!(stats.average_accuracy, 98.0);
+
+        let bucket = stats
+            .variant_stats(MatchRules::default(), punctuation())
+            .unwrap();
+        assert_eq!(bucket.games, 2);
+        assert_eq!(bucket.best_wpm, 60.0);
+        assert_eq!(bucket.best_accuracy, 94.0);
+        assert_eq!(bucket.average_wpm, 50.0);
+        assert_eq!(bucket.average_accuracy, 92.0);
+    }
+
+    #[test]
+    fn each_augmentation_combination_has_its_own_bucket() {
+        let numbers = Augmentations {
+            numbers: true,
+            ..Augmentations::default()
+        };
+        let mut stats = GameStats::default();
+        stats.apply(&record(40.0, 90.0, punctuation()));
+        stats.apply(&record(55.0, 92.0, numbers));
+
+        assert_eq!(stats.variants.len(), 2);
+        assert_eq!(
+            stats
+                .variant_stats(MatchRules::default(), numbers)
+                .unwrap()
+                .best_wpm,
+            55.0
+        );
+        assert_eq!(
+            stats
+                .variant_stats(MatchRules::default(), punctuation())
+                .unwrap()
+                .best_wpm,
+            40.0
+        );
+    }
+
+    #[test]
+    fn merge_bests_keeps_augmented_records_apart() {
+        let mut stats = GameStats::default();
+        stats.apply(&record(50.0, 95.0, Augmentations::default()));
+        let mut other = GameStats::default();
+        other.apply(&record(90.0, 99.0, punctuation()));
+
+        stats.merge_bests(&other);
+        assert_eq!(stats.best_wpm, 50.0);
+        assert_eq!(
+            stats
+                .variant_stats(MatchRules::default(), punctuation())
+                .unwrap()
+                .best_wpm,
+            90.0
+        );
+    }
+
+    #[test]
+    fn lenient_rule_runs_stay_out_of_plain_records_and_averages() {
+        let lenient = MatchRules {
+            ignore_case: true,
+            ..MatchRules::default()
+        };
+        let mut run = record(120.0, 99.0, Augmentations::default());
+        run.match_rules = lenient;
+
+        let mut stats = GameStats::default();
+        stats.apply(&record(70.0, 96.0, Augmentations::default()));
+        stats.apply(&run);
+
+        assert_eq!(stats.plain_games, 1);
+        assert_eq!(stats.best_wpm, 70.0);
+        assert_eq!(stats.average_wpm, 70.0);
+
+        let bucket = stats
+            .variant_stats(lenient, Augmentations::default())
+            .unwrap();
+        assert_eq!(bucket.games, 1);
+        assert_eq!(bucket.best_wpm, 120.0);
+        assert!(stats.variant_stats(lenient, punctuation()).is_none());
+    }
+
+    #[test]
+    fn v1_stats_count_every_game_as_plain() {
+        let backend = MemoryBackend::default();
+        let legacy =
+            r#"{ "version": 1, "data": { "total_games_played": 4, "average_wpm": 50.0 } }"#;
+        backend
+            .set(&profile_key(STATS_STORAGE_KEY, "p"), legacy)
+            .unwrap();
+
+        let mut stats = GameStats::load(&backend, "p");
+        assert_eq!(stats.plain_games, 4);
+        stats.apply(&record(100.0, 95.0, Augmentations::default()));
+        assert_eq!(stats.average_wpm, 60.0);
+    }
+}
@@ -0,0 +1,498 @@
+// src/storage.rs
+//
+// Backend di persistenza condivisi da impostazioni, statistiche e storico.
+//
+// - `LocalStorageBackend`: dati piccoli e letti all'avvio (impostazioni, stats)
+// - `IndexedDbBackend`: dati voluminosi come storico partite e replay
+// - `MemoryBackend`: fallback quando il browser non offre storage, e per i test nativi
+
+use crate::migrations::{DecodeError, Schema, backup_key};
+use leptos::logging::warn;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{
+    Event, IdbDatabase, IdbObjectStore, IdbOpenDbRequest, IdbTransactionMode, Storage, window,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StorageError(pub String);
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<JsValue> for StorageError {
+    fn from(value: JsValue) -> Self {
+        Self(value.as_string().unwrap_or_else(|| format!("{:?}", value)))
+    }
+}
+
+// Interfaccia chiave/valore sincrona comune a tutti i backend
+pub trait StorageBackend: Send + Sync {
+    fn get(&self, key: &str) -> Option<String>;
+    fn set(&self, key: &str, value: &str) -> Result<(), StorageError>;
+    fn remove(&self, key: &str) -> Result<(), StorageError>;
+}
+
+pub type SharedBackend = Arc<dyn StorageBackend>;
+
+// Backend predefinito per i dati piccoli: localStorage se disponibile, altrimenti memoria
+pub fn default_backend() -> SharedBackend {
+    if LocalStorageBackend::is_available() {
+        Arc::new(LocalStorageBackend)
+    } else {
+        Arc::new(MemoryBackend::default())
+    }
+}
+
+// Carica un valore versionato; i blob illeggibili vengono spostati in una
+// chiave di backup libera, quelli di una versione più recente restano al loro posto
+pub fn load_versioned<T>(backend: &dyn StorageBackend, key: &str, schema: &Schema) -> T
+where
+    T: DeserializeOwned + Serialize + Default,
+{
+    let Some(json) = backend.get(key) else {
+        return T::default();
+    };
+
+    match schema.decode::<T>(&json) {
+        Ok(decoded) => {
+            // Riscrive subito i dati migrati nel formato corrente
+            if decoded.migrated {
+                let _ = save_versioned(backend, key, schema, &decoded.value);
+            }
+            decoded.value
+        }
+        // Dati di una build più nuova: si usano i valori di default senza toccarli
+        Err(DecodeError::FutureVersion(_)) => T::default(),
+        Err(_) => {
+            let backup = (1..)
+                .map(|n| backup_key(key, n))
+                .find(|backup| backend.get(backup).is_none())
+                .expect("esiste sempre una chiave di backup libera");
+            let _ = backend.set(&backup, &json);
+            let _ = backend.remove(key);
+            T::default()
+        }
+    }
+}
+
+pub fn save_versioned<T: Serialize + ?Sized>(
+    backend: &dyn StorageBackend,
+    key: &str,
+    schema: &Schema,
+    value: &T,
+) -> Result<(), StorageError> {
+    let json = schema.encode(value).map_err(StorageError)?;
+    backend.set(key, &json)
+}
+
+// --- localStorage ---
+
+pub struct LocalStorageBackend;
+
+impl LocalStorageBackend {
+    fn storage() -> Option<Storage> {
+        window().and_then(|w| w.local_storage().ok().flatten())
+    }
+
+    pub fn is_available() -> bool {
+        Self::storage().is_some()
+    }
+}
+
+impl StorageBackend for LocalStorageBackend {
+    fn get(&self, key: &str) -> Option<String> {
+        Self::storage()?.get_item(key).ok().flatten()
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), StorageError> {
+        let storage =
+            Self::storage().ok_or_else(|| StorageError("localStorage non disponibile".into()))?;
+        Ok(storage.set_item(key, value)?)
+    }
+
+    fn remove(&self, key: &str) -> Result<(), StorageError> {
+        match Self::storage() {
+            Some(storage) => Ok(storage.remove_item(key)?),
+            None => Ok(()),
+        }
+    }
+}
+
+// --- Memoria ---
+
+#[derive(Clone, Default)]
+pub struct MemoryBackend {
+    entries: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl StorageBackend for MemoryBackend {
+    fn get(&self, key: &str) -> Option<String> {
+        self.entries.lock().ok()?.get(key).cloned()
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), StorageError> {
+        let mut entries = self
+            .entries
+            .lock()
+            .map_err(|_| StorageError("storage in memoria non accessibile".into()))?;
+        entries.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<(), StorageError> {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.remove(key);
+        }
+        Ok(())
+    }
+}
+
+// --- IndexedDB ---
+//
+// IndexedDB è asincrono: il backend tiene una cache in memoria, idratata
+// all'apertura del database, e scrive in background (write-through).
+// Le scritture fatte prima dell'apertura vengono accodate. Se l'apertura
+// fallisce il backend resta una cache in memoria, valida per la sessione.
+
+const IDB_VERSION: u32 = 1;
+const IDB_STORE: &str = "kv";
+
+type PendingOp = Box<dyn FnOnce(&IdbDatabase)>;
+
+type ReadyCallback = Rc<RefCell<Option<Box<dyn FnOnce(&IndexedDbBackend)>>>>;
+
+#[derive(Default)]
+struct IdbConnection {
+    db: Option<IdbDatabase>,
+    pending: Vec<PendingOp>,
+    // Apertura fallita: le scritture restano solo nella cache
+    memory_only: bool,
+}
+
+thread_local! {
+    // Gli handle JS non sono Send: le connessioni restano sul thread principale
+    static IDB_CONNECTIONS: RefCell<HashMap<String, IdbConnection>> = RefCell::new(HashMap::new());
+}
+
+#[derive(Clone)]
+pub struct IndexedDbBackend {
+    name: String,
+    cache: Arc<Mutex<HashMap<String, String>>>,
+    ready: Arc<AtomicBool>,
+}
+
+impl IndexedDbBackend {
+    // Apre il database e ne carica il contenuto in cache; `on_ready` viene
+    // invocato quando i dati salvati sono disponibili
+    pub fn open(name: &str, on_ready: impl FnOnce(&IndexedDbBackend) + 'static) -> Self {
+        let backend = Self {
+            name: name.to_string(),
+            cache: Arc::new(Mutex::new(HashMap::new())),
+            ready: Arc::new(AtomicBool::new(false)),
+        };
+
+        // Invocato una sola volta, qualunque sia l'esito dell'apertura
+        let on_ready: ReadyCallback = Rc::new(RefCell::new(Some(Box::new(on_ready))));
+
+        let factory = window().and_then(|w| w.indexed_db().ok().flatten());
+        let request = factory.and_then(|f| f.open_with_u32(name, IDB_VERSION).ok());
+        let Some(request) = request else {
+            // Senza IndexedDB il backend resta una semplice cache in memoria
+            backend.fall_back_to_memory("IndexedDB non disponibile", &on_ready);
+            return backend;
+        };
+
+        // Navigazione privata, quota esaurita o upgrade bloccato da un'altra scheda
+        let failing = backend.clone();
+        let failed_ready = on_ready.clone();
+        let on_error = move |_: Event| {
+            failing.fall_back_to_memory("apertura di IndexedDB fallita", &failed_ready);
+        };
+        request.set_onerror(Some(Closure::once_into_js(on_error).unchecked_ref()));
+
+        let blocked = backend.clone();
+        let blocked_ready = on_ready.clone();
+        let on_blocked = move |_: Event| {
+            blocked.fall_back_to_memory("apertura di IndexedDB bloccata", &blocked_ready);
+        };
+        request.set_onblocked(Some(Closure::once_into_js(on_blocked).unchecked_ref()));
+
+        let upgrade_request = request.clone();
+        let on_upgrade = move |_: Event| {
+            if let Some(db) = open_result(&upgrade_request) {
+                let _ = db.create_object_store(IDB_STORE);
+            }
+        };
+        request.set_onupgradeneeded(Some(Closure::once_into_js(on_upgrade).unchecked_ref()));
+
+        let success_request = request.clone();
+        let hydrating = backend.clone();
+        let on_success = move |_: Event| {
+            let Some(db) = open_result(&success_request) else {
+                hydrating.fall_back_to_memory("database IndexedDB non valido", &on_ready);
+                return;
+            };
+            // Già ripiegato sulla memoria (es. dopo un blocco): la cache fa fede
+            if hydrating.is_ready() {
+                db.close();
+                return;
+            }
+
+            // Esegue prima le scritture accodate, poi idrata la cache
+            let pending = IDB_CONNECTIONS.with(|conns| {
+                let mut conns = conns.borrow_mut();
+                let conn = conns.entry(hydrating.name.clone()).or_default();
+                conn.db = Some(db.clone());
+                std::mem::take(&mut conn.pending)
+            });
+            for op in pending {
+                op(&db);
+            }
+
+            hydrating.hydrate(&db, move |backend| {
+                if let Some(on_ready) = on_ready.borrow_mut().take() {
+                    on_ready(backend);
+                }
+            });
+        };
+        request.set_onsuccess(Some(Closure::once_into_js(on_success).unchecked_ref()));
+
+        backend
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::SeqCst)
+    }
+
+    // Rinuncia al database: scarta le scritture accodate e segnala il
+    // backend pronto come sola cache in memoria
+    fn fall_back_to_memory(&self, reason: &str, on_ready: &ReadyCallback) {
+        let Some(on_ready) = on_ready.borrow_mut().take() else {
+            return;
+        };
+        warn!("{}: storico e replay restano in memoria", reason);
+        IDB_CONNECTIONS.with(|conns| {
+            let mut conns = conns.borrow_mut();
+            let conn = conns.entry(self.name.clone()).or_default();
+            conn.memory_only = true;
+            conn.pending.clear();
+        });
+        self.ready.store(true, Ordering::SeqCst);
+        on_ready(self);
+    }
+
+    fn hydrate(&self, db: &IdbDatabase, on_ready: impl FnOnce(&IndexedDbBackend) + 'static) {
+        let finish = {
+            let backend = self.clone();
+            move || {
+                backend.ready.store(true, Ordering::SeqCst);
+                on_ready(&backend);
+            }
+        };
+
+        let Some(store) = object_store(db, IdbTransactionMode::Readonly) else {
+            finish();
+            return;
+        };
+        let (Ok(keys_request), Ok(values_request)) = (store.get_all_keys(), store.get_all()) else {
+            finish();
+            return;
+        };
+
+        let cache = self.cache.clone();
+        let on_complete = move |_: Event| {
+            let keys = keys_request.result().map(|v| js_sys::Array::from(&v));
+            let values = values_request.result().map(|v| js_sys::Array::from(&v));
+            if let (Ok(keys), Ok(values)) = (keys, values)
+                && let Ok(mut cache) = cache.lock()
+            {
+                for (key, value) in keys.iter().zip(values.iter()) {
+                    if let (Some(key), Some(value)) = (key.as_string(), value.as_string()) {
+                        // Le scritture fatte durante l'apertura hanno la precedenza
+                        cache.entry(key).or_insert(value);
+                    }
+                }
+            }
+            finish();
+        };
+        store
+            .transaction()
+            .set_oncomplete(Some(Closure::once_into_js(on_complete).unchecked_ref()));
+    }
+
+    fn with_db(&self, op: impl FnOnce(&IdbDatabase) + 'static) {
+        IDB_CONNECTIONS.with(|conns| {
+            let mut conns = conns.borrow_mut();
+            let conn = conns.entry(self.name.clone()).or_default();
+            match conn.db.clone() {
+                Some(db) => op(&db),
+                None if conn.memory_only => {}
+                None => conn.pending.push(Box::new(op)),
+            }
+        });
+    }
+}
+
+fn open_result(request: &IdbOpenDbRequest) -> Option<IdbDatabase> {
+    request.result().ok()?.dyn_into::<IdbDatabase>().ok()
+}
+
+fn object_store(db: &IdbDatabase, mode: IdbTransactionMode) -> Option<IdbObjectStore> {
+    db.transaction_with_str_and_mode(IDB_STORE, mode)
+        .ok()?
+        .object_store(IDB_STORE)
+        .ok()
+}
+
+impl StorageBackend for IndexedDbBackend {
+    fn get(&self, key: &str) -> Option<String> {
+        self.cache.lock().ok()?.get(key).cloned()
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), StorageError> {
+        self.cache
+            .lock()
+            .map_err(|_| StorageError("cache IndexedDB non accessibile".into()))?
+            .insert(key.to_string(), value.to_string());
+
+        let (key, value) = (key.to_string(), value.to_string());
+        self.with_db(move |db| {
+            if let Some(store) = object_store(db, IdbTransactionMode::Readwrite) {
+                let _ = store.put_with_key(&JsValue::from_str(&value), &JsValue::from_str(&key));
+            }
+        });
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<(), StorageError> {
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.remove(key);
+        }
+
+        let key = key.to_string();
+        self.with_db(move |db| {
+            if let Some(store) = object_store(db, IdbTransactionMode::Readwrite) {
+                let _ = store.delete(&JsValue::from_str(&key));
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::{Value, json};
+
+    const KEY: &str = "litterix_test";
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Sample {
+        name: String,
+        count: u32,
+    }
+
+    // La v0 salvava il contatore come stringa
+    fn parse_count(mut data: Value) -> Result<Value, String> {
+        let count = data["count"]
+            .as_str()
+            .and_then(|c| c.parse::<u32>().ok())
+            .ok_or("contatore non valido")?;
+        data["count"] = Value::from(count);
+        Ok(data)
+    }
+
+    const SCHEMA: Schema = Schema {
+        current_version: 1,
+        migrations: &[parse_count],
+    };
+
+    #[test]
+    fn legacy_blob_is_migrated_and_rewritten() {
+        let backend = MemoryBackend::default();
+        backend
+            .set(KEY, &json!({ "name": "ada", "count": "3" }).to_string())
+            .unwrap();
+
+        let loaded: Sample = load_versioned(&backend, KEY, &SCHEMA);
+        assert_eq!(
+            loaded,
+            Sample {
+                name: "ada".to_string(),
+                count: 3
+            }
+        );
+
+        // Il blob riscritto è già nel formato corrente
+        let stored: Value = serde_json::from_str(&backend.get(KEY).unwrap()).unwrap();
+        assert_eq!(stored["version"], json!(1));
+        assert_eq!(load_versioned::<Sample>(&backend, KEY, &SCHEMA), loaded);
+    }
+
+    #[test]
+    fn saved_value_round_trips() {
+        let backend = MemoryBackend::default();
+        let sample = Sample {
+            name: "ada".to_string(),
+            count: 7,
+        };
+        save_versioned(&backend, KEY, &SCHEMA, &sample).unwrap();
+        assert_eq!(load_versioned::<Sample>(&backend, KEY, &SCHEMA), sample);
+    }
+
+    #[test]
+    fn corrupt_blob_is_moved_to_backup() {
+        let backend = MemoryBackend::default();
+        backend.set(KEY, "{ non è json").unwrap();
+
+        let loaded: Sample = load_versioned(&backend, KEY, &SCHEMA);
+        assert_eq!(loaded, Sample::default());
+        assert_eq!(backend.get(KEY), None);
+        assert_eq!(
+            backend.get(&backup_key(KEY, 1)).as_deref(),
+            Some("{ non è json")
+        );
+    }
+
+    #[test]
+    fn later_failures_do_not_overwrite_earlier_backups() {
+        let backend = MemoryBackend::default();
+        let bad_migration = json!({ "name": "ada", "count": "tre" }).to_string();
+        for raw in ["{ non è json", "[1, 2", bad_migration.as_str()] {
+            backend.set(KEY, raw).unwrap();
+            let _: Sample = load_versioned(&backend, KEY, &SCHEMA);
+        }
+
+        assert_eq!(backend.get(KEY), None);
+        assert_eq!(
+            backend.get(&backup_key(KEY, 1)).as_deref(),
+            Some("{ non è json")
+        );
+        assert_eq!(backend.get(&backup_key(KEY, 2)).as_deref(), Some("[1, 2"));
+        assert_eq!(backend.get(&backup_key(KEY, 3)), Some(bad_migration));
+    }
+
+    #[test]
+    fn future_version_blob_is_left_in_place() {
+        let backend = MemoryBackend::default();
+        let raw = json!({ "version": 9, "data": { "name": "ada", "count": 3 } }).to_string();
+        backend.set(KEY, &raw).unwrap();
+
+        let loaded: Sample = load_versioned(&backend, KEY, &SCHEMA);
+        assert_eq!(loaded, Sample::default());
+        assert_eq!(backend.get(KEY), Some(raw));
+        assert_eq!(backend.get(&backup_key(KEY, 1)), None);
+    }
+}
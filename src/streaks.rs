@@ -0,0 +1,92 @@
+// src/streaks.rs
+//
+// Attività giornaliera, obiettivo quotidiano e serie di giorni consecutivi
+
+use crate::calendar::{local_day, streak_ending_at};
+use crate::settings_store::{DailyGoal, GoalKind};
+use crate::stats_store::SessionRecord;
+use std::collections::{BTreeMap, BTreeSet};
+
+// Totali delle partite giocate in un giorno
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DayActivity {
+    pub seconds: f64,
+    pub games: u32,
+    pub words: u32,
+}
+
+impl DayActivity {
+    // Avanzamento nell'unità dell'obiettivo
+    pub fn progress(&self, goal: &DailyGoal) -> u32 {
+        match goal.kind {
+            GoalKind::Minutes => (self.seconds / 60.0).floor() as u32,
+            GoalKind::Games => self.games,
+            GoalKind::Words => self.words,
+        }
+    }
+
+    pub fn meets(&self, goal: &DailyGoal) -> bool {
+        self.progress(goal) >= goal.target.max(1)
+    }
+
+    // Intensità da 0 a 4 per la heatmap, relativa all'obiettivo
+    pub fn level(&self, goal: &DailyGoal) -> u8 {
+        if self.games == 0 {
+            return 0;
+        }
+        let ratio = self.progress(goal) as f64 / goal.target.max(1) as f64;
+        match ratio {
+            r if r >= 2.0 => 4,
+            r if r >= 1.0 => 3,
+            r if r >= 0.5 => 2,
+            _ => 1,
+        }
+    }
+}
+
+pub fn activity_by_day(history: &[SessionRecord]) -> BTreeMap<i64, DayActivity> {
+    let mut days: BTreeMap<i64, DayActivity> = BTreeMap::new();
+    for record in history {
+        let day = days.entry(local_day(record.timestamp)).or_default();
+        day.seconds += record.time;
+        day.games += 1;
+        day.words += record.words;
+    }
+    days
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StreakSummary {
+    pub current: u32,
+    pub best: u32,
+}
+
+// Serie di giorni consecutivi in cui l'obiettivo è stato raggiunto
+pub fn streaks(
+    activity: &BTreeMap<i64, DayActivity>,
+    goal: &DailyGoal,
+    today: i64,
+) -> StreakSummary {
+    let met_days: BTreeSet<i64> = activity
+        .iter()
+        .filter(|(_, a)| a.meets(goal))
+        .map(|(day, _)| *day)
+        .collect();
+
+    // La serie di ieri resta valida finché la giornata di oggi non è finita
+    let current = streak_ending_at(&met_days, today).max(streak_ending_at(&met_days, today - 1));
+
+    let mut best = 0;
+    let mut run = 0;
+    let mut previous: Option<i64> = None;
+    for day in &met_days {
+        run = match previous {
+            Some(p) if p + 1 == *day => run + 1,
+            _ => 1,
+        };
+        best = best.max(run);
+        previous = Some(*day);
+    }
+
+    StreakSummary { current, best }
+}
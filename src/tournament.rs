@@ -0,0 +1,348 @@
+// src/tournament.rs
+//
+// Tornei in locale a turni sullo stesso computer: classifica unica o eliminazione diretta
+
+use crate::augment::Augmentations;
+use crate::components::typing::engine::{ErrorMode, InputModel, MatchRules};
+use crate::settings_store::{AppSettings, DatasetDifficulty};
+use crate::share::random_seed;
+use crate::stats_store::{GameMode, SessionRecord};
+
+pub const MIN_PLAYERS: usize = 2;
+pub const MAX_PLAYERS: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TournamentFormat {
+    // Tutti giocano la stessa partita, vince il punteggio più alto
+    Table,
+    // Sfide a due, chi vince passa al turno successivo
+    Knockout,
+}
+
+impl TournamentFormat {
+    pub const ALL: [TournamentFormat; 2] = [Self::Table, Self::Knockout];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Table => "Classifica",
+            Self::Knockout => "Eliminazione diretta",
+        }
+    }
+}
+
+// Impostazioni imposte a ogni turno: stesse frasi dal seed e stesse regole per tutti
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TournamentRules {
+    pub difficulty: DatasetDifficulty,
+    pub augmentations: Augmentations,
+    pub error_mode: ErrorMode,
+    pub match_rules: MatchRules,
+    pub input_model: InputModel,
+}
+
+impl TournamentRules {
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self {
+            difficulty: settings.difficulty,
+            augmentations: settings.augmentations,
+            error_mode: settings.error_mode,
+            match_rules: settings.match_rules,
+            input_model: settings.input_model,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HotSeatPlayer {
+    pub name: String,
+    // Profilo su cui vengono registrate le partite del giocatore
+    pub profile_id: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TurnResult {
+    pub player: usize,
+    pub score: u32,
+    pub wpm: f64,
+    pub accuracy: f64,
+    pub combo: usize,
+}
+
+impl TurnResult {
+    pub fn from_record(player: usize, record: &SessionRecord) -> Self {
+        Self {
+            player,
+            score: record.score.unwrap_or_default(),
+            wpm: record.wpm,
+            accuracy: record.accuracy,
+            combo: record.combo,
+        }
+    }
+}
+
+// Gruppo di giocatori che affronta la stessa sequenza di frasi
+#[derive(Clone, Debug, PartialEq)]
+pub struct Heat {
+    pub seed: u64,
+    pub players: Vec<usize>,
+    pub results: Vec<TurnResult>,
+}
+
+impl Heat {
+    fn new(players: Vec<usize>) -> Self {
+        Self {
+            seed: random_seed(),
+            players,
+            results: Vec::new(),
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.results.len() >= self.players.len()
+    }
+
+    // Chi passa il turno senza avversario non deve giocare
+    pub fn is_bye(&self) -> bool {
+        self.players.len() == 1
+    }
+
+    fn next_player(&self) -> Option<usize> {
+        self.players.get(self.results.len()).copied()
+    }
+
+    pub fn ranking(&self) -> Vec<TurnResult> {
+        let mut ranking = self.results.clone();
+        // Stesso ordine della classifica locale: punteggio, poi WPM, poi accuracy
+        ranking.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(b.wpm.total_cmp(&a.wpm))
+                .then(b.accuracy.total_cmp(&a.accuracy))
+        });
+        ranking
+    }
+
+    pub fn winner(&self) -> Option<usize> {
+        if self.is_bye() {
+            return self.players.first().copied();
+        }
+        if !self.is_complete() {
+            return None;
+        }
+        self.ranking().first().map(|r| r.player)
+    }
+}
+
+// Turno di gioco: giro, gruppo e giocatore
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Turn {
+    pub round: usize,
+    pub heat: usize,
+    pub player: usize,
+    pub seed: u64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tournament {
+    pub mode: GameMode,
+    pub rules: TournamentRules,
+    pub format: TournamentFormat,
+    pub players: Vec<HotSeatPlayer>,
+    pub rounds: Vec<Vec<Heat>>,
+}
+
+impl Tournament {
+    pub fn new(
+        mode: GameMode,
+        rules: TournamentRules,
+        format: TournamentFormat,
+        players: Vec<HotSeatPlayer>,
+    ) -> Self {
+        let entrants: Vec<usize> = (0..players.len()).collect();
+        let first_round = match format {
+            TournamentFormat::Table => vec![Heat::new(entrants)],
+            TournamentFormat::Knockout => pair_up(&entrants),
+        };
+        Self {
+            mode,
+            rules,
+            format,
+            players,
+            rounds: vec![first_round],
+        }
+    }
+
+    pub fn current_turn(&self) -> Option<Turn> {
+        let round = self.rounds.len() - 1;
+        self.rounds[round].iter().enumerate().find_map(|(heat, h)| {
+            let player = h.next_player()?;
+            (!h.is_bye()).then_some(Turn {
+                round,
+                heat,
+                player,
+                seed: h.seed,
+            })
+        })
+    }
+
+    // Registra la partita del turno corrente e, a giro concluso, prepara il successivo
+    pub fn record(&mut self, result: TurnResult) {
+        let Some(turn) = self.current_turn() else {
+            return;
+        };
+        if turn.player != result.player {
+            return;
+        }
+        self.rounds[turn.round][turn.heat].results.push(result);
+
+        if self.format == TournamentFormat::Knockout && self.current_turn().is_none() {
+            let winners: Vec<usize> = self.rounds[turn.round]
+                .iter()
+                .filter_map(|h| h.winner())
+                .collect();
+            if winners.len() > 1 {
+                self.rounds.push(pair_up(&winners));
+            }
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current_turn().is_none()
+    }
+
+    pub fn champion(&self) -> Option<usize> {
+        if !self.is_finished() {
+            return None;
+        }
+        let last = self.rounds.last()?;
+        match self.format {
+            TournamentFormat::Table => last.first()?.winner(),
+            TournamentFormat::Knockout => (last.len() == 1).then(|| last[0].winner()).flatten(),
+        }
+    }
+}
+
+// Accoppia i giocatori in ordine; con un numero dispari l'ultimo passa il turno
+fn pair_up(players: &[usize]) -> Vec<Heat> {
+    players
+        .chunks(2)
+        .map(|pair| Heat::new(pair.to_vec()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> TournamentRules {
+        TournamentRules::from_settings(&AppSettings::default())
+    }
+
+    fn tournament(format: TournamentFormat, players: usize) -> Tournament {
+        let players = (0..players)
+            .map(|i| HotSeatPlayer {
+                name: format!("Giocatore {}", i + 1),
+                profile_id: format!("p{}", i),
+            })
+            .collect();
+        Tournament::new(GameMode::Rush, rules(), format, players)
+    }
+
+    fn result(player: usize, score: u32, wpm: f64, accuracy: f64) -> TurnResult {
+        TurnResult {
+            player,
+            score,
+            wpm,
+            accuracy,
+            combo: 0,
+        }
+    }
+
+    // Gioca il turno corrente con il punteggio indicato
+    fn play(tournament: &mut Tournament, score: u32) -> usize {
+        let player = tournament.current_turn().expect("turno atteso").player;
+        tournament.record(result(player, score, 50.0, 95.0));
+        player
+    }
+
+    #[test]
+    fn odd_player_count_gives_the_last_one_a_bye() {
+        let heats = pair_up(&[0, 1, 2]);
+        assert_eq!(heats.len(), 2);
+        assert_eq!(heats[0].players, [0, 1]);
+        assert!(heats[1].is_bye());
+        assert_eq!(heats[1].winner(), Some(2));
+    }
+
+    #[test]
+    fn byes_are_not_played() {
+        let mut knockout = tournament(TournamentFormat::Knockout, 3);
+        assert_eq!(play(&mut knockout, 10), 0);
+        assert_eq!(play(&mut knockout, 20), 1);
+        // Il giocatore 2 non gioca il primo giro: si passa alla finale
+        assert_eq!(knockout.rounds.len(), 2);
+        assert_eq!(knockout.rounds[1][0].players, [1, 2]);
+    }
+
+    #[test]
+    fn knockout_winners_advance_to_the_final() {
+        let mut knockout = tournament(TournamentFormat::Knockout, 4);
+        for score in [30, 10, 5, 40] {
+            play(&mut knockout, score);
+        }
+        assert_eq!(knockout.rounds.len(), 2);
+        assert_eq!(knockout.rounds[1][0].players, [0, 3]);
+        assert_eq!(knockout.champion(), None);
+
+        play(&mut knockout, 25);
+        play(&mut knockout, 15);
+        assert!(knockout.is_finished());
+        assert_eq!(knockout.champion(), Some(0));
+    }
+
+    #[test]
+    fn table_ranking_breaks_ties_on_wpm_then_accuracy() {
+        let mut table = tournament(TournamentFormat::Table, 3);
+        table.record(result(0, 20, 60.0, 90.0));
+        table.record(result(1, 20, 60.0, 97.0));
+        table.record(result(2, 20, 70.0, 80.0));
+
+        let order: Vec<usize> = table.rounds[0][0]
+            .ranking()
+            .iter()
+            .map(|r| r.player)
+            .collect();
+        assert_eq!(order, [2, 1, 0]);
+        assert_eq!(table.champion(), Some(2));
+    }
+
+    #[test]
+    fn results_out_of_turn_are_ignored() {
+        let mut table = tournament(TournamentFormat::Table, 2);
+        table.record(result(1, 99, 99.0, 100.0));
+        assert!(table.rounds[0][0].results.is_empty());
+        assert_eq!(table.champion(), None);
+
+        play(&mut table, 10);
+        assert_eq!(table.champion(), None);
+        play(&mut table, 5);
+        assert_eq!(table.champion(), Some(0));
+    }
+
+    #[test]
+    fn rules_are_taken_from_the_organizer_settings() {
+        let settings = AppSettings {
+            error_mode: ErrorMode::SuddenDeath,
+            match_rules: MatchRules {
+                ignore_case: true,
+                ..MatchRules::default()
+            },
+            input_model: InputModel::WordAware,
+            ..AppSettings::default()
+        };
+        let rules = TournamentRules::from_settings(&settings);
+        assert_eq!(rules.error_mode, ErrorMode::SuddenDeath);
+        assert!(rules.match_rules.ignore_case);
+        assert_eq!(rules.input_model, InputModel::WordAware);
+    }
+}